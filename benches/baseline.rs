@@ -0,0 +1,231 @@
+//! Compares the lifo with a `Mutex<Vec>` used as a stack and with the `SegQueue` of crossbeam.
+//!
+//! Every scenario runs with small `u64` values and with 4 KiB values, which the lifo stores inline in its nodes.
+//! A single group runs with `cargo bench --bench baseline -- "round trip"`.
+//! With `--features stats` the counters of the lifo, for example its CAS retries, are printed after every group.
+use atomic_lifo::AtomicLifo;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use crossbeam_queue::SegQueue;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const OPS: u64 = 10_000;
+
+/// Large values are moved fewer times, so producers that outrun the consumers do not fill the memory.
+const LARGE_OPS: u64 = 1_000;
+
+const BURST: u64 = 256;
+
+/// Value that is expensive to move.
+type Large = [u8; 4096];
+
+/// The operations every benchmarked container supports.
+trait Stack<T>: Default + Sync {
+    const NAME: &'static str;
+
+    fn push(&self, value: T);
+
+    fn pop(&self) -> Option<T>;
+
+    fn extend(&self, values: impl Iterator<Item = T>);
+
+    /// Removes all elements and passes them to `f`.
+    fn drain(&self, f: impl FnMut(T));
+
+    /// Prints the internal counters of the container after the group ran.
+    fn report(&self, _group: &str) {}
+}
+
+impl<T: Send + Sync + 'static> Stack<T> for AtomicLifo<T> {
+    const NAME: &'static str = "AtomicLifo";
+
+    fn push(&self, value: T) {
+        Self::push(self, value);
+    }
+
+    fn pop(&self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    fn extend(&self, values: impl Iterator<Item = T>) {
+        Self::extend(self, values);
+    }
+
+    fn drain(&self, f: impl FnMut(T)) {
+        self.take_all().for_each(f);
+    }
+
+    #[cfg(feature = "stats")]
+    fn report(&self, group: &str) {
+        //Groups that were filtered out did not push anything.
+        let stats = self.stats();
+        if stats.pushes != 0 {
+            eprintln!("{group}, {}: {stats:?}", Self::NAME);
+        }
+    }
+}
+
+impl<T: Send> Stack<T> for Mutex<Vec<T>> {
+    const NAME: &'static str = "Mutex<Vec>";
+
+    fn push(&self, value: T) {
+        self.lock().unwrap().push(value);
+    }
+
+    fn pop(&self) -> Option<T> {
+        self.lock().unwrap().pop()
+    }
+
+    fn extend(&self, values: impl Iterator<Item = T>) {
+        self.lock().unwrap().extend(values);
+    }
+
+    fn drain(&self, f: impl FnMut(T)) {
+        let values = std::mem::take(&mut *self.lock().unwrap());
+        values.into_iter().rev().for_each(f);
+    }
+}
+
+impl<T: Send> Stack<T> for SegQueue<T> {
+    const NAME: &'static str = "SegQueue";
+
+    fn push(&self, value: T) {
+        Self::push(self, value);
+    }
+
+    fn pop(&self) -> Option<T> {
+        Self::pop(self)
+    }
+
+    fn extend(&self, values: impl Iterator<Item = T>) {
+        values.for_each(|value| Self::push(self, value));
+    }
+
+    fn drain(&self, mut f: impl FnMut(T)) {
+        while let Some(value) = Self::pop(self) {
+            f(value);
+        }
+    }
+}
+
+/// Splits the threads into producers and consumers that move `OPS` values, or `LARGE_OPS` large values, per producer.
+/// A single thread first pushes and then pops all values.
+fn produce_consume<T: Send, S: Stack<T>>(
+    stack: &S,
+    threads: usize,
+    value: impl Fn() -> T + Sync,
+) -> Duration {
+    let ops = if size_of::<T>() > size_of::<u64>() {
+        LARGE_OPS
+    } else {
+        OPS
+    };
+    let producers = threads.div_ceil(2);
+    let consumers = threads / 2;
+    let remaining = AtomicU64::new(ops * producers as u64);
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..producers {
+            scope.spawn(|| {
+                for _ in 0..ops {
+                    stack.push(value());
+                }
+            });
+        }
+        for _ in 0..consumers {
+            scope.spawn(|| {
+                while remaining.load(Relaxed) != 0 {
+                    if let Some(value) = stack.pop() {
+                        black_box(value);
+                        remaining.fetch_sub(1, Relaxed);
+                    }
+                }
+            });
+        }
+    });
+    stack.drain(|value| {
+        black_box(value);
+    });
+    start.elapsed()
+}
+
+fn bench_round_trip<T, S: Stack<T>>(c: &mut Criterion, payload: &str, value: impl Fn() -> T) {
+    let stack = S::default();
+    let group = format!("round trip of {payload}");
+    c.benchmark_group(&group).bench_function(S::NAME, |b| {
+        b.iter(|| {
+            stack.push(value());
+            black_box(stack.pop());
+        });
+    });
+    stack.report(&group);
+}
+
+fn bench_burst<T, S: Stack<T>>(c: &mut Criterion, payload: &str, value: impl Fn() -> T) {
+    let stack = S::default();
+    let group = format!("burst of {BURST} {payload} then drain");
+    c.benchmark_group(&group).bench_function(S::NAME, |b| {
+        b.iter(|| {
+            stack.extend((0..BURST).map(|_| value()));
+            stack.drain(|value| {
+                black_box(value);
+            });
+        });
+    });
+    stack.report(&group);
+}
+
+fn bench_threads<T: Send, S: Stack<T>>(
+    c: &mut Criterion,
+    payload: &str,
+    value: impl Fn() -> T + Sync,
+) {
+    let stack = S::default();
+    let name = format!("producers and consumers of {payload}");
+    let mut group = c.benchmark_group(&name);
+    for threads in [1, 2, 4, 8, 16] {
+        group.bench_with_input(
+            BenchmarkId::new(S::NAME, threads),
+            &threads,
+            |b, &threads| {
+                b.iter_custom(|iters| {
+                    (0..iters)
+                        .map(|_| produce_consume(&stack, threads, &value))
+                        .sum()
+                });
+            },
+        );
+    }
+    group.finish();
+    stack.report(&name);
+}
+
+fn bench_all<T: Send + Sync + 'static>(
+    c: &mut Criterion,
+    payload: &str,
+    value: impl Fn() -> T + Sync + Copy,
+) {
+    bench_round_trip::<T, AtomicLifo<T>>(c, payload, value);
+    bench_round_trip::<T, Mutex<Vec<T>>>(c, payload, value);
+    bench_round_trip::<T, SegQueue<T>>(c, payload, value);
+    bench_burst::<T, AtomicLifo<T>>(c, payload, value);
+    bench_burst::<T, Mutex<Vec<T>>>(c, payload, value);
+    bench_burst::<T, SegQueue<T>>(c, payload, value);
+    bench_threads::<T, AtomicLifo<T>>(c, payload, value);
+    bench_threads::<T, Mutex<Vec<T>>>(c, payload, value);
+    bench_threads::<T, SegQueue<T>>(c, payload, value);
+}
+
+fn bench_small(c: &mut Criterion) {
+    bench_all(c, "u64", || black_box(1u64));
+}
+
+fn bench_large(c: &mut Criterion) {
+    bench_all::<Large>(c, "4 KiB", || black_box([1; 4096]));
+}
+
+criterion_group!(benches, bench_small, bench_large);
+criterion_main!(benches);
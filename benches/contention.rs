@@ -0,0 +1,104 @@
+use atomic_lifo::AtomicLifo;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const OPS: u64 = 10_000;
+
+fn push_heavy(lifo: &AtomicLifo<u64>, threads: usize) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads - 1 {
+            scope.spawn(|| {
+                for i in 0..OPS {
+                    lifo.push(i);
+                }
+            });
+        }
+        scope.spawn(|| {
+            for _ in 0..OPS {
+                black_box(lifo.pop());
+            }
+        });
+    });
+    let elapsed = start.elapsed();
+    lifo.clear();
+    elapsed
+}
+
+fn pop_heavy(lifo: &AtomicLifo<u64>, threads: usize) -> Duration {
+    lifo.extend(0..OPS * (threads as u64 - 1));
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads - 1 {
+            scope.spawn(|| {
+                for _ in 0..OPS {
+                    black_box(lifo.pop());
+                }
+            });
+        }
+        scope.spawn(|| {
+            for i in 0..OPS {
+                lifo.push(i);
+            }
+        });
+    });
+    let elapsed = start.elapsed();
+    lifo.clear();
+    elapsed
+}
+
+//Every thread pushes and pops in turns, the load an elimination array absorbs.
+fn pairs(lifo: &AtomicLifo<u64>, threads: usize) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for i in 0..OPS {
+                    lifo.push(i);
+                    black_box(lifo.pop());
+                }
+            });
+        }
+    });
+    let elapsed = start.elapsed();
+    lifo.clear();
+    elapsed
+}
+
+fn bench_contention(c: &mut Criterion) {
+    let lifos = [
+        ("", AtomicLifo::new()),
+        (", elimination", AtomicLifo::new_with_elimination::<8>()),
+    ];
+    let mut group = c.benchmark_group("threads contending on the head");
+    for threads in [2, 8, 16, 32] {
+        for (name, lifo) in &lifos {
+            group.bench_with_input(
+                BenchmarkId::new(format!("push heavy{name}"), threads),
+                &threads,
+                |b, &threads| {
+                    b.iter_custom(|iters| (0..iters).map(|_| push_heavy(lifo, threads)).sum());
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("pop heavy{name}"), threads),
+                &threads,
+                |b, &threads| {
+                    b.iter_custom(|iters| (0..iters).map(|_| pop_heavy(lifo, threads)).sum());
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("pairs{name}"), threads),
+                &threads,
+                |b, &threads| {
+                    b.iter_custom(|iters| (0..iters).map(|_| pairs(lifo, threads)).sum());
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_contention);
+criterion_main!(benches);
@@ -0,0 +1,94 @@
+//! Compares the head with and without the `dwcas` feature.
+//!
+//! Run once with `cargo bench --bench dwcas` and once with `cargo bench --bench dwcas --features dwcas`.
+//! Besides the throughput this prints the peak amount of live allocations while threads push and pop,
+//! which are mostly popped nodes that wait in the hazard list to be freed.
+use atomic_lifo::AtomicLifo;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::thread;
+use std::time::{Duration, Instant};
+
+struct CountingAllocator;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE.fetch_add(1, Relaxed) + 1;
+        PEAK.fetch_max(live, Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(1, Relaxed);
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const OPS: u64 = 10_000;
+
+fn push_pop(lifo: &AtomicLifo<u64>, threads: usize) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for i in 0..OPS {
+                    lifo.push(i);
+                    black_box(lifo.pop());
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn peak_live_allocations(threads: usize) -> usize {
+    let lifo = AtomicLifo::new();
+    //Spawn the threads once so their own allocations are not counted.
+    push_pop(&lifo, threads);
+    let live = LIVE.load(Relaxed);
+    PEAK.store(live, Relaxed);
+    push_pop(&lifo, threads);
+    PEAK.load(Relaxed) - live
+}
+
+fn bench_dwcas(c: &mut Criterion) {
+    let head = if cfg!(feature = "dwcas") {
+        "tagged head"
+    } else {
+        "plain head"
+    };
+
+    for threads in [2, 8] {
+        println!(
+            "peak live allocations with {threads} threads and a {head}: {}",
+            peak_live_allocations(threads)
+        );
+    }
+
+    let mut group = c.benchmark_group(format!("push and pop with a {head}"));
+    for threads in [2, 8] {
+        group.bench_with_input(
+            BenchmarkId::new("threads", threads),
+            &threads,
+            |b, &threads| {
+                b.iter_custom(|iters| {
+                    (0..iters)
+                        .map(|_| push_pop(&AtomicLifo::new(), threads))
+                        .sum()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_dwcas);
+criterion_main!(benches);
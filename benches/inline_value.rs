@@ -0,0 +1,24 @@
+use atomic_lifo::AtomicLifo;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn cycle<T: Send + Sync>(lifo: &AtomicLifo<T>, value: impl Fn(u64) -> T) {
+    for i in 0..100u64 {
+        lifo.push(value(i));
+    }
+    while let Some(value) = lifo.pop() {
+        black_box(value);
+    }
+}
+
+fn bench_inline_value(c: &mut Criterion) {
+    let inline = AtomicLifo::new();
+    let boxed = AtomicLifo::new();
+
+    let mut group = c.benchmark_group("push and pop 100 u64");
+    group.bench_function("inline", |b| b.iter(|| cycle(&inline, |i| i)));
+    group.bench_function("boxed", |b| b.iter(|| cycle(&boxed, Box::new)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_inline_value);
+criterion_main!(benches);
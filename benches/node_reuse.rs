@@ -0,0 +1,62 @@
+use atomic_lifo::AtomicLifo;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn cycle(lifo: &AtomicLifo<u64>) {
+    for i in 0..100u64 {
+        lifo.push(i);
+    }
+    while let Some(value) = lifo.pop() {
+        black_box(value);
+    }
+}
+
+fn allocations_per_push(mut f: impl FnMut()) -> f64 {
+    let before = ALLOCATIONS.load(Relaxed);
+    for _ in 0..100 {
+        f();
+    }
+
+    (ALLOCATIONS.load(Relaxed) - before) as f64 / 10_000.0
+}
+
+fn bench_node_reuse(c: &mut Criterion) {
+    let reused = AtomicLifo::new();
+    cycle(&reused);
+    println!(
+        "allocations per push and pop with reused nodes: {}",
+        allocations_per_push(|| cycle(&reused))
+    );
+    println!(
+        "allocations per push and pop without reused nodes: {}",
+        allocations_per_push(|| cycle(&AtomicLifo::new()))
+    );
+
+    let mut group = c.benchmark_group("push and pop 100 elements");
+    group.bench_function("reused nodes", |b| b.iter(|| cycle(&reused)));
+    group.bench_function("new lifo", |b| b.iter(|| cycle(&AtomicLifo::new())));
+    group.finish();
+}
+
+criterion_group!(benches, bench_node_reuse);
+criterion_main!(benches);
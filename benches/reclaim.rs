@@ -0,0 +1,62 @@
+//! Compares the strategies that free popped nodes.
+//!
+//! `EpochReclaim` is only benchmarked with the `epoch` feature, for example `cargo bench --bench reclaim --features epoch`.
+#[cfg(feature = "epoch")]
+use atomic_lifo::EpochReclaim;
+use atomic_lifo::{AtomicLifo, Global, HazardReclaim, LeakReclaim, Reclaim};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const OPS: u64 = 10_000;
+
+fn push_pop<R: Reclaim>(lifo: &AtomicLifo<u64, Global, R>, threads: usize) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for i in 0..OPS {
+                    lifo.push(i);
+                    black_box(lifo.pop());
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn bench_strategy<R: Reclaim + Copy>(c: &mut Criterion, name: &str, reclaim: R) {
+    let mut group = c.benchmark_group(format!("push and pop with {name} reclamation"));
+    let lifo = AtomicLifo::with_reclaim(reclaim);
+    group.bench_function("single thread", |b| {
+        b.iter(|| {
+            lifo.push(1);
+            black_box(lifo.pop());
+        });
+    });
+    for threads in [2, 8] {
+        group.bench_with_input(
+            BenchmarkId::new("threads", threads),
+            &threads,
+            |b, &threads| {
+                //A fresh lifo per sample, a leaking lifo would otherwise keep growing across all samples.
+                b.iter_custom(|iters| {
+                    (0..iters)
+                        .map(|_| push_pop(&AtomicLifo::with_reclaim(reclaim), threads))
+                        .sum()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_reclaim(c: &mut Criterion) {
+    bench_strategy(c, "hazard", HazardReclaim);
+    bench_strategy(c, "leak", LeakReclaim);
+    #[cfg(feature = "epoch")]
+    bench_strategy(c, "epoch", EpochReclaim);
+}
+
+criterion_group!(benches, bench_reclaim);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+use atomic_lifo::{AtomicLifo, AtomicLifoSc};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+fn bench_single_consumer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pop 10000 elements");
+    group.bench_function("AtomicLifo", |b| {
+        b.iter_batched(
+            || AtomicLifo::from_iter(0..10_000u64),
+            |lifo| {
+                while let Some(value) = lifo.pop() {
+                    black_box(value);
+                }
+                lifo
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.bench_function("AtomicLifoSc", |b| {
+        b.iter_batched(
+            || {
+                let lifo = AtomicLifoSc::new();
+                lifo.extend(0..10_000u64);
+                lifo
+            },
+            |lifo| {
+                //Safe, only this thread pops.
+                while let Some(value) = unsafe { lifo.pop() } {
+                    black_box(value);
+                }
+                lifo
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_single_consumer);
+criterion_main!(benches);
@@ -0,0 +1,42 @@
+use atomic_lifo::AtomicLifo;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+fn filled(amount: u64) -> AtomicLifo<u64> {
+    let lifo = AtomicLifo::new();
+    for i in 0..amount {
+        lifo.push(i);
+    }
+    lifo
+}
+
+fn bench_take_all(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remove 10000 elements");
+    group.bench_function("pop loop", |b| {
+        b.iter_batched(
+            || filled(10_000),
+            |lifo| {
+                while let Some(value) = lifo.pop() {
+                    black_box(value);
+                }
+                lifo
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.bench_function("take_all", |b| {
+        b.iter_batched(
+            || filled(10_000),
+            |lifo| {
+                for value in lifo.take_all() {
+                    black_box(value);
+                }
+                lifo
+            },
+            BatchSize::SmallInput,
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_take_all);
+criterion_main!(benches);
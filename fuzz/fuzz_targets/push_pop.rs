@@ -0,0 +1,148 @@
+#![no_main]
+//Drives push, pop, clear, take_all and drops of the lifo from the fuzzing thread and a few short-lived helper threads.
+//Every payload is a heap allocation so ASAN reports a node or value that is freed twice or accessed after it was freed.
+//Every payload must be dropped exactly once, either after it was popped or by the lifo, which the tracker asserts.
+//Whenever no helper accesses the lifo its structure is checked with assert_invariants.
+use arbitrary::Arbitrary;
+use atomic_lifo::AtomicLifo;
+use libfuzzer_sys::fuzz_target;
+use std::collections::HashSet;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+//Maximum amount of helper threads that run at the same time.
+const MAX_HELPERS: usize = 3;
+
+//Payloads that were pushed and not dropped yet.
+#[derive(Default)]
+struct Tracker {
+    next_id: AtomicU64,
+    live: Mutex<HashSet<u64>>,
+}
+
+//A pushed value, its bytes are the id followed by a pattern derived from the id.
+struct Payload {
+    bytes: Box<[u8]>,
+    tracker: Arc<Tracker>,
+}
+
+impl Payload {
+    fn new(tracker: &Arc<Tracker>, len: u8) -> Self {
+        let id = tracker.next_id.fetch_add(1, SeqCst);
+        assert!(tracker.live.lock().unwrap().insert(id));
+        let bytes = id
+            .to_le_bytes()
+            .into_iter()
+            .chain((0..len).map(|i| (id as u8).wrapping_add(i)))
+            .collect();
+        Self {
+            bytes,
+            tracker: Arc::clone(tracker),
+        }
+    }
+
+    fn id(&self) -> u64 {
+        u64::from_le_bytes(self.bytes[..8].try_into().unwrap())
+    }
+
+    //Checks that the bytes are the ones the payload was pushed with.
+    fn check(&self) {
+        let id = self.id();
+        for (i, byte) in self.bytes[8..].iter().enumerate() {
+            assert_eq!(*byte, (id as u8).wrapping_add(i as u8));
+        }
+    }
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        self.check();
+        let id = self.id();
+        assert!(self.tracker.live.lock().unwrap().remove(&id), "payload {id} dropped twice");
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Push(u8),
+    Extend(Vec<u8>),
+    Pop,
+    PopN(u8),
+    Peek,
+    Clear,
+    TakeAll,
+    Recreate,
+    Spawn(Vec<HelperOp>),
+    Join,
+}
+
+#[derive(Arbitrary, Debug)]
+enum HelperOp {
+    Push(u8),
+    Pop,
+    Clear,
+    TakeAll,
+}
+
+fn run_helper(lifo: &AtomicLifo<Payload>, tracker: &Arc<Tracker>, ops: Vec<HelperOp>) {
+    for op in ops {
+        match op {
+            HelperOp::Push(len) => lifo.push(Payload::new(tracker, len)),
+            HelperOp::Pop => drop(lifo.pop()),
+            HelperOp::Clear => lifo.clear(),
+            HelperOp::TakeAll => lifo.take_all().for_each(drop),
+        }
+    }
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let tracker = Arc::new(Tracker::default());
+    let mut lifo = Arc::new(AtomicLifo::new());
+    let mut helpers: Vec<JoinHandle<()>> = Vec::new();
+
+    for op in ops {
+        match op {
+            Op::Push(len) => lifo.push(Payload::new(&tracker, len)),
+            Op::Extend(lens) => lifo.extend(lens.into_iter().map(|len| Payload::new(&tracker, len))),
+            Op::Pop => drop(lifo.pop()),
+            Op::PopN(n) => drop(lifo.pop_n(usize::from(n))),
+            Op::Peek => {
+                lifo.peek_with(Payload::check);
+            }
+            Op::Clear => lifo.clear(),
+            Op::TakeAll => lifo.take_all().for_each(drop),
+            //Helpers may still hold the old lifo, it is dropped by the last of them.
+            Op::Recreate => lifo = Arc::new(AtomicLifo::new()),
+            Op::Spawn(helper_ops) => {
+                if helpers.len() == MAX_HELPERS {
+                    helpers.remove(0).join().unwrap();
+                }
+
+                let lifo = Arc::clone(&lifo);
+                let tracker = Arc::clone(&tracker);
+                helpers.push(thread::spawn(move || run_helper(&lifo, &tracker, helper_ops)));
+            }
+            Op::Join => {
+                if !helpers.is_empty() {
+                    helpers.remove(0).join().unwrap();
+                }
+
+                //Only checked once no helper holds this lifo anymore.
+                if let Some(lifo) = Arc::get_mut(&mut lifo) {
+                    lifo.assert_invariants();
+                }
+            }
+        }
+    }
+
+    for helper in helpers {
+        helper.join().unwrap();
+    }
+
+    Arc::get_mut(&mut lifo).unwrap().assert_invariants();
+    drop(lifo);
+    let live = tracker.live.lock().unwrap();
+    assert!(live.is_empty(), "payloads {live:?} were never dropped");
+});
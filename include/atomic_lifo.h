@@ -0,0 +1,28 @@
+/* C interface of the atomic_lifo crate, available with the "capi" feature. */
+#ifndef ATOMIC_LIFO_H
+#define ATOMIC_LIFO_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Lifo of void* payloads, opaque to C code. Any amount of threads may use the same handle at the same time. */
+typedef struct AtomicLifoHandle AtomicLifoHandle;
+
+/* Allocates a new empty lifo, never returns NULL. */
+AtomicLifoHandle *atomic_lifo_new(void);
+
+/* Pushes the payload on top of the lifo. A NULL payload cannot be told apart from an empty lifo by atomic_lifo_pop. */
+void atomic_lifo_push(const AtomicLifoHandle *handle, void *value);
+
+/* Pops the payload on top of the lifo, returns NULL if the lifo is empty. */
+void *atomic_lifo_pop(const AtomicLifoHandle *handle);
+
+/* Frees the lifo and passes every payload still in it to dtor, which may be NULL. No other thread may use the handle anymore. */
+void atomic_lifo_free(AtomicLifoHandle *handle, void (*dtor)(void *));
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
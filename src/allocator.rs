@@ -0,0 +1,80 @@
+//! Allocator of the nodes, `allocator_api2` with the `allocator-api` feature or the global allocator otherwise.
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+#[cfg(feature = "allocator-api")]
+pub use allocator_api2::alloc::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api"))]
+pub use global::{Allocator, Global};
+
+/// Stand in for the allocator api when the `allocator-api` feature is disabled, only the global allocator implements it.
+#[cfg(not(feature = "allocator-api"))]
+mod global {
+    use core::alloc::Layout;
+    use core::ptr::NonNull;
+
+    /// Subset of the allocator api that is used by the lifo.
+    ///
+    /// # Safety
+    /// Same contract as `core::alloc::Allocator`.
+    pub unsafe trait Allocator {
+        /// Allocates memory for the layout, returns Err if the allocation failed.
+        #[allow(clippy::result_unit_err)]
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, ()>;
+
+        /// Frees memory that was allocated by this allocator with the same layout.
+        ///
+        /// # Safety
+        /// `ptr` must have been allocated by this allocator using `layout`.
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+    }
+
+    /// The global allocator, the default allocator of `AtomicLifo`.
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Global;
+
+    unsafe impl Allocator for Global {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, ()> {
+            //Safe, nodes are never zero sized.
+            let ptr = NonNull::new(unsafe { alloc::alloc::alloc(layout) }).ok_or(())?;
+            Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            alloc::alloc::dealloc(ptr.as_ptr(), layout);
+        }
+    }
+}
+
+/// Moves the value into memory allocated by the allocator.
+/// Calls the allocation error handler if the allocation failed.
+pub fn alloc_in<N, A: Allocator>(allocator: &A, value: N) -> NonNull<N> {
+    try_alloc_in(allocator, value)
+        .unwrap_or_else(|_| alloc::alloc::handle_alloc_error(Layout::new::<N>()))
+}
+
+/// Moves the value into memory allocated by the allocator.
+/// Returns the value if the allocation failed.
+pub fn try_alloc_in<N, A: Allocator>(allocator: &A, value: N) -> Result<NonNull<N>, N> {
+    let Ok(ptr) = allocator.allocate(Layout::new::<N>()) else {
+        return Err(value);
+    };
+
+    let ptr = ptr.cast::<N>();
+    //Safe, the memory was allocated for the layout of N.
+    unsafe {
+        ptr.write(value);
+    }
+
+    Ok(ptr)
+}
+
+/// Drops the value and frees its memory.
+///
+/// # Safety
+/// `ptr` must have been returned by `alloc_in` with the same allocator and must not be used afterward.
+pub unsafe fn free_in<N, A: Allocator>(allocator: &A, ptr: *mut N) {
+    ptr.drop_in_place();
+    allocator.deallocate(NonNull::new_unchecked(ptr.cast()), Layout::new::<N>());
+}
@@ -0,0 +1,212 @@
+//! Lifo that stores its elements in a fixed size array and never allocates.
+use crate::atomic::{AtomicU32, AtomicUsize};
+use core::cell::UnsafeCell;
+use core::fmt::{Debug, Formatter};
+use core::mem::MaybeUninit;
+use core::sync::atomic::Ordering::SeqCst;
+
+/// Amount of bits of a list head that hold the index, the remaining bits hold the tag.
+const INDEX_BITS: u32 = usize::BITS / 2;
+
+/// Index that marks the end of a list of slots, the largest index that fits into `INDEX_BITS`.
+#[allow(clippy::cast_possible_truncation)]
+const NIL: u32 = (usize::MAX >> (usize::BITS - INDEX_BITS)) as u32;
+
+/// Slot of an `ArrayLifo`
+struct Slot<T> {
+    /// index of the next slot in the list this slot is part of.
+    next: AtomicU32,
+    /// the value, only initialized while the slot is part of the used list or owned by a thread that pushes or pops it.
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> Slot<T> {
+    /// Constructs a new empty slot.
+    const fn new() -> Self {
+        Self {
+            next: AtomicU32::new(NIL),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+/// Packs a tag and an index into a list head, bits of the tag that do not fit are dropped.
+const fn pack(tag: usize, index: u32) -> usize {
+    (tag << INDEX_BITS) | index as usize
+}
+
+/// Returns the tag of a list head.
+const fn tag(head: usize) -> usize {
+    head >> INDEX_BITS
+}
+
+/// Returns the index of a list head.
+#[allow(clippy::cast_possible_truncation)]
+const fn index(head: usize) -> u32 {
+    (head & NIL as usize) as u32
+}
+
+///
+/// Thread Safe LIFO Stack that stores up to `N` elements in a fixed size array.
+///
+/// This never allocates, so it can be used on targets without a heap or where allocating is too slow.
+/// Slots are referred to by index instead of by pointer, the elements and the free slots are kept in two lists
+/// that work the same way as `AtomicLifo`. Since slots are never freed there is no need for a hazard list,
+/// instead every list head carries a tag that is incremented on every change to detect that a slot was reused (ABA).
+/// A list head is one `AtomicUsize`, half of its bits hold the index and the other half the tag, so this works
+/// on every target that has pointer sized atomics. The tag wraps after `2^32` changes on 64 bit targets
+/// and after `2^16` changes on 32 bit targets, a thread would have to be suspended for that many
+/// concurrent operations on the same list in the middle of a push or pop for this to fail.
+///
+/// `N` must be smaller than `2^32 - 1` on 64 bit targets and smaller than `u16::MAX` on 32 bit targets,
+/// larger capacities fail to compile.
+///
+pub struct ArrayLifo<T: Send, const N: usize> {
+    /// the slots.
+    slots: [Slot<T>; N],
+    /// the head of the list of slots that contain elements.
+    used: AtomicUsize,
+    /// the head of the list of slots that no longer contain elements.
+    free: AtomicUsize,
+    /// amount of slots that were never used, they are not part of the free list yet.
+    fresh: AtomicUsize,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for ArrayLifo<T, N> {}
+
+impl<T: Send, const N: usize> Default for ArrayLifo<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send, const N: usize> Debug for ArrayLifo<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ArrayLifo")
+            .field("capacity", &N)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Send, const N: usize> Drop for ArrayLifo<T, N> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T: Send, const N: usize> ArrayLifo<T, N> {
+    /// Fails to compile if the index of a slot does not fit into a list head.
+    const CAPACITY_FITS: () = assert!(N < NIL as usize, "ArrayLifo capacity too large");
+
+    /// Constructs a new empty `ArrayLifo`
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Self::CAPACITY_FITS;
+        Self {
+            slots: [const { Slot::new() }; N],
+            used: AtomicUsize::new(pack(0, NIL)),
+            free: AtomicUsize::new(pack(0, NIL)),
+            fresh: AtomicUsize::new(0),
+        }
+    }
+
+    /// Removes the first slot of the list and returns its index.
+    fn pop_slot(&self, list: &AtomicUsize) -> Option<u32> {
+        loop {
+            let head = list.load(SeqCst);
+            let slot = index(head);
+            if slot == NIL {
+                return None;
+            }
+
+            //The slot may already have been removed by another thread, in that case the tag has changed and the CAS fails.
+            let next = self.slots[slot as usize].next.load(SeqCst);
+            if list
+                .compare_exchange(head, pack(tag(head).wrapping_add(1), next), SeqCst, SeqCst)
+                .is_ok()
+            {
+                return Some(slot);
+            }
+        }
+    }
+
+    /// Adds the slot to the front of the list.
+    fn push_slot(&self, list: &AtomicUsize, slot: u32) {
+        loop {
+            let head = list.load(SeqCst);
+            self.slots[slot as usize].next.store(index(head), SeqCst);
+            if list
+                .compare_exchange(head, pack(tag(head).wrapping_add(1), slot), SeqCst, SeqCst)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+
+    /// Returns the index of a slot that is owned by the calling thread.
+    fn alloc_slot(&self) -> Option<u32> {
+        if let Some(slot) = self.pop_slot(&self.free) {
+            return Some(slot);
+        }
+
+        let mut fresh = self.fresh.load(SeqCst);
+        while fresh < N {
+            match self.fresh.compare_exchange(fresh, fresh + 1, SeqCst, SeqCst) {
+                //Cannot truncate, N is smaller than NIL.
+                #[allow(clippy::cast_possible_truncation)]
+                Ok(_) => return Some(fresh as u32),
+                Err(current) => fresh = current,
+            }
+        }
+
+        //Another thread may have freed a slot in the meantime.
+        self.pop_slot(&self.free)
+    }
+
+    ///
+    /// Pushes a value on top of the lifo stack if there is a free slot.
+    ///
+    /// # Errors
+    /// returns the value if all `N` slots are in use.
+    ///
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        let Some(slot) = self.alloc_slot() else {
+            return Err(value);
+        };
+
+        //Safe, we own the slot until it is part of the used list.
+        unsafe {
+            (*self.slots[slot as usize].value.get()).write(value);
+        }
+
+        self.push_slot(&self.used, slot);
+        Ok(())
+    }
+
+    /// Pops the top of the lifo stack
+    pub fn pop(&self) -> Option<T> {
+        let slot = self.pop_slot(&self.used)?;
+        //Safe, we own the slot until it is part of the free list.
+        let value = unsafe { (*self.slots[slot as usize].value.get()).assume_init_read() };
+        self.push_slot(&self.free, slot);
+        Some(value)
+    }
+
+    ///
+    /// Returns true if the lifo contains no elements.
+    ///
+    /// This is only a snapshot, other threads may push or pop elements at any time
+    /// so the returned value may already be stale once this fn returns.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        index(self.used.load(SeqCst)) == NIL
+    }
+
+    /// Returns the maximum amount of elements.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
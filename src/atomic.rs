@@ -0,0 +1,47 @@
+//! Atomic types of `core` or, with the `portable-atomic` feature, of `portable-atomic`.
+//!
+//! `portable-atomic` also provides compare and swap on targets that only have atomic loads and stores,
+//! for example with its `critical-section` feature or the `portable_atomic_unsafe_assume_single_core` cfg on single core MCUs.
+//!
+//! When built with `--cfg loom` the atomic types of loom are used instead, see the loom tests.
+//! Loom atomics cannot be constructed in a const context and have no `get_mut`,
+//! fns that have exclusive access to an atomic therefore use `with_mut`, which `WithMut` provides for the other atomics.
+//!
+//! `AtomicU64` is only available with `portable-atomic`, with loom or on targets that have 64 bit atomics.
+#[cfg(all(not(loom), not(feature = "portable-atomic")))]
+pub use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicUsize};
+//Targets such as thumbv7em only have 32 bit atomics, users of `AtomicU64` fall back to `AtomicUsize` there.
+#[cfg(all(not(loom), not(feature = "portable-atomic"), target_has_atomic = "64"))]
+pub use core::sync::atomic::AtomicU64;
+#[cfg(loom)]
+pub use loom::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, AtomicUsize};
+#[cfg(all(not(loom), feature = "portable-atomic"))]
+pub use portable_atomic::{AtomicBool, AtomicPtr, AtomicU32, AtomicU64, AtomicUsize};
+
+/// Access to the value of an atomic that is not shared with other threads, the same as `with_mut` of the loom atomics.
+#[cfg(not(loom))]
+pub trait WithMut<T> {
+    /// Calls `f` with a mutable reference to the value.
+    fn with_mut<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R;
+}
+
+#[cfg(not(loom))]
+impl<N> WithMut<*mut N> for AtomicPtr<N> {
+    fn with_mut<R>(&mut self, f: impl FnOnce(&mut *mut N) -> R) -> R {
+        f(self.get_mut())
+    }
+}
+
+#[cfg(not(loom))]
+impl WithMut<usize> for AtomicUsize {
+    fn with_mut<R>(&mut self, f: impl FnOnce(&mut usize) -> R) -> R {
+        f(self.get_mut())
+    }
+}
+
+#[cfg(all(not(loom), any(target_has_atomic = "64", feature = "portable-atomic")))]
+impl WithMut<u64> for AtomicU64 {
+    fn with_mut<R>(&mut self, f: impl FnOnce(&mut u64) -> R) -> R {
+        f(self.get_mut())
+    }
+}
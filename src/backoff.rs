@@ -0,0 +1,60 @@
+//! Exponential backoff for compare and swap loops that failed because of contention.
+
+/// Amount of failed attempts that are retried right away, an uncontended loop never waits.
+#[cfg(not(loom))]
+const IMMEDIATE_RETRIES: u32 = 2;
+
+/// Attempts after which the amount of spins stops doubling, the longest burst spins `1 << SPIN_LIMIT` times.
+#[cfg(not(loom))]
+const SPIN_LIMIT: u32 = 6;
+
+/// Waits a little longer after every failed attempt of a compare and swap loop,
+/// so contending threads stop invalidating the cache line of the head for each other.
+#[derive(Debug)]
+pub struct Backoff {
+    /// amount of failed attempts so far.
+    attempt: u32,
+}
+
+impl Backoff {
+    /// Constructs a new `Backoff` for a loop that has not failed yet.
+    pub const fn new() -> Self {
+        Self { attempt: 0 }
+    }
+
+    /// Waits after a failed attempt.
+    /// The first attempts are retried immediately, then the amount of spins doubles with every attempt.
+    /// Once the longest burst was reached the thread yields to the scheduler instead with the `std` feature.
+    pub fn snooze(&mut self) {
+        self.attempt = self.attempt.saturating_add(1);
+        //Loom has to be told that the thread waits for another thread, otherwise it explores the spinning forever.
+        #[cfg(loom)]
+        loom::thread::yield_now();
+        #[cfg(not(loom))]
+        self.spin();
+    }
+
+    /// Returns the amount of failed attempts so far.
+    pub const fn attempts(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Spins or yields depending on the amount of failed attempts.
+    #[cfg(not(loom))]
+    fn spin(&self) {
+        if self.attempt <= IMMEDIATE_RETRIES {
+            return;
+        }
+
+        let exponent = self.attempt - IMMEDIATE_RETRIES;
+        #[cfg(feature = "std")]
+        if exponent > SPIN_LIMIT {
+            std::thread::yield_now();
+            return;
+        }
+
+        for _ in 0..1u32 << exponent.min(SPIN_LIMIT) {
+            core::hint::spin_loop();
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Lifo whose consumers wait on a `Condvar` until an element is pushed.
+use crate::atomic::AtomicUsize;
+use crate::AtomicLifo;
+use core::fmt::{Debug, Formatter};
+use core::sync::atomic::Ordering::SeqCst;
+use core::time::Duration;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+///
+/// Thread Safe LIFO Stack whose consumers block on a `Condvar` until an element is available.
+///
+/// Pushing and popping stay lock free, the mutex is only locked by consumers that are about to wait
+/// and by producers that have to notify them. A consumer counts itself as waiting and checks the lifo again
+/// while it holds the mutex, a producer pushes and then checks for waiting consumers.
+/// Both are `SeqCst`, so either the consumer sees the element or the producer sees the consumer,
+/// which cannot start waiting before the producer gets the mutex to notify it. No wakeup is lost.
+///
+/// Unlike `AtomicLifo::pop_blocking`, which parks and unparks threads itself,
+/// this suits worker pools that already wait on condition variables.
+/// Elements pushed to the lifo in another way than through this wrapper do not wake consumers.
+///
+pub struct BlockingLifo<T: Send> {
+    /// the elements.
+    inner: AtomicLifo<T>,
+    /// amount of consumers that are waiting or about to wait on `available`.
+    waiting: AtomicUsize,
+    /// held by a consumer from its last check of the lifo until it waits on `available`.
+    lock: Mutex<()>,
+    /// notified once for every element pushed while a consumer is waiting.
+    available: Condvar,
+}
+
+impl<T: Sync + Send + Debug> Debug for BlockingLifo<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BlockingLifo")
+            .field("inner", &self.inner)
+            .field("waiting", &self.waiting.load(SeqCst))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Send> Default for BlockingLifo<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send> BlockingLifo<T> {
+    /// Constructs a new empty `BlockingLifo`
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: AtomicLifo::new(),
+            waiting: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            available: Condvar::new(),
+        }
+    }
+
+    ///
+    /// Pushes a value on top of the lifo stack and wakes one waiting consumer.
+    ///
+    /// If no consumer is waiting this costs a single additional atomic load.
+    ///
+    pub fn push(&self, value: T) {
+        self.inner.push(value);
+        self.notify(1);
+    }
+
+    ///
+    /// Pushes all values of the iterator on top of the lifo stack and wakes one waiting consumer for each of them.
+    ///
+    /// See `AtomicLifo::extend`.
+    ///
+    pub fn extend(&self, iter: impl IntoIterator<Item = T>) {
+        let mut count = 0;
+        self.inner.extend(iter.into_iter().inspect(|_| count += 1));
+        self.notify(count);
+    }
+
+    ///
+    /// Pops the top of the lifo stack without waiting.
+    ///
+    /// See `AtomicLifo::pop`.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop(&self) -> Option<T> {
+        self.inner.pop()
+    }
+
+    ///
+    /// Pops the top of the lifo stack, waits until an element is pushed if the lifo is empty.
+    ///
+    /// This fn never returns if no element is ever pushed,
+    /// to shut down consumers waiting in this fn push a sentinel element for each of them.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_wait(&self) -> T {
+        //Safe, without a deadline pop_until never returns None.
+        unsafe { self.pop_until(None).unwrap_unchecked() }
+    }
+
+    ///
+    /// Pops the top of the lifo stack, waits for up to `timeout` until an element is pushed if the lifo is empty.
+    ///
+    /// Returns None if no element could be popped before the timeout elapsed.
+    /// A zero timeout makes this behave like `pop`.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_wait_timeout(&self, timeout: Duration) -> Option<T> {
+        //A timeout too large to be represented is as good as no timeout.
+        self.pop_until(Instant::now().checked_add(timeout))
+    }
+
+    ///
+    /// Returns true if the lifo contains no elements.
+    ///
+    /// See `AtomicLifo::is_empty`.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    ///
+    /// Returns the amount of elements in the lifo.
+    ///
+    /// See `AtomicLifo::len`.
+    ///
+    #[cfg(feature = "count")]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    ///
+    /// Returns the amount of consumers that are currently waiting in `pop_wait` or `pop_wait_timeout`.
+    ///
+    /// This is only a snapshot, consumers may start or stop waiting at any time.
+    ///
+    #[must_use]
+    pub fn waiting_count(&self) -> usize {
+        self.waiting.load(SeqCst)
+    }
+
+    ///
+    /// Consumes the lifo and returns all elements in the order `pop` would have returned them.
+    ///
+    #[must_use]
+    pub fn into_vec(self) -> alloc::vec::Vec<T> {
+        self.inner.into_vec()
+    }
+
+    /// Locks the mutex, it guards no data so a consumer that panicked while holding it left nothing inconsistent.
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wakes up to `count` waiting consumers after `count` elements were pushed.
+    fn notify(&self, count: usize) {
+        //SeqCst, ordered after the push, see the increment in pop_until.
+        if count == 0 || self.waiting.load(SeqCst) == 0 {
+            return;
+        }
+
+        //A consumer that counted itself holds the mutex until it waits, so it cannot miss the notification.
+        drop(self.lock());
+        if count == 1 {
+            self.available.notify_one();
+        } else {
+            self.available.notify_all();
+        }
+    }
+
+    /// Pops the top of the lifo stack, waits until an element is pushed or the deadline is reached.
+    /// Only returns None once the deadline is reached.
+    fn pop_until(&self, deadline: Option<Instant>) -> Option<T> {
+        loop {
+            if let Some(value) = self.inner.pop() {
+                return Some(value);
+            }
+
+            let timeout = match deadline {
+                None => None,
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return None;
+                    }
+
+                    Some(remaining)
+                }
+            };
+
+            let guard = self.lock();
+            //SeqCst, ordered before the check of the head, a producer that pushes after that check sees us.
+            self.waiting.fetch_add(1, SeqCst);
+            //Only checked, the element is popped without the mutex once we looped around.
+            if self.inner.is_empty() {
+                let guard = match timeout {
+                    None => self
+                        .available
+                        .wait(guard)
+                        .unwrap_or_else(PoisonError::into_inner),
+                    Some(timeout) => {
+                        self.available
+                            .wait_timeout(guard, timeout)
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .0
+                    }
+                };
+                drop(guard);
+            } else {
+                drop(guard);
+            }
+
+            self.waiting.fetch_sub(1, SeqCst);
+        }
+    }
+}
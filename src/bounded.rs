@@ -0,0 +1,155 @@
+//! Lifo with a maximum amount of elements.
+use crate::atomic::AtomicUsize;
+use crate::backoff::Backoff;
+use crate::AtomicLifo;
+use core::fmt::{Debug, Formatter};
+use core::sync::atomic::Ordering::SeqCst;
+
+///
+/// Thread Safe LIFO Stack that holds at most `capacity` elements.
+///
+/// A push reserves a slot before the element is pushed and a pop releases the slot after the element was popped.
+/// The lifo therefore never contains more than `capacity` elements,
+/// but a push may be rejected while another thread is still in the middle of popping an element.
+///
+pub struct BoundedAtomicLifo<T: Send> {
+    /// the elements.
+    inner: AtomicLifo<T>,
+    /// amount of reserved slots, includes elements that are currently being pushed or popped.
+    len: AtomicUsize,
+    /// maximum amount of elements.
+    capacity: usize,
+}
+
+impl<T: Sync + Send + Debug> Debug for BoundedAtomicLifo<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BoundedAtomicLifo")
+            .field("inner", &self.inner)
+            .field("len", &self.len)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl<T: Send> BoundedAtomicLifo<T> {
+    /// Constructs a new empty `BoundedAtomicLifo` that holds at most `capacity` elements.
+    #[must_use]
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            inner: AtomicLifo::new(),
+            len: AtomicUsize::new(0),
+            capacity,
+        }
+    }
+
+    ///
+    /// Pushes a value on top of the lifo stack if the lifo is not full.
+    ///
+    /// # Errors
+    /// returns the value if the lifo is full.
+    ///
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        if self.len.fetch_add(1, SeqCst) >= self.capacity {
+            self.len.fetch_sub(1, SeqCst);
+            return Err(value);
+        }
+
+        self.inner.push(value);
+        Ok(())
+    }
+
+    ///
+    /// Pushes a value on top of the lifo stack, evicts the bottom element if the lifo is full.
+    ///
+    /// Returns the evicted element, which is the element that has been in the lifo the longest,
+    /// or the value itself if the capacity is 0.
+    /// Evicting is O(n), see `AtomicLifo::pop_bottom`.
+    /// The evicted slot is reused for the value, so the lifo never contains more than `capacity` elements.
+    /// If the lifo is full but all of its elements are currently being pushed or popped by other threads
+    /// then this fn waits until it can either push or evict.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn push_evict(&self, value: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(value);
+        }
+
+        let mut backoff = Backoff::new();
+        loop {
+            if self.len.fetch_add(1, SeqCst) < self.capacity {
+                self.inner.push(value);
+                return None;
+            }
+
+            self.len.fetch_sub(1, SeqCst);
+
+            if let Some(evicted) = self.inner.pop_bottom() {
+                self.inner.push(value);
+                return Some(evicted);
+            }
+
+            backoff.snooze();
+        }
+    }
+
+    ///
+    /// Pops the top of the lifo stack
+    ///
+    /// See `AtomicLifo::pop`.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop(&self) -> Option<T> {
+        let value = self.inner.pop()?;
+        self.len.fetch_sub(1, SeqCst);
+        Some(value)
+    }
+
+    /// Returns the maximum amount of elements.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    ///
+    /// Returns the amount of elements in the lifo.
+    ///
+    /// This is only a snapshot, other threads may push or pop elements at any time.
+    /// Elements that are currently being pushed or popped by other threads are counted.
+    ///
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len.load(SeqCst).min(self.capacity)
+    }
+
+    ///
+    /// Returns true if the lifo contains no elements.
+    ///
+    /// See `AtomicLifo::is_empty`.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    ///
+    /// Returns true if a push would currently be rejected.
+    ///
+    /// This is only a snapshot, other threads may push or pop elements at any time.
+    ///
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len.load(SeqCst) >= self.capacity
+    }
+
+    ///
+    /// Consumes the lifo and returns all elements in the order `pop` would have returned them.
+    ///
+    #[must_use]
+    pub fn into_vec(self) -> alloc::vec::Vec<T> {
+        self.inner.into_vec()
+    }
+}
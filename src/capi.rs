@@ -0,0 +1,87 @@
+//! C interface of the `capi` feature, declared in `include/atomic_lifo.h`.
+//!
+//! The lifo stores opaque `void*` payloads that C code passes in and gets back, it never dereferences them.
+use crate::AtomicLifo;
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::ptr::null_mut;
+
+/// Opaque payload of the C interface.
+///
+/// The lifo only moves the pointer between threads and never dereferences it,
+/// whether the pointee may be used by the thread that pops it is the responsibility of the C code.
+struct Payload(*mut c_void);
+
+//Safe, the pointer is never dereferenced by Rust, see above.
+unsafe impl Send for Payload {}
+unsafe impl Sync for Payload {}
+
+///
+/// Lifo of `void*` payloads, created by `atomic_lifo_new` and freed by `atomic_lifo_free`.
+///
+/// Opaque to C code, which only ever holds a pointer to it.
+/// Any amount of threads may push to and pop from the same handle at the same time.
+///
+pub struct AtomicLifoHandle(AtomicLifo<Payload>);
+
+///
+/// Allocates a new empty lifo.
+///
+/// Never returns null, the process is aborted if the allocation fails.
+///
+#[no_mangle]
+pub extern "C" fn atomic_lifo_new() -> *mut AtomicLifoHandle {
+    Box::into_raw(Box::new(AtomicLifoHandle(AtomicLifo::new())))
+}
+
+///
+/// Pushes the payload on top of the lifo.
+///
+/// A null payload can be pushed, but cannot be told apart from an empty lifo by `atomic_lifo_pop`.
+///
+/// # Safety
+/// `handle` must have been returned by `atomic_lifo_new` and not yet been passed to `atomic_lifo_free`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn atomic_lifo_push(handle: *const AtomicLifoHandle, value: *mut c_void) {
+    (*handle).0.push(Payload(value));
+}
+
+///
+/// Pops the payload on top of the lifo, returns null if the lifo is empty.
+///
+/// The process is aborted if more than `isize::MAX` threads are popping at the same time.
+///
+/// # Safety
+/// `handle` must have been returned by `atomic_lifo_new` and not yet been passed to `atomic_lifo_free`.
+///
+#[no_mangle]
+pub unsafe extern "C" fn atomic_lifo_pop(handle: *const AtomicLifoHandle) -> *mut c_void {
+    (*handle).0.pop().map_or(null_mut(), |payload| payload.0)
+}
+
+///
+/// Frees the lifo and passes every payload still in it to `dtor` in the order they would have been popped.
+///
+/// If `dtor` is null the remaining payloads are only removed. Passing a null handle does nothing.
+///
+/// # Safety
+/// `handle` must be null or have been returned by `atomic_lifo_new` and not yet been passed to this fn.
+/// No other thread may use the handle during or after this call.
+///
+#[no_mangle]
+pub unsafe extern "C" fn atomic_lifo_free(
+    handle: *mut AtomicLifoHandle,
+    dtor: Option<extern "C" fn(*mut c_void)>,
+) {
+    if handle.is_null() {
+        return;
+    }
+
+    let lifo = Box::from_raw(handle).0;
+    for payload in lifo {
+        if let Some(dtor) = dtor {
+            dtor(payload.0);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Unbounded lifo channel built on top of an `AtomicLifo`.
+use crate::atomic::AtomicUsize;
+use crate::{AtomicLifo, Drain};
+use alloc::sync::Arc;
+use core::fmt::{Debug, Display, Formatter};
+use core::sync::atomic::Ordering::SeqCst;
+
+/// State shared by all senders and receivers of a channel.
+struct Shared<T: Send> {
+    /// the elements of the channel.
+    lifo: AtomicLifo<T>,
+    /// amount of senders that were not dropped yet.
+    senders: AtomicUsize,
+}
+
+/// Only prints the approximate amount of elements, the elements need not be `Sync` to be sent.
+impl<T: Send> Debug for Shared<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Shared")
+            .field("lifo", &self.lifo.debug_len())
+            .field("senders", &self.senders)
+            .finish()
+    }
+}
+
+///
+/// Creates a new unbounded lifo channel.
+///
+/// Elements are received in the reverse order they were sent, the most recently sent element is received first.
+/// Both handles can be cloned and sent to other threads.
+/// Once all senders were dropped and all elements were received the receivers report that the channel is disconnected.
+///
+#[must_use]
+pub fn channel<T: Send>() -> (LifoSender<T>, LifoReceiver<T>) {
+    let shared = Arc::new(Shared {
+        lifo: AtomicLifo::new(),
+        senders: AtomicUsize::new(1),
+    });
+
+    (
+        LifoSender {
+            shared: Arc::clone(&shared),
+        },
+        LifoReceiver { shared },
+    )
+}
+
+/// Error returned by `LifoReceiver::try_recv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TryRecvError {
+    /// The channel is currently empty, but senders still exist.
+    Empty,
+    /// The channel is empty and all senders were dropped, no more elements will ever be received.
+    Disconnected,
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("receiving on an empty channel"),
+            Self::Disconnected => f.write_str("receiving on an empty and disconnected channel"),
+        }
+    }
+}
+
+impl core::error::Error for TryRecvError {}
+
+/// Push only handle of a channel created by `channel`.
+#[derive(Debug)]
+pub struct LifoSender<T: Send> {
+    /// the state of the channel.
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Send> LifoSender<T> {
+    /// Sends a value, it is the next value a receiver receives unless another value is sent before.
+    pub fn push(&self, value: T) {
+        self.shared.lifo.push(value);
+    }
+
+    ///
+    /// Sends all values of the iterator.
+    ///
+    /// The last value of the iterator is received first.
+    /// All values become visible to the receivers at once.
+    ///
+    pub fn extend(&self, iter: impl IntoIterator<Item = T>) {
+        self.shared.lifo.extend(iter);
+    }
+}
+
+impl<T: Send> Clone for LifoSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, SeqCst);
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: Send> Drop for LifoSender<T> {
+    fn drop(&mut self) {
+        self.shared.senders.fetch_sub(1, SeqCst);
+    }
+}
+
+/// Pop only handle of a channel created by `channel`.
+#[derive(Debug)]
+pub struct LifoReceiver<T: Send> {
+    /// the state of the channel.
+    shared: Arc<Shared<T>>,
+}
+
+impl<T: Send> LifoReceiver<T> {
+    ///
+    /// Receives the most recently sent value.
+    ///
+    /// Returns None if the channel is empty, regardless of whether senders still exist.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    #[must_use]
+    pub fn pop(&self) -> Option<T> {
+        self.shared.lifo.pop()
+    }
+
+    ///
+    /// Receives the most recently sent value.
+    ///
+    /// Returns `TryRecvError::Disconnected` once the channel is empty and all senders were dropped,
+    /// otherwise `TryRecvError::Empty` if the channel is empty.
+    ///
+    /// # Errors
+    /// if the channel is empty.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        //Loaded before popping, if there were no senders left then nothing can be sent after the pop failed.
+        let disconnected = self.shared.senders.load(SeqCst) == 0;
+        if let Some(value) = self.shared.lifo.pop() {
+            return Ok(value);
+        }
+
+        if disconnected {
+            return Err(TryRecvError::Disconnected);
+        }
+
+        Err(TryRecvError::Empty)
+    }
+
+    ///
+    /// Returns an iterator that receives values until the channel is empty.
+    ///
+    /// See `AtomicLifo::drain`.
+    ///
+    #[must_use]
+    pub fn drain(&self) -> Drain<'_, T> {
+        self.shared.lifo.drain()
+    }
+
+    ///
+    /// Returns true if all senders were dropped.
+    ///
+    /// The channel may still contain elements that were sent before the senders were dropped.
+    ///
+    #[must_use]
+    pub fn is_disconnected(&self) -> bool {
+        self.shared.senders.load(SeqCst) == 0
+    }
+}
+
+impl<T: Send> Clone for LifoReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Lifo whose head is only accessed inside critical sections, for bare metal targets and interrupt handlers.
+use crate::allocator::{alloc_in, free_in, try_alloc_in, Global};
+use crate::error::PushError;
+use core::cell::Cell;
+use core::fmt::{Debug, Formatter};
+use core::mem::ManuallyDrop;
+use core::ptr::{null_mut, NonNull};
+use critical_section::Mutex;
+
+/// Node of a `CriticalSectionLifo`.
+struct CsNode<T> {
+    /// the next node.
+    next: *mut Self,
+    /// the value, it is moved out when the node is popped and dropped when the lifo is dropped.
+    value: ManuallyDrop<T>,
+}
+
+///
+/// LIFO Stack that only accesses its head inside a critical section of the `critical-section` crate.
+///
+/// This is the established pattern of embedded crates to share data between the main loop and interrupt handlers,
+/// and it works on targets without atomic compare and swap. The critical section provides mutual exclusion,
+/// so a popped node is freed right away and none of the hazard bookkeeping of `AtomicLifo` is needed.
+/// Push and pop have the same semantics as the ones of `AtomicLifo`.
+///
+/// # Interrupt safety
+/// All fns may be called from interrupt handlers and from the main loop at the same time.
+/// The critical sections are short, they only link or unlink a single node, except for `peek_with`,
+/// whose closure runs inside the critical section and delays interrupts until it has returned.
+///
+/// Pushing allocates the node before the critical section is entered, so `push` is only interrupt safe
+/// if the global allocator is. `try_push` returns the value instead of calling the allocation error handler,
+/// which suits allocators that fail rather than block inside interrupt handlers.
+/// Popping frees the node after the critical section was left, so `pop` is only interrupt safe if deallocating is.
+/// The values are dropped in the context that pops them or drops the lifo.
+///
+pub struct CriticalSectionLifo<T: Send> {
+    /// the top node.
+    head: Mutex<Cell<*mut CsNode<T>>>,
+    /// amount of elements in the lifo.
+    len: Mutex<Cell<usize>>,
+}
+
+//Safe, the nodes are only accessed inside critical sections or by the thread that unlinked them.
+unsafe impl<T: Send> Send for CriticalSectionLifo<T> {}
+unsafe impl<T: Send> Sync for CriticalSectionLifo<T> {}
+
+impl<T: Send> Default for CriticalSectionLifo<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send> Debug for CriticalSectionLifo<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CriticalSectionLifo")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Send> Drop for CriticalSectionLifo<T> {
+    fn drop(&mut self) {
+        let mut node = self.head.get_mut().get();
+        //Safe, we own all nodes.
+        while let Some(node_ref) = unsafe { node.as_mut() } {
+            let next = node_ref.next;
+            unsafe {
+                ManuallyDrop::drop(&mut node_ref.value);
+                free_in(&Global, node);
+            }
+            node = next;
+        }
+    }
+}
+
+impl<T: Send> CriticalSectionLifo<T> {
+    /// Constructs a new empty `CriticalSectionLifo`
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            head: Mutex::new(Cell::new(null_mut())),
+            len: Mutex::new(Cell::new(0)),
+        }
+    }
+
+    /// Pushes a value on top of the lifo stack
+    pub fn push(&self, value: T) {
+        let node = alloc_in(
+            &Global,
+            CsNode {
+                next: null_mut(),
+                value: ManuallyDrop::new(value),
+            },
+        );
+
+        //Safe, the node was never part of the lifo.
+        unsafe {
+            self.link(node);
+        }
+    }
+
+    ///
+    /// Pushes a value on top of the lifo stack, returns the value if the node for it could not be allocated.
+    ///
+    /// Unlike `push` this fn does not call the allocation error handler, which aborts the process by default.
+    ///
+    /// # Errors
+    /// returns the value if the allocator failed to allocate the node.
+    ///
+    pub fn try_push(&self, value: T) -> Result<(), PushError<T>> {
+        let node = try_alloc_in(
+            &Global,
+            CsNode {
+                next: null_mut(),
+                value: ManuallyDrop::new(value),
+            },
+        )
+        .map_err(|node| PushError(ManuallyDrop::into_inner(node.value)))?;
+
+        //Safe, the node was never part of the lifo.
+        unsafe {
+            self.link(node);
+        }
+
+        Ok(())
+    }
+
+    /// Links the node on top of the lifo.
+    ///
+    /// # Safety
+    /// The node must be exclusively owned by the caller.
+    unsafe fn link(&self, mut node: NonNull<CsNode<T>>) {
+        critical_section::with(|cs| {
+            let head = self.head.borrow(cs);
+            node.as_mut().next = head.get();
+            head.set(node.as_ptr());
+            let len = self.len.borrow(cs);
+            len.set(len.get() + 1);
+        });
+    }
+
+    /// Pops the top of the lifo stack
+    pub fn pop(&self) -> Option<T> {
+        let node = critical_section::with(|cs| {
+            let head = self.head.borrow(cs);
+            //Safe, nodes are only unlinked inside critical sections.
+            let node = head.get();
+            head.set(unsafe { node.as_ref()? }.next);
+            let len = self.len.borrow(cs);
+            len.set(len.get() - 1);
+            Some(node)
+        })?;
+
+        //Safe, the node was unlinked by us, no other context can access it anymore.
+        unsafe {
+            let value = ManuallyDrop::take(&mut (*node).value);
+            free_in(&Global, node);
+            Some(value)
+        }
+    }
+
+    ///
+    /// Calls the closure with a reference to the top of the lifo stack without removing it.
+    /// Returns None if the lifo is empty, otherwise the result of the closure.
+    ///
+    /// The closure runs inside the critical section, so it should not do anything expensive.
+    ///
+    pub fn peek_with<U>(&self, f: impl FnOnce(&T) -> U) -> Option<U> {
+        critical_section::with(|cs| {
+            //Safe, the node cannot be unlinked while we are inside the critical section.
+            let node = unsafe { self.head.borrow(cs).get().as_ref()? };
+            Some(f(&node.value))
+        })
+    }
+
+    ///
+    /// Returns true if the lifo contains no elements.
+    ///
+    /// This is only a snapshot, other contexts may push or pop elements at any time.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        critical_section::with(|cs| self.head.borrow(cs).get().is_null())
+    }
+
+    ///
+    /// Returns the amount of elements in the lifo.
+    ///
+    /// This is only a snapshot, other contexts may push or pop elements at any time.
+    ///
+    #[must_use]
+    pub fn len(&self) -> usize {
+        critical_section::with(|cs| self.len.borrow(cs).get())
+    }
+}
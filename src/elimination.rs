@@ -0,0 +1,217 @@
+//! Elimination array where a push and a pop that both failed their compare and swap exchange a node directly,
+//! and the slot where `pop_waiting` waits for a node of `push_or_exchange`.
+#[cfg(all(not(loom), any(test, feature = "debug-validate")))]
+use crate::atomic::WithMut;
+use crate::atomic::{AtomicPtr, AtomicUsize};
+use crate::backoff::Backoff;
+use alloc::boxed::Box;
+use core::ptr::null_mut;
+use core::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+/// Slot that nobody uses.
+const EMPTY: usize = 0;
+/// The slot is being written, either by a pusher that is about to offer its node or by the partner of a waiting pop.
+const BUSY: usize = 1;
+/// A pusher waits for a pop to take the node in the slot.
+const OFFER: usize = 2;
+/// A pop waits for a pusher to put a node into the slot.
+const REQUEST: usize = 3;
+/// The partner has taken or put the node, the waiting thread resets the slot once it has seen this.
+const DONE: usize = 4;
+
+/// Amount of times a thread checks its slot for a partner before it gives up.
+#[cfg(not(loom))]
+const WAIT_SPINS: u32 = 128;
+
+/// Loom explores every check, one is enough to find a partner and to give up.
+#[cfg(loom)]
+const WAIT_SPINS: u32 = 1;
+
+/// Place where one push and one pop can meet.
+///
+/// Only the thread that moved the slot out of `EMPTY` resets it to `EMPTY`, after it either withdrew or saw `DONE`.
+/// A partner only acts on `OFFER` and `REQUEST` and moves the slot to `BUSY` before it touches `node`,
+/// so at most one partner is ever matched with a waiting thread and the slot is not reused before the waiting thread is done.
+#[derive(Debug)]
+pub struct Slot<N> {
+    /// one of `EMPTY`, `BUSY`, `OFFER`, `REQUEST` and `DONE`.
+    state: AtomicUsize,
+    /// the exchanged node, only accessed by the thread that moved the state to `BUSY` or saw `DONE`.
+    node: AtomicPtr<N>,
+}
+
+impl<N> Slot<N> {
+    const_fn! {
+        /// Constructs a slot that nobody uses.
+        pub fn new() -> Self {
+            Self {
+                state: AtomicUsize::new(EMPTY),
+                node: AtomicPtr::new(null_mut()),
+            }
+        }
+    }
+
+    /// Returns true if a pop currently waits in the slot for a node.
+    pub fn is_requested(&self) -> bool {
+        self.state.load(Relaxed) == REQUEST
+    }
+
+    /// Hands the node to a pop that waits in the slot.
+    /// Returns true if the pop took the node, which the caller no longer owns then.
+    pub fn give(&self, node: *mut N) -> bool {
+        //Acquire, synchronizes with the reset by the previous user of the slot, the request continues its release sequence.
+        if self
+            .state
+            .compare_exchange(REQUEST, BUSY, Acquire, Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+
+        self.node.store(node, Relaxed);
+        //Release, the pop must see the node and its value.
+        self.state.store(DONE, Release);
+        true
+    }
+
+    /// Waits in the slot for a pusher to put its node into it.
+    /// Returns the node, which the caller owns then, or None if the slot is in use or no pusher came along.
+    pub fn request(&self) -> Option<*mut N> {
+        //Acquire, synchronizes with the reset by the previous user of the slot.
+        self.state
+            .compare_exchange(EMPTY, REQUEST, Acquire, Relaxed)
+            .ok()?;
+        self.wait(REQUEST)
+    }
+
+    /// Panics if a thread still uses the slot.
+    #[cfg(any(test, feature = "debug-validate"))]
+    pub fn assert_invariants(&mut self) {
+        assert_eq!(
+            self.state.with_mut(|state| *state),
+            EMPTY,
+            "a thread still uses a slot"
+        );
+    }
+
+    /// Waits for the partner that matched us, resets the slot and returns the node in it.
+    fn finish(&self) -> *mut N {
+        let mut backoff = Backoff::new();
+        //Acquire, synchronizes with the store of DONE so we see the node the partner put into the slot.
+        while self.state.load(Acquire) != DONE {
+            backoff.snooze();
+        }
+
+        let node = self.node.load(Relaxed);
+        //Release, the next thread that uses the slot writes the node only after we read it.
+        self.state.store(EMPTY, Release);
+        node
+    }
+
+    /// Waits for a partner until `WAIT_SPINS` checks were made.
+    /// Returns the node in the slot if a partner matched us, None if we withdrew and the slot is `EMPTY` again.
+    fn wait(&self, waiting: usize) -> Option<*mut N> {
+        for _ in 0..WAIT_SPINS {
+            if self.state.load(Relaxed) != waiting {
+                return Some(self.finish());
+            }
+
+            #[cfg(loom)]
+            loom::thread::yield_now();
+            #[cfg(not(loom))]
+            core::hint::spin_loop();
+        }
+
+        //Withdrawing fails if a partner moved the slot to BUSY in the meantime, it then finishes the exchange.
+        //Relaxed, a pusher that withdraws keeps its node and never reads the slot again.
+        match self
+            .state
+            .compare_exchange(waiting, EMPTY, Relaxed, Relaxed)
+        {
+            Ok(_) => None,
+            Err(_) => Some(self.finish()),
+        }
+    }
+}
+
+/// Slots of a lifo where pushes and pops that failed their compare and swap wait for each other.
+#[derive(Debug)]
+pub struct Elimination<N> {
+    /// the slots, threads pick one by their stack address and move on to the next one on every retry.
+    slots: Box<[Slot<N>]>,
+}
+
+impl<N> Elimination<N> {
+    /// Constructs `len` slots that nobody uses.
+    pub fn new(len: usize) -> Self {
+        Self {
+            slots: (0..len).map(|_| Slot::new()).collect(),
+        }
+    }
+
+    /// Returns the amount of slots.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the slot of the calling thread for its `attempt`th retry.
+    fn slot(&self, attempt: u32) -> &Slot<N> {
+        //Threads have different stacks, the multiplication spreads the bits that differ over the slots.
+        let local = 0u8;
+        let hash = ((core::ptr::addr_of!(local) as usize) >> 4).wrapping_mul(0x9E37_79B9) >> 16;
+        &self.slots[hash.wrapping_add(attempt as usize) % self.slots.len()]
+    }
+
+    /// Hands the node to a pop that waits or waits for a pop to take it.
+    /// Returns true if a pop took the node, which the caller no longer owns then.
+    pub fn push(&self, node: *mut N, attempt: u32) -> bool {
+        let slot = self.slot(attempt);
+        match slot.state.load(Relaxed) {
+            REQUEST => slot.give(node),
+            EMPTY => {
+                //Acquire, synchronizes with the reset by the previous user of the slot.
+                if slot
+                    .state
+                    .compare_exchange(EMPTY, BUSY, Acquire, Relaxed)
+                    .is_err()
+                {
+                    return false;
+                }
+
+                slot.node.store(node, Relaxed);
+                //Release, the pop that takes the node must see it and its value.
+                slot.state.store(OFFER, Release);
+                slot.wait(OFFER).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Takes the node of a pusher that waits or waits for a pusher to put its node into the slot.
+    /// Returns the node, which the caller owns then, or None if no pusher came along.
+    pub fn pop(&self, attempt: u32) -> Option<*mut N> {
+        let slot = self.slot(attempt);
+        match slot.state.load(Relaxed) {
+            OFFER => {
+                //Acquire, synchronizes with the offer so we see the node and its value.
+                slot.state
+                    .compare_exchange(OFFER, BUSY, Acquire, Relaxed)
+                    .ok()?;
+                let node = slot.node.load(Relaxed);
+                //Release, the pusher resets the slot only after we read the node.
+                slot.state.store(DONE, Release);
+                Some(node)
+            }
+            EMPTY => slot.request(),
+            _ => None,
+        }
+    }
+
+    /// Panics if a thread still uses a slot.
+    #[cfg(any(test, feature = "debug-validate"))]
+    pub fn assert_invariants(&mut self) {
+        for slot in &mut *self.slots {
+            slot.assert_invariants();
+        }
+    }
+}
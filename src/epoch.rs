@@ -0,0 +1,446 @@
+//! Epoch based reclamation that frees removed nodes once every thread that was pinned when they were removed has unpinned.
+use crate::allocator::{alloc_in, Allocator};
+use crate::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+use crate::backoff::Backoff;
+use crate::error::PopContention;
+use crate::hazard::{FreeList, Unlock};
+use crate::reclaim::{EpochReclaim, Reclaimer, Retire};
+#[cfg(feature = "stats")]
+use crate::stats::ReclaimCounters;
+use crate::trace;
+use alloc::sync::Arc;
+use core::fmt::{Debug, Formatter};
+use core::marker::PhantomData;
+use core::ptr::{null_mut, NonNull};
+use core::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed, Release, SeqCst};
+use crossbeam_epoch::Guard;
+
+/// Stamp of nodes that are not the first node of a retired chain or whose stamp was not stored yet.
+const UNSTAMPED: usize = 0;
+
+/// Link of a node in the list of retired nodes of an `EpochList`, every node that can be retired contains one.
+pub struct Retired<N> {
+    /// the node that was retired before this node.
+    next: AtomicPtr<N>,
+    /// stamp of the retire that added the chain this node is the first node of.
+    stamp: AtomicUsize,
+}
+
+impl<N> Debug for Retired<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Retired")
+            .field("next", &self.next)
+            .field("stamp", &self.stamp)
+            .finish()
+    }
+}
+
+impl<N> Default for Retired<N> {
+    fn default() -> Self {
+        Self {
+            next: AtomicPtr::new(null_mut()),
+            stamp: AtomicUsize::new(UNSTAMPED),
+        }
+    }
+}
+
+/// Returns true if `stamp` is not newer than `safe`, stamps wrap around.
+const fn is_covered(stamp: usize, safe: usize) -> bool {
+    stamp != UNSTAMPED && safe.wrapping_sub(stamp) <= usize::MAX / 2
+}
+
+/// State that is shared with the deferred functions, they may run after the `EpochList` was dropped.
+#[derive(Debug)]
+struct Shared {
+    /// newest stamp whose deferred function ran.
+    safe: AtomicUsize,
+}
+
+impl Shared {
+    /// Records that the deferred function of the stamp ran.
+    fn cover(&self, stamp: usize) {
+        //Release, the thread that frees the nodes must see everything that happened before the deferred function ran.
+        _ = self.safe.fetch_update(Release, Relaxed, |safe| {
+            (!is_covered(stamp, safe)).then_some(stamp)
+        });
+    }
+}
+
+///
+/// List of nodes that were removed from a data structure but may still be accessed by other threads.
+///
+/// Threads pin the epoch of crossbeam-epoch while they access nodes.
+/// Retired nodes are linked through their `Retired` link, so retiring never allocates.
+/// Every retire takes a stamp from `stamps` after its chain was added to `retired_head`
+/// and defers a function that records the stamp in `Shared::safe` once every thread that was pinned has unpinned.
+///
+/// A chain whose stamp was taken before the stamp `s` was added to the list before `s` was taken,
+/// so its nodes were removed before the function of `s` was deferred and are safe to free once it ran.
+/// The same goes for all chains that are below it in the list, they were added before it.
+/// Once `safe` is `s` the list is therefore cut at the first node whose stamp is not newer than `s`.
+/// Chains above it may have newer stamps or have not stored their stamp yet, they are freed by a later cut.
+///
+/// The deferred functions only access `Shared`, which they keep alive, never the nodes or the allocator.
+/// Nodes whose deferred function did not run yet are freed by `free_all` when the data structure is dropped.
+///
+pub struct EpochList<N: Retire<EpochReclaim>> {
+    /// the most recently retired chain, older chains follow through the `Retired` links.
+    retired_head: AtomicPtr<N>,
+    /// source of the stamps of retired chains.
+    stamps: AtomicUsize,
+    /// amount of retired chains since the list was last cut or flushed.
+    retired_count: AtomicUsize,
+    /// amount of retired nodes that were not freed yet.
+    deferred: AtomicUsize,
+    /// the newest stamp the list was cut at.
+    cut_stamp: AtomicUsize,
+    /// provides mutual exclusion to cut the list.
+    retired_lock: AtomicBool,
+    /// `Shared` as created by `Arc::into_raw`, allocated by the first retire.
+    shared: AtomicPtr<Shared>,
+    /// freed nodes that are reused by `alloc` instead of allocating new ones.
+    free_nodes: FreeList<N>,
+    /// counters of retired and freed nodes.
+    #[cfg(feature = "stats")]
+    counters: ReclaimCounters,
+}
+
+impl<N: Retire<EpochReclaim>> EpochList<N> {
+    /// Returns `Shared`, allocates it if this is the first call.
+    fn shared(&self) -> *const Shared {
+        let shared = self.shared.load(Acquire);
+        if !shared.is_null() {
+            return shared;
+        }
+
+        let new = Arc::into_raw(Arc::new(Shared {
+            safe: AtomicUsize::new(UNSTAMPED),
+        }))
+        .cast_mut();
+
+        match self
+            .shared
+            .compare_exchange(null_mut(), new, AcqRel, Acquire)
+        {
+            Ok(_) => new,
+            Err(current) => {
+                //Safe, another thread was faster, ours was never shared.
+                unsafe {
+                    drop(Arc::from_raw(new));
+                }
+                current
+            }
+        }
+    }
+
+    /// Frees the retired chains starting at `node` and everything below them.
+    unsafe fn free_retired<A: Allocator>(&self, allocator: &A, mut node: *mut N) {
+        while let Some(node_ptr) = NonNull::new(node) {
+            let next = node_ptr.as_ref().link().next.load(Relaxed);
+            self.deferred.fetch_sub(1, Relaxed);
+            #[cfg(feature = "stats")]
+            self.counters.freed.add(1);
+            self.free_nodes.reclaim(allocator, node_ptr);
+            node = next;
+        }
+    }
+
+    /// Frees the retired chains starting at `retired_head` and returns all nodes that are kept for reuse to the allocator.
+    ///
+    /// # Safety
+    /// The chains must have been removed from the list and no thread may access their nodes anymore.
+    unsafe fn release<A: Allocator>(&self, allocator: &A, retired_head: *mut N) {
+        self.retired_count.store(0, Relaxed);
+        self.free_retired(allocator, retired_head);
+        self.free_nodes.clear(allocator);
+    }
+
+    /// Takes a new stamp and defers the function that records it.
+    fn defer_cover(&self) -> usize {
+        let shared = self.shared();
+        //AcqRel, a thread that takes a newer stamp must see the chains that were added before ours.
+        let mut stamp = self.stamps.fetch_add(1, AcqRel).wrapping_add(1);
+        if stamp == UNSTAMPED {
+            stamp = self.stamps.fetch_add(1, AcqRel).wrapping_add(1);
+        }
+
+        //Safe, the function only accesses Shared, which it keeps alive until it ran.
+        unsafe {
+            Arc::increment_strong_count(shared);
+            crossbeam_epoch::pin().defer_unchecked(move || {
+                Arc::from_raw(shared).cover(stamp);
+            });
+        }
+
+        stamp
+    }
+}
+
+impl<N: Retire<EpochReclaim>> Reclaimer<N> for EpochList<N> {
+    type Entered<'a, A: Allocator + 'a>
+        = Entered<'a, N>
+    where
+        Self: 'a;
+
+    const NEW: Self = Self {
+        retired_head: AtomicPtr::new(null_mut()),
+        stamps: AtomicUsize::new(UNSTAMPED),
+        retired_count: AtomicUsize::new(0),
+        deferred: AtomicUsize::new(0),
+        cut_stamp: AtomicUsize::new(UNSTAMPED),
+        retired_lock: AtomicBool::new(false),
+        shared: AtomicPtr::new(null_mut()),
+        free_nodes: FreeList::new(),
+        #[cfg(feature = "stats")]
+        counters: ReclaimCounters::new(),
+    };
+
+    fn alloc<A: Allocator>(&self, allocator: &A, node: N) -> NonNull<N> {
+        self.free_nodes.alloc(allocator, node)
+    }
+
+    fn try_alloc<A: Allocator>(&self, allocator: &A, node: N) -> Result<NonNull<N>, N> {
+        self.free_nodes.try_alloc(allocator, node)
+    }
+
+    /// Retiring needs no bookkeeping nodes. The free list may exceed its capacity until the reserved nodes were used.
+    fn reserve<A: Allocator>(&self, allocator: &A, additional: usize, empty: impl Fn() -> N) {
+        for _ in 0..additional {
+            unsafe {
+                self.free_nodes.push(alloc_in(allocator, empty()));
+            }
+        }
+    }
+
+    fn free_node_count(&self) -> usize {
+        self.free_nodes.len()
+    }
+
+    fn deferred_count(&self) -> usize {
+        self.deferred.load(Relaxed)
+    }
+
+    /// crossbeam-epoch does not count the pinned threads.
+    fn entered_count(&self) -> usize {
+        0
+    }
+
+    /// Pinned threads are only known to crossbeam-epoch.
+    fn is_quiescent(&self) -> bool {
+        false
+    }
+
+    /// Only `Shared`, the deferred functions are allocated by crossbeam-epoch.
+    fn bookkeeping_bytes(&self) -> usize {
+        if self.shared.load(Relaxed).is_null() {
+            return 0;
+        }
+
+        size_of::<Shared>()
+    }
+
+    #[cfg(feature = "stats")]
+    fn counters(&self) -> &ReclaimCounters {
+        &self.counters
+    }
+
+    /// Pins the calling thread, never fails since crossbeam-epoch does not count the pinned threads.
+    fn try_enter<'a, A: Allocator + 'a>(
+        &'a self,
+        allocator: &'a A,
+        hazard_limit: usize,
+    ) -> Result<Entered<'a, N>, PopContention> {
+        let guard = crossbeam_epoch::pin();
+        if self.retired_count.load(Relaxed) > hazard_limit {
+            //Many chains were retired without being freed, their deferred functions may still wait in the
+            //thread local storage of the threads that retired them. Move ours to the global queue.
+            //Flushing allocates, so the next flush waits for as many retires again if nothing could be freed.
+            #[cfg(feature = "stats")]
+            self.counters.limit_hits.add(1);
+            trace::hazard_limit(self.deferred.load(Relaxed), hazard_limit);
+            self.retired_count.store(0, Relaxed);
+            guard.flush();
+            self.collect(allocator);
+        }
+
+        Ok(Entered {
+            _guard: guard,
+            _list: PhantomData,
+        })
+    }
+
+    unsafe fn retire<A: Allocator>(&self, allocator: &A, node: *mut N, count: usize) {
+        #[cfg(feature = "stats")]
+        self.counters.retired.add(count);
+
+        let mut last = &*node;
+        for _ in 1..count {
+            let next = last.next();
+            last.link().next.store(next, Relaxed);
+            last = &*next;
+        }
+
+        //Relaxed, incremented before the chain is linked so the thread that frees it cannot decrement it below 0.
+        self.deferred.fetch_add(count, Relaxed);
+
+        let mut head = self.retired_head.load(Relaxed);
+        loop {
+            last.link().next.store(head, Relaxed);
+            //Release, the thread that cuts the list must see the links of the chain.
+            match self
+                .retired_head
+                .compare_exchange_weak(head, node, Release, Relaxed)
+            {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+
+        let stamp = self.defer_cover();
+        (*node).link().stamp.store(stamp, Relaxed);
+        self.retired_count.fetch_add(1, Relaxed);
+        self.collect(allocator);
+    }
+
+    /// Frees the retired chains whose deferred function ran, directly or through a newer one.
+    /// Gives up if another thread is currently freeing, the nodes are then freed by a later call.
+    fn collect<A: Allocator>(&self, allocator: &A) {
+        let shared = self.shared.load(Acquire);
+        //Safe, once allocated the shared state lives as long as the list.
+        let Some(shared) = (unsafe { shared.as_ref() }) else {
+            return;
+        };
+
+        //Acquire, synchronizes with the deferred function so the threads that accessed the nodes are done with them.
+        let safe = shared.safe.load(Acquire);
+        if safe == self.cut_stamp.load(Relaxed) || self.retired_lock.swap(true, Acquire) {
+            return;
+        }
+
+        let _unlock = Unlock {
+            lock: &self.retired_lock,
+        };
+
+        self.cut_stamp.store(safe, Relaxed);
+        self.retired_count.store(0, Relaxed);
+
+        'cut: loop {
+            //Acquire, synchronizes with the retire that added the chain so we see its links.
+            let head = self.retired_head.load(Acquire);
+            let mut above: *mut N = null_mut();
+            let mut cur = head;
+            //Safe, only the thread that holds the lock frees retired nodes.
+            while let Some(node) = unsafe { cur.as_ref() } {
+                let link = node.link();
+                if !is_covered(link.stamp.load(Relaxed), safe) {
+                    above = cur;
+                    cur = link.next.load(Relaxed);
+                    continue;
+                }
+
+                //Safe, see above.
+                match unsafe { above.as_ref() } {
+                    Some(above) => above.link().next.store(null_mut(), Relaxed),
+                    None => {
+                        if self
+                            .retired_head
+                            .compare_exchange(head, null_mut(), Relaxed, Relaxed)
+                            .is_err()
+                        {
+                            //A chain was retired in the meantime, the node is no longer the head.
+                            continue 'cut;
+                        }
+                    }
+                }
+
+                //Safe, the deferred function of this chain or of a newer one ran.
+                unsafe {
+                    self.free_retired(allocator, cur);
+                }
+                return;
+            }
+
+            return;
+        }
+    }
+
+    /// Waits for another thread that is currently freeing.
+    fn quiesce<A: Allocator>(&self, allocator: &A) {
+        let mut backoff = Backoff::new();
+        while self.retired_lock.swap(true, Acquire) {
+            backoff.snooze();
+        }
+
+        let _unlock = Unlock {
+            lock: &self.retired_lock,
+        };
+
+        let retired_head = self.retired_head.swap(null_mut(), SeqCst);
+        if !retired_head.is_null() {
+            //All chains in the list were added before this stamp was taken.
+            let stamp = self.defer_cover();
+            //Safe, we just allocated it.
+            let shared = unsafe { &*self.shared() };
+            let mut backoff = Backoff::new();
+            while !is_covered(stamp, shared.safe.load(Acquire)) {
+                //Moves our deferred function to the global queue and runs the functions of all threads that unpinned.
+                crossbeam_epoch::pin().flush();
+                backoff.snooze();
+            }
+        }
+
+        //Safe, no thread can access the nodes anymore.
+        unsafe {
+            self.release(allocator, retired_head);
+        }
+    }
+
+    unsafe fn quiesce_unchecked<A: Allocator>(&self, allocator: &A) {
+        self.release(allocator, self.retired_head.swap(null_mut(), SeqCst));
+    }
+
+    #[cfg(any(test, feature = "debug-validate"))]
+    fn assert_invariants(&mut self, mut visit: impl FnMut(*mut N)) {
+        assert!(
+            !*self.retired_lock.get_mut(),
+            "retired list is still locked"
+        );
+        let mut count = 0;
+        let mut node = *self.retired_head.get_mut();
+        //Safe, retired nodes stay allocated until they are freed, which needs the list.
+        while let Some(node_ref) = unsafe { node.as_ref() } {
+            visit(node);
+            count += 1;
+            node = node_ref.link().next.load(Relaxed);
+        }
+
+        assert_eq!(
+            count,
+            *self.deferred.get_mut(),
+            "deferred does not match the retired nodes"
+        );
+        self.free_nodes.assert_invariants(visit);
+    }
+
+    /// Nodes whose deferred function did not run yet are freed as well, the function only accesses `Shared`.
+    unsafe fn free_all<A: Allocator>(&mut self, allocator: &A) {
+        let retired_head = *self.retired_head.get_mut();
+        self.free_retired(allocator, retired_head);
+        self.free_nodes.free_all(allocator);
+
+        let shared = *self.shared.get_mut();
+        if !shared.is_null() {
+            drop(Arc::from_raw(shared));
+        }
+    }
+}
+
+/// Pinned epoch of a thread that accesses the nodes of the data structure, returned by `EpochList::enter`.
+/// Dropping it unpins the thread, also if the thread unwinds.
+#[must_use]
+pub struct Entered<'a, N: Retire<EpochReclaim>> {
+    /// the pinned epoch.
+    _guard: Guard,
+    /// the list the thread entered.
+    _list: PhantomData<&'a EpochList<N>>,
+}
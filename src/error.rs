@@ -0,0 +1,64 @@
+//! Errors of the fallible fns of `AtomicLifo`.
+use core::fmt::{Debug, Display, Formatter};
+
+/// Error returned by `AtomicLifo::try_push` if the node for the value could not be allocated.
+///
+/// Contains the value that was not pushed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PushError<T>(pub T);
+
+impl<T> PushError<T> {
+    /// Returns the value that was not pushed.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Debug for PushError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PushError").finish_non_exhaustive()
+    }
+}
+
+impl<T> Display for PushError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("failed to allocate the node for the pushed value")
+    }
+}
+
+impl<T> core::error::Error for PushError<T> {}
+
+/// Error returned by `AtomicLifo::try_pop` if too many threads are popping, peeking or traversing the lifo at the same time.
+///
+/// The lifo counts these threads to know when popped nodes can be freed,
+/// the count is not incremented beyond `isize::MAX` so it can never overflow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PopContention;
+
+impl Display for PopContention {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("too many threads calling pop concurrently")
+    }
+}
+
+impl core::error::Error for PopContention {}
+
+/// Error returned by `AtomicLifo::pop_if_eq` if the top of the lifo was not popped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PopIfEqError {
+    /// The lifo was empty.
+    Empty,
+    /// The top element was not equal to the expected value.
+    Mismatch,
+}
+
+impl Display for PopIfEqError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the lifo is empty"),
+            Self::Mismatch => f.write_str("the top of the lifo is not the expected value"),
+        }
+    }
+}
+
+impl core::error::Error for PopIfEqError {}
@@ -0,0 +1,334 @@
+//! Lock free fifo queue that uses the same allocators, reclamation strategies and hooks as `AtomicLifo`.
+use crate::allocator::{free_in, Allocator, Global};
+#[cfg(not(loom))]
+use crate::atomic::WithMut;
+use crate::atomic::AtomicPtr;
+use crate::hazard::{Linked, DEFAULT_HAZARD_LIMIT};
+use crate::reclaim::{Reclaimer, Retire};
+use crate::{HazardReclaim, LifoHooks, Reclaim};
+use core::cell::UnsafeCell;
+use core::fmt::{Debug, Formatter};
+use core::mem::MaybeUninit;
+use core::ptr::null_mut;
+use core::sync::atomic::Ordering::SeqCst;
+
+/// Fifo node
+struct FifoNode<T: Send, R: Reclaim> {
+    /// the next node, pushed after this node.
+    next: AtomicPtr<Self>,
+    /// the value, uninitialized or already taken once this node is the dummy node at the head of the fifo.
+    value: UnsafeCell<MaybeUninit<T>>,
+    /// link used by the strategy to retire the node.
+    link: R::Link<Self>,
+}
+
+impl<T: Send, R: Reclaim> Linked for FifoNode<T, R> {
+    fn next(&self) -> *mut Self {
+        self.next.load(SeqCst)
+    }
+
+    fn set_next(&mut self, next: *mut Self) {
+        self.next.with_mut(|current| *current = next);
+    }
+}
+
+impl<T: Send, R: Reclaim> Retire<R> for FifoNode<T, R> {
+    fn link(&self) -> &R::Link<Self> {
+        &self.link
+    }
+}
+
+impl<T: Send, R: Reclaim> FifoNode<T, R> {
+    /// Constructs a new node that does not have a next node.
+    fn new(value: MaybeUninit<T>) -> Self {
+        Self {
+            next: AtomicPtr::new(null_mut()),
+            value: UnsafeCell::new(value),
+            link: R::Link::default(),
+        }
+    }
+}
+
+///
+/// Thread Safe FIFO Queue.
+///
+/// This is a Michael-Scott queue, the head always points to a dummy node whose value was already popped.
+/// The dummy node is allocated by the first push, so the queue can be constructed in a const context.
+/// Values are stored inline in the nodes, so every push allocates a single node.
+/// Nodes are allocated by `A` and popped nodes are freed using the strategy `R`, the same way as in `AtomicLifo`.
+/// Unlike `AtomicLifo::push`, `push` also has to access nodes that may be popped concurrently
+/// and therefore takes part in the bookkeeping of the strategy.
+///
+pub struct AtomicFifo<
+    T: Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// nodes that were removed from the fifo but may still be accessed by other threads.
+    hazard: R::List<FifoNode<T, R>>,
+    /// allocator of all nodes and of the bookkeeping of popped nodes.
+    allocator: A,
+    /// the dummy node before the oldest element.
+    head: AtomicPtr<FifoNode<T, R>>,
+    /// the newest node or a node shortly before it.
+    tail: AtomicPtr<FifoNode<T, R>>,
+    /// callbacks for the elements that are pushed and popped.
+    hooks: H,
+}
+
+//Values only ever move between threads, see `AtomicLifo`.
+unsafe impl<T: Send, A: Allocator + Clone + Send, R: Reclaim, H: LifoHooks<T>> Send
+    for AtomicFifo<T, A, R, H>
+{
+}
+unsafe impl<T: Send, A: Allocator + Clone + Sync, R: Reclaim, H: LifoHooks<T>> Sync
+    for AtomicFifo<T, A, R, H>
+{
+}
+
+impl<T: Send> Default for AtomicFifo<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug for AtomicFifo<T, A, R, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AtomicFifo").finish_non_exhaustive()
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop for AtomicFifo<T, A, R, H> {
+    fn drop(&mut self) {
+        let dummy = self.head.with_mut(|head| *head);
+        if dummy.is_null() {
+            return;
+        }
+
+        unsafe {
+            let mut current_free = (*dummy).next.with_mut(|next| *next);
+            free_in(&self.allocator, dummy);
+            while !current_free.is_null() {
+                let node = current_free;
+                current_free = (*node).next.with_mut(|next| *next);
+                (*node).value.get_mut().assume_init_drop();
+                free_in(&self.allocator, node);
+            }
+
+            self.hazard.free_all(&self.allocator);
+        }
+    }
+}
+
+impl<T: Send> AtomicFifo<T> {
+    const_fn! {
+        /// Constructs a new empty `AtomicFifo`
+        #[must_use]
+        pub fn new() -> Self {
+            Self::new_in(Global)
+        }
+    }
+}
+
+impl<T: Send, A: Allocator + Clone> AtomicFifo<T, A> {
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicFifo` whose nodes are allocated by the given allocator.
+        ///
+        /// All allocations of the fifo, including the bookkeeping of popped nodes, go through the allocator
+        /// and are returned to it at the latest when the fifo is dropped.
+        ///
+        #[must_use]
+        pub fn new_in(allocator: A) -> Self {
+            Self::with_hooks_in((), HazardReclaim, allocator)
+        }
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> AtomicFifo<T, A, R, H> {
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicFifo` whose nodes are allocated by the given allocator,
+        /// that frees popped nodes using the given strategy and passes every pushed and popped element to the hooks.
+        ///
+        /// See `AtomicLifo::with_hooks_in`, `on_empty_pop` is called whenever `pop` found the fifo empty.
+        ///
+        #[must_use]
+        pub fn with_hooks_in(hooks: H, reclaim: R, allocator: A) -> Self {
+            //The strategy is a marker, only its type is used.
+            core::mem::forget(reclaim);
+            Self {
+                #[cfg(not(loom))]
+                hazard: R::List::NEW,
+                #[cfg(loom)]
+                hazard: R::List::new(),
+                allocator,
+                head: AtomicPtr::new(null_mut()),
+                tail: AtomicPtr::new(null_mut()),
+                hooks,
+            }
+        }
+    }
+
+    /// Returns the hooks this fifo passes its elements to, see `with_hooks_in`.
+    #[must_use]
+    pub const fn hooks(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Returns the tail, allocates the dummy node if this is the first push.
+    fn tail_or_init(&self) -> *mut FifoNode<T, R> {
+        let tail = self.tail.load(SeqCst);
+        if !tail.is_null() {
+            return tail;
+        }
+
+        let mut head = self.head.load(SeqCst);
+        if head.is_null() {
+            let dummy = self
+                .hazard
+                .alloc(&self.allocator, FifoNode::new(MaybeUninit::uninit()))
+                .as_ptr();
+            match self
+                .head
+                .compare_exchange(null_mut(), dummy, SeqCst, SeqCst)
+            {
+                Ok(_) => head = dummy,
+                Err(existing) => {
+                    //Safe, the dummy was never visible to other threads.
+                    unsafe {
+                        free_in(&self.allocator, dummy);
+                    }
+                    head = existing;
+                }
+            }
+        }
+
+        //The head cannot have moved on yet, that requires a push and every push sets the tail first.
+        _ = self.tail.compare_exchange(null_mut(), head, SeqCst, SeqCst);
+        self.tail.load(SeqCst)
+    }
+
+    ///
+    /// Pushes a value to the back of the fifo queue.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn push(&self, value: T) {
+        self.hooks.on_push(&value);
+        let node = self
+            .hazard
+            .alloc(&self.allocator, FifoNode::new(MaybeUninit::new(value)))
+            .as_ptr();
+
+        let _entered = self.hazard.enter(&self.allocator, DEFAULT_HAZARD_LIMIT);
+
+        loop {
+            let tail = self.tail_or_init();
+            //Safe, the tail cannot be freed before we leave.
+            let tail_ref = unsafe { &*tail };
+            let next = tail_ref.next.load(SeqCst);
+            if tail != self.tail.load(SeqCst) {
+                continue;
+            }
+
+            if !next.is_null() {
+                //The tail is lagging behind, help the other push to move it.
+                _ = self.tail.compare_exchange(tail, next, SeqCst, SeqCst);
+                continue;
+            }
+
+            if tail_ref
+                .next
+                .compare_exchange(null_mut(), node, SeqCst, SeqCst)
+                .is_err()
+            {
+                continue;
+            }
+
+            //If this fails then some other thread already moved it for us.
+            _ = self.tail.compare_exchange(tail, node, SeqCst, SeqCst);
+            return;
+        }
+    }
+
+    ///
+    /// Pops the front of the fifo queue, which is the oldest element.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop(&self) -> Option<T> {
+        if self.head.load(SeqCst).is_null() {
+            //Fast path, nothing was ever pushed.
+            self.hooks.on_empty_pop();
+            return None;
+        }
+
+        let _entered = self.hazard.enter(&self.allocator, DEFAULT_HAZARD_LIMIT);
+
+        loop {
+            let head = self.head.load(SeqCst);
+            let tail = self.tail.load(SeqCst);
+            //Safe, the head is never null once it was set and it cannot be freed before we leave.
+            let next = unsafe { (*head).next.load(SeqCst) };
+            if head != self.head.load(SeqCst) {
+                continue;
+            }
+
+            //Safe, same as the head.
+            let Some(next_ref) = (unsafe { next.as_ref() }) else {
+                self.hooks.on_empty_pop();
+                return None;
+            };
+
+            if head == tail {
+                //The tail is lagging behind, move it before the head can overtake it.
+                _ = self.tail.compare_exchange(tail, next, SeqCst, SeqCst);
+                continue;
+            }
+
+            if self
+                .head
+                .compare_exchange(head, next, SeqCst, SeqCst)
+                .is_err()
+            {
+                continue;
+            }
+
+            //Safe, only the thread that moved the head to next may take the value of next, which is now the dummy node.
+            //The former dummy was removed with SeqCst and its value was taken by the pop that made it the dummy.
+            let value = unsafe {
+                let value = (*next_ref.value.get()).assume_init_read();
+                self.hazard.retire(&self.allocator, head, 1);
+                value
+            };
+
+            self.hooks.on_pop(&value);
+            return Some(value);
+        }
+    }
+
+    ///
+    /// Returns true if the fifo contains no elements.
+    ///
+    /// This is only a snapshot, other threads may push or pop elements at any time
+    /// so the returned value may already be stale once this fn returns.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        if self.head.load(SeqCst).is_null() {
+            return true;
+        }
+
+        let _entered = self.hazard.enter(&self.allocator, DEFAULT_HAZARD_LIMIT);
+
+        //Safe, the head is never null once it was set and it cannot be freed before we leave.
+        unsafe { (*self.head.load(SeqCst)).next.load(SeqCst).is_null() }
+    }
+}
@@ -0,0 +1,138 @@
+//! Futures that wait for elements of an `AtomicLifo`.
+use crate::allocator::{Allocator, Global};
+use crate::waker::WakerSlot;
+use crate::{AtomicLifo, HazardReclaim, LifoHooks, Reclaim};
+use core::fmt::{Debug, Formatter};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::{FusedStream, Stream};
+
+/// Future that pops an element from an `AtomicLifo` once one is available.
+///
+/// Created by `AtomicLifo::pop_async`.
+/// Polling this future registers the waker of the task, it is woken once an element is pushed.
+/// The future holds a single waker, polling it again replaces the waker of the previous poll.
+/// Dropping a future that was woken but did not pop an element wakes the next waiting task instead.
+pub struct PopFuture<
+    'a,
+    T: Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// the lifo we pop from.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// holds the waker of the last poll, acquired the first time we had to wait.
+    slot: Option<&'a WakerSlot>,
+}
+
+impl<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> PopFuture<'a, T, A, R, H> {
+    /// Constructs a new `PopFuture` that pops from the given lifo.
+    pub(crate) const fn new(lifo: &'a AtomicLifo<T, A, R, H>) -> Self {
+        Self { lifo, slot: None }
+    }
+}
+
+impl<T: Sync + Send + Debug, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for PopFuture<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PopFuture")
+            .field("lifo", &self.lifo)
+            .finish()
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Future
+    for PopFuture<'_, T, A, R, H>
+{
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let value = core::task::ready!(this.lifo.poll_pop(cx, &mut this.slot));
+        if let Some(slot) = this.slot.take() {
+            //Safe, we acquired the slot. We popped an element, so nothing has to be passed on.
+            unsafe { this.lifo.release_waker(slot, true) };
+        }
+
+        Poll::Ready(value)
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop
+    for PopFuture<'_, T, A, R, H>
+{
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot.take() {
+            //Safe, we acquired the slot.
+            unsafe { self.lifo.release_waker(slot, false) };
+        }
+    }
+}
+
+/// Stream that pops elements from an `AtomicLifo` as they become available.
+///
+/// Created by `AtomicLifo::stream`. The stream never terminates, an empty lifo makes it wait for the next element.
+/// Polling this stream registers the waker of the task, it is woken once an element is pushed.
+/// Like `PopFuture` the stream holds a single waker for all its polls and passes a wake on when it is dropped.
+pub struct LifoStream<
+    'a,
+    T: Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// the lifo we pop from.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// holds the waker of the last poll, acquired the first time we had to wait.
+    slot: Option<&'a WakerSlot>,
+}
+
+impl<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> LifoStream<'a, T, A, R, H> {
+    /// Constructs a new `LifoStream` that pops from the given lifo.
+    pub(crate) const fn new(lifo: &'a AtomicLifo<T, A, R, H>) -> Self {
+        Self { lifo, slot: None }
+    }
+}
+
+impl<T: Sync + Send + Debug, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for LifoStream<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LifoStream")
+            .field("lifo", &self.lifo)
+            .finish()
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Stream
+    for LifoStream<'_, T, A, R, H>
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.lifo.poll_pop(cx, &mut this.slot).map(Some)
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop
+    for LifoStream<'_, T, A, R, H>
+{
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot.take() {
+            //Safe, we acquired the slot.
+            unsafe { self.lifo.release_waker(slot, false) };
+        }
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> FusedStream
+    for LifoStream<'_, T, A, R, H>
+{
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,74 @@
+//! Guard that pushes a popped value back unless it is committed.
+use crate::allocator::{Allocator, Global};
+use crate::{AtomicLifo, HazardReclaim, LifoHooks, Reclaim};
+use core::fmt::{Debug, Formatter};
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+/// Value that was popped from an `AtomicLifo` and is pushed back once this guard is dropped.
+///
+/// Created by `AtomicLifo::pop_guarded`.
+pub struct PopGuard<
+    'a,
+    T: Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// the lifo the value is pushed back to.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// the value, only taken in drop or commit.
+    value: ManuallyDrop<T>,
+}
+
+impl<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> PopGuard<'a, T, A, R, H> {
+    /// Constructs a new `PopGuard` for a value that was popped from the lifo.
+    pub(crate) const fn new(lifo: &'a AtomicLifo<T, A, R, H>, value: T) -> Self {
+        Self {
+            lifo,
+            value: ManuallyDrop::new(value),
+        }
+    }
+
+    /// Takes the value out of the guard, it is not pushed back.
+    #[must_use]
+    pub fn commit(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        //Safe, the guard is never dropped so the value is not taken again.
+        unsafe { ManuallyDrop::take(&mut this.value) }
+    }
+}
+
+impl<T: Send + Debug, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for PopGuard<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("PopGuard").field(&*self.value).finish()
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Deref
+    for PopGuard<'_, T, A, R, H>
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> DerefMut
+    for PopGuard<'_, T, A, R, H>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop for PopGuard<'_, T, A, R, H> {
+    fn drop(&mut self) {
+        //Safe, this is the only place besides commit that takes the value and commit never drops the guard.
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        self.lifo.push(value);
+    }
+}
@@ -0,0 +1,942 @@
+//! Hazard list that frees removed nodes once no thread can access them anymore.
+use crate::allocator::{alloc_in, free_in, try_alloc_in, Allocator};
+#[cfg(not(loom))]
+use crate::atomic::WithMut;
+#[cfg(target_has_atomic = "64")]
+use crate::atomic::AtomicU64;
+use crate::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+use crate::backoff::Backoff;
+use crate::error::PopContention;
+use crate::reclaim::Reclaimer;
+#[cfg(feature = "stats")]
+use crate::stats::ReclaimCounters;
+use crate::trace;
+#[cfg(any(test, feature = "debug-validate"))]
+use alloc::collections::BTreeSet;
+use core::ptr::{null_mut, NonNull};
+use core::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
+
+/// Maximum amount of freed nodes a `HazardList` keeps for reuse, further nodes are returned to the allocator.
+const FREE_LIST_CAPACITY: usize = 1024;
+
+/// Default amount of retired nodes since the hazard list was last freed after which `enter` helps freeing it.
+pub const DEFAULT_HAZARD_LIMIT: usize = 500_000;
+
+/// Maximum amount of threads that may be counted in one counter of a `HazardList` at the same time.
+/// Threads that would exceed it undo their increment, the counter stays far from overflowing since each of them
+/// only adds 1 for a short time, like the reference count of `Arc`.
+pub const MAX_ENTERED: usize = isize::MAX as usize;
+
+/// Generation of the hazard nodes, see `HazardList` for its width.
+#[cfg(target_has_atomic = "64")]
+type Generation = u64;
+/// Generation of the hazard nodes, see `HazardList` for its width.
+#[cfg(not(target_has_atomic = "64"))]
+type Generation = usize;
+
+/// Atomic that holds the current `Generation`.
+#[cfg(target_has_atomic = "64")]
+type AtomicGeneration = AtomicU64;
+/// Atomic that holds the current `Generation`.
+#[cfg(not(target_has_atomic = "64"))]
+type AtomicGeneration = AtomicUsize;
+
+/// Returns the index of the counter of the threads that entered in the generation.
+const fn parity(generation: Generation) -> usize {
+    (generation & 1) as usize
+}
+
+/// Node of a linked list whose nodes can be freed by a `HazardList`.
+pub trait Linked {
+    /// Returns the next node of the list.
+    fn next(&self) -> *mut Self;
+
+    /// Sets the next node of the list, used to link freed nodes in the free list.
+    fn set_next(&mut self, next: *mut Self);
+}
+
+/// Node that contains normal nodes that should be freed later.
+#[derive(Debug)]
+struct HazardNode<N: Linked> {
+    /// the generation of this hazard node
+    generation: Generation,
+    /// the node we want to free later
+    node: *mut N,
+    /// amount of nodes starting at node that are freed by following their next pointers.
+    count: usize,
+    /// next hazard node
+    next: *mut Self,
+}
+
+impl<N: Linked> Linked for HazardNode<N> {
+    fn next(&self) -> *mut Self {
+        self.next
+    }
+
+    fn set_next(&mut self, next: *mut Self) {
+        self.next = next;
+    }
+}
+
+/// Releases a spin lock once dropped, also if the thread holding the lock unwinds.
+pub struct Unlock<'a> {
+    /// the locked lock.
+    pub(crate) lock: &'a AtomicBool,
+}
+
+impl Drop for Unlock<'_> {
+    fn drop(&mut self) {
+        self.lock.store(false, Release);
+    }
+}
+
+/// Lock free list of allocations of freed nodes that can be reused instead of allocating new nodes.
+pub struct FreeList<N: Linked> {
+    /// the first freed node.
+    head: AtomicPtr<N>,
+    /// amount of nodes in the list.
+    len: AtomicUsize,
+    /// only one thread at a time may take nodes out of the list, this prevents the ABA problem.
+    lock: AtomicBool,
+}
+
+impl<N: Linked> FreeList<N> {
+    const_fn! {
+        /// Constructs a new empty `FreeList`
+        pub(crate) fn new() -> Self {
+            Self {
+                head: AtomicPtr::new(null_mut()),
+                len: AtomicUsize::new(0),
+                lock: AtomicBool::new(false),
+            }
+        }
+    }
+
+    /// Frees all nodes in the list.
+    ///
+    /// # Safety
+    /// All nodes must have been allocated by the allocator.
+    pub(crate) unsafe fn free_all<A: Allocator>(&mut self, allocator: &A) {
+        let mut cur_free = self.head.with_mut(|head| *head);
+        while !cur_free.is_null() {
+            //The nodes in the list are initialized, their content is just no longer used.
+            let next = (*cur_free).next();
+            free_in(allocator, cur_free);
+            cur_free = next;
+        }
+    }
+
+    /// Moves the node into memory of the allocator, reusing the allocation of a freed node if there is one.
+    pub(crate) fn alloc<A: Allocator>(&self, allocator: &A, node: N) -> NonNull<N> {
+        let Some(reused) = self.take() else {
+            return alloc_in(allocator, node);
+        };
+
+        //Safe, the freed node is no longer accessed by any other thread and its old content was already taken.
+        unsafe {
+            reused.write(node);
+        }
+
+        reused
+    }
+
+    /// Same as `alloc` but returns the node if the allocator failed to allocate.
+    pub(crate) fn try_alloc<A: Allocator>(&self, allocator: &A, node: N) -> Result<NonNull<N>, N> {
+        let Some(reused) = self.take() else {
+            return try_alloc_in(allocator, node);
+        };
+
+        //Safe, see alloc.
+        unsafe {
+            reused.write(node);
+        }
+
+        Ok(reused)
+    }
+
+    /// Returns the amount of nodes in the list.
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Relaxed)
+    }
+
+    /// Passes every node in the list to `visit` and panics if the length or the lock of the list is wrong.
+    /// `visit` has to panic on a node it has seen before, otherwise a cycle in the list is never left.
+    #[cfg(any(test, feature = "debug-validate"))]
+    pub(crate) fn assert_invariants(&mut self, mut visit: impl FnMut(*mut N)) {
+        assert!(!self.lock.load(Relaxed), "free list is still locked");
+        let mut count = 0;
+        let mut node = self.head.with_mut(|head| *head);
+        //Safe, nodes stay allocated while they are in the list.
+        while let Some(node_ref) = unsafe { node.as_ref() } {
+            visit(node);
+            count += 1;
+            node = node_ref.next();
+        }
+
+        assert_eq!(
+            count,
+            self.len.with_mut(|len| *len),
+            "len of the free list does not match its nodes"
+        );
+    }
+
+    /// Takes a freed node out of the list.
+    /// Gives up if another thread is currently taking a node, the caller then uses the allocator instead.
+    fn take(&self) -> Option<NonNull<N>> {
+        //Relaxed, only a hint to skip the lock, the list is checked again after locking.
+        if self.head.load(Relaxed).is_null() || self.lock.swap(true, Acquire) {
+            return None;
+        }
+
+        let _unlock = Unlock { lock: &self.lock };
+
+        //Acquire, synchronizes with the push of the node so we see its next pointer.
+        let mut head = self.head.load(Acquire);
+        loop {
+            let node = NonNull::new(head)?;
+            //Safe, nodes are only removed from the list by the thread that holds the lock.
+            let next = unsafe { node.as_ref().next() };
+            match self
+                .head
+                .compare_exchange_weak(head, next, Relaxed, Acquire)
+            {
+                Ok(_) => {
+                    //Relaxed, the len only decides whether nodes are kept, it never guards an access to them.
+                    self.len.fetch_sub(1, Relaxed);
+                    return Some(node);
+                }
+                //Only a concurrent push can have changed the head.
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Returns all nodes in the list to the allocator, waits if another thread is currently taking a node.
+    ///
+    /// # Safety
+    /// All nodes must have been allocated by the allocator.
+    pub(crate) unsafe fn clear<A: Allocator>(&self, allocator: &A) {
+        let mut backoff = Backoff::new();
+        while self.lock.swap(true, Acquire) {
+            backoff.snooze();
+        }
+
+        let _unlock = Unlock { lock: &self.lock };
+
+        //Acquire, synchronizes with the pushes of the nodes so we see their next pointers.
+        //Only the thread that holds the lock removes nodes, so none of them can be taken while we free them.
+        let mut node = self.head.swap(null_mut(), Acquire);
+        while let Some(node_ref) = node.as_ref() {
+            let next = node_ref.next();
+            self.len.fetch_sub(1, Relaxed);
+            free_in(allocator, node);
+            node = next;
+        }
+    }
+
+    /// Adds a node that is no longer accessed by any thread to the list or frees it if the list is full.
+    pub(crate) unsafe fn reclaim<A: Allocator>(&self, allocator: &A, node: NonNull<N>) {
+        if self.len.load(Relaxed) >= FREE_LIST_CAPACITY {
+            free_in(allocator, node.as_ptr());
+            return;
+        }
+
+        self.push(node);
+    }
+
+    /// Adds a node that is no longer accessed by any thread to the list regardless of the capacity.
+    pub(crate) unsafe fn push(&self, mut node: NonNull<N>) {
+        self.len.fetch_add(1, Relaxed);
+        let mut head = self.head.load(Relaxed);
+        loop {
+            node.as_mut().set_next(head);
+            //Release, the thread that takes the node must see the next pointer and must not access the node
+            //before everything we did with it happened.
+            match self
+                .head
+                .compare_exchange_weak(head, node.as_ptr(), Release, Relaxed)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+}
+
+///
+/// List of nodes that were removed from a data structure but may still be accessed by other threads.
+///
+/// Threads that access nodes are counted in one of two counters, the one of the parity of the generation they entered in.
+/// A thread that enters in generation `g` re-checks the generation after it was counted and enters again if it changed.
+/// The generation is advanced from `k` to `k + 1` by any thread that leaves and sees that no thread is counted for the parity of `k + 1`.
+/// While a thread that entered in `g` is still counted the generation therefore never exceeds `g + 1`.
+///
+/// A node retired in generation `r` was loaded by threads that entered in a generation `g <= r`.
+/// Once the generation was advanced to `r + 2` every such thread has left:
+/// the advance to `g + 2` had to see the counter of the parity of `g` at 0 after such a thread was counted.
+/// Retiring threads load the generation before they push to the hazard list, so nodes that were pushed
+/// earlier may have a generation that is one larger than nodes pushed later. Once the generation was advanced
+/// from `k` to `k + 1` the list is therefore cut at the first node of a generation `<= k - 2`, all nodes behind it
+/// are of a generation `<= k - 1` and were retired at least two generations ago.
+/// The thread that advanced the generation frees them, if another thread is freeing at the same time they are freed later.
+/// Every node is freed exactly once, so the cost of freeing is constant per retired node.
+/// No thread ever waits for another thread, if a thread stays between `enter` and `leave` the nodes retired
+/// after it entered are kept until it left.
+///
+/// A thread that retires nodes while it is the only thread that is counted frees them right away instead,
+/// so a data structure that is only used by one thread at a time never allocates hazard nodes.
+///
+/// The counters, `hazard_generation` and `hazard_head` are only accessed with `SeqCst`,
+/// the data structure must do the same for the loads of nodes that are made between `enter` and `leave`.
+/// The argument above relies on the counting in `enter` and the check when advancing being ordered by the single total order
+/// of `SeqCst` operations. Acquire and Release alone do not give this guarantee,
+/// a load of a node could then be ordered before the increment in `enter`.
+///
+/// `hazard_lock` only protects the nodes behind the head of the list and uses Acquire and Release,
+/// `hazard_threshold` is a heuristic, `deferred` and `hazard_count` are only reported, none of them guards an access and all are Relaxed.
+///
+/// The generation is 64 bits wide on targets that have 64 bit atomics and pointer sized otherwise.
+/// Nodes are told apart by their age modulo the width of the generation, all comparisons wrap,
+/// so a node that stays in the list while the generation wraps around would look freshly retired.
+/// With 64 bits this cannot happen in practice. With 32 bits the generation has to advance about two billion times
+/// while one hazard node stays in the list, an advance frees all nodes that are two generations old
+/// unless another thread is freeing at the same time, so this does not happen either.
+///
+pub struct HazardList<N: Linked> {
+    /// amount of threads that access nodes, indexed by the parity of the generation they entered in.
+    concurrent_pop_count: [AtomicUsize; 2],
+    /// current generation of hazard nodes
+    hazard_generation: AtomicGeneration,
+    /// amount of retired nodes since the hazard list was last freed, catches the edge case where freeing was skipped.
+    hazard_threshold: AtomicUsize,
+    /// amount of nodes in the chains of the hazard nodes in the list.
+    deferred: AtomicUsize,
+    /// amount of hazard nodes in the list.
+    hazard_count: AtomicUsize,
+    /// provides mutual exclusion to free some elements in the hazard list.
+    hazard_lock: AtomicBool,
+    /// the head of the hazard list
+    hazard_head: AtomicPtr<HazardNode<N>>,
+    /// freed nodes that are reused by `alloc` instead of allocating new ones.
+    free_nodes: FreeList<N>,
+    /// freed hazard nodes that are reused by `retire`.
+    free_hazards: FreeList<HazardNode<N>>,
+    /// counters of retired and freed nodes.
+    #[cfg(feature = "stats")]
+    counters: ReclaimCounters,
+}
+
+impl<N: Linked> HazardList<N> {
+    const_fn! {
+        /// Constructs a new list that has not retired any node yet.
+        fn empty() -> Self {
+            Self {
+                concurrent_pop_count: [AtomicUsize::new(0), AtomicUsize::new(0)],
+                hazard_generation: AtomicGeneration::new(0),
+                hazard_threshold: AtomicUsize::new(0),
+                deferred: AtomicUsize::new(0),
+                hazard_count: AtomicUsize::new(0),
+                hazard_lock: AtomicBool::new(false),
+                hazard_head: AtomicPtr::new(null_mut()),
+                free_nodes: FreeList::new(),
+                free_hazards: FreeList::new(),
+                #[cfg(feature = "stats")]
+                counters: ReclaimCounters::new(),
+            }
+        }
+    }
+
+    /// Reclaims the chain of `count` nodes starting at `node`.
+    unsafe fn free_chain<A: Allocator>(&self, allocator: &A, mut node: *mut N, count: usize) {
+        #[cfg(feature = "stats")]
+        self.counters.freed.add(count);
+        for _ in 0..count {
+            //Safe, the chain consists of count nodes.
+            let node_ptr = NonNull::new_unchecked(node);
+            node = node_ptr.as_ref().next();
+            self.free_nodes.reclaim(allocator, node_ptr);
+        }
+    }
+
+    /// Frees the hazard nodes starting at `hazard_node` and reclaims the nodes they contain.
+    /// Returns the amount of reclaimed nodes.
+    unsafe fn free_hazard_nodes<A: Allocator>(
+        &self,
+        allocator: &A,
+        mut hazard_node: *mut HazardNode<N>,
+    ) -> usize {
+        let mut freed = 0;
+        while let Some(hazard) = NonNull::new(hazard_node) {
+            let hazard_ref = hazard.as_ref();
+            freed += hazard_ref.count;
+            self.deferred.fetch_sub(hazard_ref.count, Relaxed);
+            self.hazard_count.fetch_sub(1, Relaxed);
+            self.free_chain(allocator, hazard_ref.node, hazard_ref.count);
+
+            hazard_node = hazard_ref.next;
+            self.free_hazards.reclaim(allocator, hazard);
+        }
+
+        freed
+    }
+
+    /// Frees the hazard nodes that were retired at least two generations before `count` if possible.
+    /// Gives up if another thread is currently freeing, the nodes are then freed by a later call.
+    unsafe fn free_hazard_list<A: Allocator>(&self, allocator: &A, count: Generation) {
+        if self.hazard_lock.swap(true, Acquire) {
+            return;
+        }
+
+        let _unlock = Unlock {
+            lock: &self.hazard_lock,
+        };
+
+        //Reset before freeing, nodes retired while we free are counted towards the next free.
+        self.hazard_threshold.store(0, Relaxed);
+        trace::freed(self.free_old_generations(allocator, count));
+    }
+
+    /// Frees the hazard nodes behind the first node of a generation older than `count - 1`.
+    /// Returns the amount of reclaimed nodes.
+    ///
+    /// # Safety
+    /// The caller must hold `hazard_lock` and the generation must have been advanced from `count` to `count + 1`.
+    unsafe fn free_old_generations<A: Allocator>(&self, allocator: &A, count: Generation) -> usize {
+        /// To handle overflow we only consider elements to be of an old generation
+        /// If the abs diff to the current generation is less than half the possible values.
+        const MAX_DIFF: Generation = Generation::MAX / 2;
+
+        //The hazard head may be in flux and I don't bother trying to free it here.
+        //The drop of the entire thing will free it.
+        let mut cur_ptr = self.hazard_head.load(SeqCst);
+
+        //This only iterates the nodes of the last two generations, older nodes were already freed.
+        while let Some(cur) = cur_ptr.as_mut() {
+            let next_ptr = cur.next;
+            let Some(next) = next_ptr.as_ref() else {
+                return 0;
+            };
+
+            //Second check prevents funny overflow things.
+            let age = count.wrapping_sub(next.generation);
+            if (2..=MAX_DIFF).contains(&age) {
+                cur.next = null_mut();
+                return self.free_hazard_nodes(allocator, next_ptr);
+            }
+
+            cur_ptr = next_ptr;
+        }
+
+        0
+    }
+
+    /// Frees the hazard nodes starting at `hazard_head` and returns all nodes that are kept for reuse to the allocator.
+    ///
+    /// # Safety
+    /// The hazard nodes must have been removed from the hazard list and no thread may access their nodes anymore.
+    unsafe fn release<A: Allocator>(&self, allocator: &A, hazard_head: *mut HazardNode<N>) {
+        self.hazard_threshold.store(0, Relaxed);
+        self.free_hazard_nodes(allocator, hazard_head);
+        self.free_nodes.clear(allocator);
+        self.free_hazards.clear(allocator);
+    }
+
+    /// Deregisters the calling thread that entered in a generation of the parity
+    /// and tries to start a new hazard generation to free old hazard nodes.
+    ///
+    /// # Safety
+    /// Must only be called once for every time the calling thread was counted in `enter`.
+    /// The calling thread may no longer access any node it loaded after this call.
+    unsafe fn leave<A: Allocator>(&self, allocator: &A, parity: usize) {
+        let sub = self.concurrent_pop_count[parity].fetch_sub(1, SeqCst);
+        debug_assert_ne!(sub, 0, "AtomicLifo::poll UNDERFLOW");
+        self.try_advance(allocator);
+    }
+
+    /// Starts a new hazard generation and frees old hazard nodes if no thread that entered in the previous generation is left.
+    /// Returns false if the generation could not be advanced.
+    fn try_advance<A: Allocator>(&self, allocator: &A) -> bool {
+        let generation = self.hazard_generation.load(SeqCst);
+        if self.concurrent_pop_count[parity(generation.wrapping_add(1))].load(SeqCst) != 0 {
+            //Threads that entered in the previous generation are still accessing nodes.
+            return false;
+        }
+
+        if self
+            .hazard_generation
+            .compare_exchange(generation, generation.wrapping_add(1), SeqCst, SeqCst)
+            .is_err()
+        {
+            return false;
+        }
+
+        //Safe, we advanced the generation from generation to generation + 1.
+        unsafe {
+            self.free_hazard_list(allocator, generation);
+        }
+
+        true
+    }
+}
+
+impl<N: Linked> Reclaimer<N> for HazardList<N> {
+    type Entered<'a, A: Allocator + 'a>
+        = Entered<'a, N, A>
+    where
+        Self: 'a;
+
+    #[cfg(not(loom))]
+    const NEW: Self = Self::empty();
+
+    #[cfg(loom)]
+    fn new() -> Self {
+        Self::empty()
+    }
+
+    fn alloc<A: Allocator>(&self, allocator: &A, node: N) -> NonNull<N> {
+        self.free_nodes.alloc(allocator, node)
+    }
+
+    fn try_alloc<A: Allocator>(&self, allocator: &A, node: N) -> Result<NonNull<N>, N> {
+        self.free_nodes.try_alloc(allocator, node)
+    }
+
+    /// The free lists may exceed their capacity until the reserved nodes were used.
+    fn reserve<A: Allocator>(&self, allocator: &A, additional: usize, empty: impl Fn() -> N) {
+        for _ in 0..additional {
+            unsafe {
+                self.free_nodes.push(alloc_in(allocator, empty()));
+                self.free_hazards.push(alloc_in(
+                    allocator,
+                    HazardNode {
+                        generation: 0,
+                        node: null_mut(),
+                        count: 0,
+                        next: null_mut(),
+                    },
+                ));
+            }
+        }
+    }
+
+    fn free_node_count(&self) -> usize {
+        self.free_nodes.len()
+    }
+
+    fn deferred_count(&self) -> usize {
+        self.deferred.load(Relaxed)
+    }
+
+    /// Threads that entered in either generation.
+    fn entered_count(&self) -> usize {
+        self.concurrent_pop_count[0].load(Relaxed) + self.concurrent_pop_count[1].load(Relaxed)
+    }
+
+    /// A thread stays counted in the same counter until it leaves, so both being 0 proves that every thread counted before has left.
+    /// The counters are read with a read-modify-write, so a thread that is counted afterward synchronizes with it
+    /// without relying on the single total order of `SeqCst`, which loom does not model.
+    fn is_quiescent(&self) -> bool {
+        self.concurrent_pop_count
+            .iter()
+            .all(|counter| counter.fetch_add(0, SeqCst) == 0)
+    }
+
+    /// Hazard nodes in the list and hazard nodes kept for reuse.
+    fn bookkeeping_bytes(&self) -> usize {
+        (self.hazard_count.load(Relaxed) + self.free_hazards.len()) * size_of::<HazardNode<N>>()
+    }
+
+    #[cfg(feature = "stats")]
+    fn counters(&self) -> &ReclaimCounters {
+        &self.counters
+    }
+
+    fn try_enter<'a, A: Allocator + 'a>(
+        &'a self,
+        allocator: &'a A,
+        hazard_limit: usize,
+    ) -> Result<Entered<'a, N, A>, PopContention> {
+        if self.hazard_threshold.load(Relaxed) > hazard_limit {
+            //Many nodes were retired without being freed, either because the thread that advanced the generation
+            //could not free them while another thread was freeing or because a thread stays in pop for a very long time,
+            //for example because it was preempted. We help freeing instead of waiting for that thread,
+            //in the latter case the nodes retired after it entered cannot be freed until it left.
+            #[cfg(feature = "stats")]
+            self.counters.limit_hits.add(1);
+            trace::hazard_limit(self.deferred.load(Relaxed), hazard_limit);
+            self.collect(allocator);
+        }
+
+        loop {
+            let generation = self.hazard_generation.load(SeqCst);
+            let parity = parity(generation);
+            if self.concurrent_pop_count[parity].fetch_add(1, SeqCst) >= MAX_ENTERED {
+                //The counter cannot reach 0 while the other threads are counted, so undoing frees nothing.
+                self.concurrent_pop_count[parity].fetch_sub(1, SeqCst);
+                return Err(PopContention);
+            }
+
+            if self.hazard_generation.load(SeqCst) == generation {
+                return Ok(Entered {
+                    hazard: self,
+                    allocator,
+                    parity,
+                });
+            }
+
+            //The generation advanced before we were counted, the advance may not have seen us.
+            unsafe {
+                self.leave(allocator, parity);
+            }
+        }
+    }
+
+    /// The nodes are freed right away if the calling thread is the only one that entered.
+    unsafe fn retire<A: Allocator>(&self, allocator: &A, node: *mut N, count: usize) {
+        #[cfg(feature = "stats")]
+        self.counters.retired.add(count);
+
+        //A thread can only access the nodes if it loaded them while it was counted, that is before they were removed.
+        //If it was counted before we load its counter below then we see it, a thread that leaves and enters again
+        //in between is counted in the other counter after the nodes were removed and cannot load them anymore.
+        //This relies on the removal, the increment in enter and these loads being ordered by the single total order of SeqCst.
+        let entered =
+            self.concurrent_pop_count[0].load(SeqCst) + self.concurrent_pop_count[1].load(SeqCst);
+        if entered == 1 {
+            //Only we are counted, no other thread can access the nodes.
+            self.free_chain(allocator, node, count);
+            return;
+        }
+
+        let generation = self.hazard_generation.load(SeqCst);
+
+        let mut hazard_node = self.free_hazards.alloc(
+            allocator,
+            HazardNode {
+                generation,
+                node,
+                count,
+                next: self.hazard_head.load(SeqCst),
+            },
+        );
+
+        //Relaxed, incremented before the hazard node is linked so the thread that frees it cannot decrement it below 0.
+        //That thread loads the hazard node with SeqCst from our SeqCst store, so our increment happens before its decrement.
+        self.deferred.fetch_add(count, Relaxed);
+        self.hazard_count.fetch_add(1, Relaxed);
+
+        let node_ref = hazard_node.as_mut();
+        loop {
+            match self.hazard_head.compare_exchange_weak(
+                node_ref.next,
+                hazard_node.as_ptr(),
+                SeqCst,
+                SeqCst,
+            ) {
+                Ok(_) => break,
+                Err(current) => node_ref.next = current,
+            }
+        }
+
+        self.hazard_threshold.fetch_add(1, Relaxed);
+    }
+
+    /// Called when many nodes were retired without being freed.
+    fn collect<A: Allocator>(&self, allocator: &A) {
+        if self.try_advance(allocator) {
+            return;
+        }
+
+        //Another thread advanced to the current generation, the nodes it was allowed to free are still safe to free.
+        //Freeing them again is a no-op if that thread already did.
+        let generation = self.hazard_generation.load(SeqCst);
+        unsafe {
+            self.free_hazard_list(allocator, generation.wrapping_sub(1));
+        }
+    }
+
+    /// Waits for another thread that is currently freeing.
+    fn quiesce<A: Allocator>(&self, allocator: &A) {
+        let mut backoff = Backoff::new();
+        while self.hazard_lock.swap(true, Acquire) {
+            backoff.snooze();
+        }
+
+        let _unlock = Unlock {
+            lock: &self.hazard_lock,
+        };
+
+        //All nodes in the list were removed from the data structure before we took them,
+        //threads that enter after this swap cannot load them anymore.
+        let hazard_head = self.hazard_head.swap(null_mut(), SeqCst);
+
+        //A counter that is 0 after the swap proves that every thread that was counted in it at the time of the swap has left.
+        //The generation is not reset, threads may enter at any time and rely on it only ever increasing.
+        for counter in &self.concurrent_pop_count {
+            let mut backoff = Backoff::new();
+            while counter.load(SeqCst) != 0 {
+                backoff.snooze();
+            }
+        }
+
+        //Safe, no thread can access the nodes anymore.
+        unsafe {
+            self.release(allocator, hazard_head);
+        }
+    }
+
+    /// Also resets the generation.
+    unsafe fn quiesce_unchecked<A: Allocator>(&self, allocator: &A) {
+        self.hazard_generation.store(0, SeqCst);
+        self.release(allocator, self.hazard_head.swap(null_mut(), SeqCst));
+    }
+
+    /// Also checks that no hazard node is newer than the generation and that the generations are ordered as described in `HazardList`.
+    #[cfg(any(test, feature = "debug-validate"))]
+    fn assert_invariants(&mut self, mut visit: impl FnMut(*mut N)) {
+        /// See `free_old_generations`.
+        const MAX_DIFF: Generation = Generation::MAX / 2;
+
+        assert_eq!(self.entered_count(), 0, "a thread is still entered");
+        assert!(
+            !self.hazard_lock.load(Relaxed),
+            "hazard list is still locked"
+        );
+
+        let generation = self.hazard_generation.with_mut(|generation| *generation);
+        let mut hazards = BTreeSet::new();
+        let mut deferred = 0;
+        let mut newer_generation: Option<Generation> = None;
+        let mut hazard = self.hazard_head.with_mut(|head| *head);
+        //Safe, hazard nodes and the nodes of their chains stay allocated while they are in the list.
+        while let Some(hazard_ref) = unsafe { hazard.as_ref() } {
+            assert!(hazards.insert(hazard), "hazard list contains a cycle");
+            assert!(
+                generation.wrapping_sub(hazard_ref.generation) <= MAX_DIFF,
+                "hazard node of generation {} is newer than the generation {generation}",
+                hazard_ref.generation
+            );
+            if let Some(newer) = newer_generation {
+                //Hazard nodes pushed earlier may be one generation larger, but never more.
+                assert!(
+                    newer.wrapping_add(1).wrapping_sub(hazard_ref.generation) <= MAX_DIFF,
+                    "hazard node of generation {} is behind a hazard node of generation {newer}",
+                    hazard_ref.generation
+                );
+            }
+            newer_generation = Some(hazard_ref.generation);
+
+            let mut node = hazard_ref.node;
+            for _ in 0..hazard_ref.count {
+                assert!(!node.is_null(), "chain of a hazard node ends early");
+                visit(node);
+                node = unsafe { (*node).next() };
+            }
+
+            deferred += hazard_ref.count;
+            hazard = hazard_ref.next;
+        }
+
+        assert_eq!(
+            hazards.len(),
+            self.hazard_count.with_mut(|count| *count),
+            "hazard_count does not match the hazard nodes"
+        );
+        assert_eq!(
+            deferred,
+            self.deferred.with_mut(|deferred| *deferred),
+            "deferred does not match the nodes of the hazard nodes"
+        );
+
+        self.free_nodes.assert_invariants(visit);
+        self.free_hazards.assert_invariants(|hazard| {
+            assert!(
+                hazards.insert(hazard),
+                "hazard node is in the hazard list and kept for reuse"
+            );
+        });
+    }
+
+    unsafe fn free_all<A: Allocator>(&mut self, allocator: &A) {
+        let hazard_head = self.hazard_head.with_mut(|head| *head);
+        self.free_hazard_nodes(allocator, hazard_head);
+        self.free_nodes.free_all(allocator);
+        self.free_hazards.free_all(allocator);
+    }
+}
+
+/// Registration of a thread that accesses the nodes of the data structure, returned by `HazardList::enter`.
+/// Dropping it leaves the hazard list, also if the thread unwinds.
+#[must_use]
+pub struct Entered<'a, N: Linked, A: Allocator> {
+    /// the list the thread entered.
+    hazard: &'a HazardList<N>,
+    /// allocator of the nodes that are freed when the thread leaves.
+    allocator: &'a A,
+    /// parity of the generation the thread entered in.
+    parity: usize,
+}
+
+impl<N: Linked, A: Allocator> Drop for Entered<'_, N, A> {
+    fn drop(&mut self) {
+        //Safe, every Entered is created by exactly one call to enter.
+        unsafe {
+            self.hazard.leave(self.allocator, self.parity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Generation, HazardList, Linked, MAX_ENTERED};
+    use crate::allocator::{alloc_in, Global};
+    use crate::reclaim::Reclaimer;
+    use crate::{AtomicLifo, PopContention};
+    use core::ptr::null_mut;
+    use core::sync::atomic::Ordering::SeqCst;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    /// Node without a value, freed nodes are counted by the free list of the hazard list.
+    struct TestNode {
+        /// the next node.
+        next: *mut Self,
+    }
+
+    impl Linked for TestNode {
+        fn next(&self) -> *mut Self {
+            self.next
+        }
+
+        fn set_next(&mut self, next: *mut Self) {
+            self.next = next;
+        }
+    }
+
+    /// Pretends that `entered` threads are counted in both counters.
+    fn set_entered(lifo: &AtomicLifo<u32>, entered: usize) {
+        for counter in &lifo.hazard.concurrent_pop_count {
+            counter.store(entered, SeqCst);
+        }
+    }
+
+    /// Asserts that both counters count `entered` threads.
+    fn assert_entered(lifo: &AtomicLifo<u32>, entered: usize) {
+        for counter in &lifo.hazard.concurrent_pop_count {
+            assert_eq!(counter.load(SeqCst), entered);
+        }
+    }
+
+    #[test]
+    fn test_pop_contention() {
+        let lifo = AtomicLifo::new();
+        lifo.push(1);
+        lifo.push(2);
+
+        set_entered(&lifo, MAX_ENTERED);
+        assert_eq!(lifo.try_pop(), Err(PopContention));
+        //The failed attempt did not leave its increment behind.
+        assert_entered(&lifo, MAX_ENTERED);
+
+        assert!(catch_unwind(AssertUnwindSafe(|| lifo.pop())).is_err());
+        assert_entered(&lifo, MAX_ENTERED);
+
+        //The last thread that fits is still counted.
+        set_entered(&lifo, MAX_ENTERED - 1);
+        assert_eq!(lifo.try_pop(), Ok(Some(2)));
+        assert_entered(&lifo, MAX_ENTERED - 1);
+
+        set_entered(&lifo, 0);
+        assert_eq!(lifo.pop(), Some(1));
+        assert_eq!(lifo.try_pop(), Ok(None));
+    }
+
+    #[test]
+    fn test_generation_wrap() {
+        const ROUNDS: usize = 8;
+        let mut list = HazardList::<TestNode>::NEW;
+        //Half of the rounds are before the wrap.
+        list.hazard_generation.store(Generation::MAX - 3, SeqCst);
+
+        for round in 0..ROUNDS {
+            let freed = list.free_node_count();
+            let reader = list.enter(&Global, usize::MAX);
+            //Retire while the reader is counted as well, so the node is not freed right away.
+            {
+                let _entered = list.enter(&Global, usize::MAX);
+                unsafe {
+                    list.retire(
+                        &Global,
+                        alloc_in(&Global, TestNode { next: null_mut() }).as_ptr(),
+                        1,
+                    );
+                }
+            }
+
+            //The reader may have loaded the node before it was retired, it must not have been freed.
+            assert!(list.free_node_count() <= round);
+            drop(reader);
+            //Freeing never decreases the count, a node that was freed twice would be counted twice.
+            assert!(list.free_node_count() >= freed);
+            assert!(list.free_node_count() <= round + 1);
+        }
+
+        list.assert_invariants(|_| ());
+        assert!(list.hazard_generation.load(SeqCst) < 4 * ROUNDS as Generation);
+        //Every round advanced the generation twice, so every node was freed exactly once except for the head of the list.
+        assert_eq!(list.free_node_count(), ROUNDS - 1);
+        list.quiesce(&Global);
+        assert_eq!(list.free_node_count(), 0);
+    }
+
+    /// Asserts that `assert_invariants` panics with a message that contains `message`.
+    fn assert_broken(lifo: &mut AtomicLifo<u32>, message: &str) {
+        let err = catch_unwind(AssertUnwindSafe(|| lifo.assert_invariants()))
+            .expect_err("the lifo is consistent");
+        let panic = err
+            .downcast_ref::<std::string::String>()
+            .map(std::string::String::as_str)
+            .or_else(|| err.downcast_ref::<&str>().copied())
+            .expect("panic without a message");
+        assert!(panic.contains(message), "{panic}");
+    }
+
+    #[test]
+    fn test_assert_invariants() {
+        let mut lifo = AtomicLifo::new();
+        lifo.extend(0..10);
+        //Keeps the nodes of the popped elements in two hazard nodes.
+        let pinned = lifo.pin();
+        assert_eq!(lifo.pop(), Some(9));
+        assert_eq!(lifo.pop(), Some(8));
+        drop(pinned);
+        lifo.assert_invariants();
+
+        let head = lifo.hazard.hazard_head.load(SeqCst);
+        let hazard = unsafe { &mut *head };
+        let older = unsafe { &mut *hazard.next };
+        assert!(older.next.is_null());
+
+        lifo.hazard.hazard_count.fetch_add(1, SeqCst);
+        assert_broken(&mut lifo, "hazard_count");
+        lifo.hazard.hazard_count.fetch_sub(1, SeqCst);
+
+        let generation = hazard.generation;
+        hazard.generation = older.generation.wrapping_sub(2);
+        assert_broken(&mut lifo, "is behind a hazard node");
+
+        hazard.generation = lifo.hazard.hazard_generation.load(SeqCst) + 1;
+        assert_broken(&mut lifo, "is newer than the generation");
+        hazard.generation = generation;
+
+        older.next = head;
+        assert_broken(&mut lifo, "cycle");
+        older.next = null_mut();
+
+        //The node of the head is popped as well.
+        let popped = older.node;
+        older.node = lifo.head.ptr(SeqCst);
+        assert_broken(&mut lifo, "two lists");
+        older.node = popped;
+        lifo.assert_invariants();
+    }
+}
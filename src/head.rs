@@ -0,0 +1,315 @@
+//! Head of a lifo whose compare and swap can detect that the top node was removed and pushed again in between.
+#[cfg(all(
+    feature = "dwcas",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub use tagged::Head;
+
+#[cfg(not(all(
+    feature = "dwcas",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+pub use plain::Head;
+
+/// Snapshot of a `Head`, the compare and swaps of the head succeed only if the head still equals it.
+pub struct Top<N> {
+    /// the top node.
+    pub(crate) ptr: *mut N,
+    /// amount of removals from the head at the time of the snapshot.
+    #[cfg(all(
+        feature = "dwcas",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    tag: u64,
+}
+
+impl<N> Clone for Top<N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N> Copy for Top<N> {}
+
+impl<N> PartialEq for Top<N> {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(all(
+            feature = "dwcas",
+            any(target_arch = "x86_64", target_arch = "aarch64")
+        ))]
+        if self.tag != other.tag {
+            return false;
+        }
+
+        self.ptr == other.ptr
+    }
+}
+
+///
+/// Head that packs a counter of the removals next to the pointer and swaps both with a 128 bit compare and swap.
+///
+/// Every removal increments the counter, pushes keep it. A compare and swap that removes nodes can therefore only succeed
+/// if no node was removed since the head was loaded, even if the top node was removed and pushed again in between.
+/// The counter wraps after 2^64 removals, which no stale snapshot survives.
+/// Packing the pointer into an integer exposes its provenance, this head is the only part of the crate that is not
+/// compatible with strict provenance.
+///
+#[cfg(all(
+    feature = "dwcas",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod tagged {
+    use super::Top;
+    use crate::atomic::AtomicPtr;
+    use core::marker::PhantomData;
+    use core::sync::atomic::Ordering;
+    use portable_atomic::AtomicU128;
+
+    /// Pointer to the top node in the low and the removal counter in the high 64 bits.
+    #[derive(Debug)]
+    pub struct Head<N> {
+        /// the packed pointer and counter.
+        tagged: AtomicU128,
+        /// the head behaves like an `AtomicPtr` for the auto traits.
+        node: PhantomData<AtomicPtr<N>>,
+    }
+
+    impl<N> Head<N> {
+        /// True if removals from this head are detected by a counter, which makes it safe to reuse removed nodes right away.
+        pub const TAGGED: bool = true;
+
+        /// Constructs a new empty head.
+        pub const fn new() -> Self {
+            Self {
+                tagged: AtomicU128::new(0),
+                node: PhantomData,
+            }
+        }
+
+        /// Packs the snapshot into the value of the atomic.
+        fn pack(top: Top<N>) -> u128 {
+            //The provenance is exposed so unpack can recover the pointer from the address.
+            (u128::from(top.tag) << 64) | top.ptr.expose_provenance() as u128
+        }
+
+        /// Unpacks a value of the atomic.
+        #[allow(clippy::cast_possible_truncation)]
+        const fn unpack(value: u128) -> Top<N> {
+            //The low 64 bits are an address of a 64 bit target.
+            Top {
+                ptr: core::ptr::with_exposed_provenance_mut(value as u64 as usize),
+                tag: (value >> 64) as u64,
+            }
+        }
+
+        /// Loads a snapshot of the head.
+        pub fn load(&self, order: Ordering) -> Top<N> {
+            Self::unpack(self.tagged.load(order))
+        }
+
+        /// Loads the top node.
+        pub fn ptr(&self, order: Ordering) -> *mut N {
+            self.load(order).ptr
+        }
+
+        /// Replaces the head with `ptr` if it still equals `current`, keeping the removal counter.
+        /// Used to push nodes. May fail spuriously.
+        pub fn publish_weak(
+            &self,
+            current: Top<N>,
+            ptr: *mut N,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<(), Top<N>> {
+            let new = Top {
+                ptr,
+                tag: current.tag,
+            };
+
+            self.tagged
+                .compare_exchange_weak(Self::pack(current), Self::pack(new), success, failure)
+                .map(drop)
+                .map_err(Self::unpack)
+        }
+
+        /// Replaces the head with `ptr` if it still equals `current` and increments the removal counter.
+        /// Used to remove nodes.
+        pub fn remove(
+            &self,
+            current: Top<N>,
+            ptr: *mut N,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<(), Top<N>> {
+            let new = Top {
+                ptr,
+                tag: current.tag.wrapping_add(1),
+            };
+
+            self.tagged
+                .compare_exchange(Self::pack(current), Self::pack(new), success, failure)
+                .map(drop)
+                .map_err(Self::unpack)
+        }
+
+        /// Same as `remove` but may fail spuriously.
+        pub fn remove_weak(
+            &self,
+            current: Top<N>,
+            ptr: *mut N,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<(), Top<N>> {
+            let new = Top {
+                ptr,
+                tag: current.tag.wrapping_add(1),
+            };
+
+            self.tagged
+                .compare_exchange_weak(Self::pack(current), Self::pack(new), success, failure)
+                .map(drop)
+                .map_err(Self::unpack)
+        }
+
+        /// Removes all nodes and returns the former top node.
+        pub fn take(&self, order: Ordering) -> *mut N {
+            let mut current = self.tagged.load(order);
+            loop {
+                let top = Self::unpack(current);
+                let new = Top {
+                    ptr: core::ptr::null_mut(),
+                    tag: top.tag.wrapping_add(1),
+                };
+
+                match self
+                    .tagged
+                    .compare_exchange_weak(current, Self::pack(new), order, order)
+                {
+                    Ok(_) => return top.ptr,
+                    Err(actual) => current = actual,
+                }
+            }
+        }
+
+        /// Returns the top node of a head that is not shared with other threads.
+        pub const fn get_mut(&mut self) -> *mut N {
+            Self::unpack(*self.tagged.get_mut()).ptr
+        }
+
+        /// Replaces the top node of a head that is not shared with other threads and returns the former top node.
+        pub fn replace_mut(&mut self, ptr: *mut N) -> *mut N {
+            let top = Self::unpack(*self.tagged.get_mut());
+            *self.tagged.get_mut() = Self::pack(Top {
+                ptr,
+                tag: top.tag.wrapping_add(1),
+            });
+            top.ptr
+        }
+    }
+}
+
+///
+/// Head that is a plain `AtomicPtr`, used without the `dwcas` feature and on targets without a 128 bit compare and swap.
+///
+/// A compare and swap cannot tell whether the top node was removed and pushed again in between,
+/// so removed nodes may only be reused once no thread that loaded them is left.
+///
+#[cfg(not(all(
+    feature = "dwcas",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod plain {
+    use super::Top;
+    use crate::atomic::AtomicPtr;
+    #[cfg(not(loom))]
+    use crate::atomic::WithMut;
+    use core::sync::atomic::Ordering;
+
+    /// Pointer to the top node.
+    #[derive(Debug)]
+    pub struct Head<N> {
+        /// the top node.
+        ptr: AtomicPtr<N>,
+    }
+
+    impl<N> Head<N> {
+        /// True if removals from this head are detected by a counter, which makes it safe to reuse removed nodes right away.
+        pub const TAGGED: bool = false;
+
+        const_fn! {
+            /// Constructs a new empty head.
+            pub fn new() -> Self {
+                Self {
+                    ptr: AtomicPtr::new(core::ptr::null_mut()),
+                }
+            }
+        }
+
+        /// Loads a snapshot of the head.
+        pub fn load(&self, order: Ordering) -> Top<N> {
+            Top {
+                ptr: self.ptr.load(order),
+            }
+        }
+
+        /// Loads the top node.
+        pub fn ptr(&self, order: Ordering) -> *mut N {
+            self.ptr.load(order)
+        }
+
+        /// Replaces the head with `ptr` if it still equals `current`. Used to push nodes. May fail spuriously.
+        pub fn publish_weak(
+            &self,
+            current: Top<N>,
+            ptr: *mut N,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<(), Top<N>> {
+            self.ptr
+                .compare_exchange_weak(current.ptr, ptr, success, failure)
+                .map(drop)
+                .map_err(|ptr| Top { ptr })
+        }
+
+        /// Replaces the head with `ptr` if it still equals `current`. Used to remove nodes.
+        pub fn remove(
+            &self,
+            current: Top<N>,
+            ptr: *mut N,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<(), Top<N>> {
+            self.ptr
+                .compare_exchange(current.ptr, ptr, success, failure)
+                .map(drop)
+                .map_err(|ptr| Top { ptr })
+        }
+
+        /// Same as `remove` but may fail spuriously.
+        pub fn remove_weak(
+            &self,
+            current: Top<N>,
+            ptr: *mut N,
+            success: Ordering,
+            failure: Ordering,
+        ) -> Result<(), Top<N>> {
+            self.publish_weak(current, ptr, success, failure)
+        }
+
+        /// Removes all nodes and returns the former top node.
+        pub fn take(&self, order: Ordering) -> *mut N {
+            self.ptr.swap(core::ptr::null_mut(), order)
+        }
+
+        /// Returns the top node of a head that is not shared with other threads.
+        pub fn get_mut(&mut self) -> *mut N {
+            self.ptr.with_mut(|ptr| *ptr)
+        }
+
+        /// Replaces the top node of a head that is not shared with other threads and returns the former top node.
+        pub fn replace_mut(&mut self, ptr: *mut N) -> *mut N {
+            self.ptr
+                .with_mut(|current| core::mem::replace(current, ptr))
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Callbacks that let users instrument the operations of a lifo.
+
+///
+/// Callbacks an `AtomicLifo` makes for every element that enters or leaves it, see `AtomicLifo::with_hooks`.
+///
+/// All fns do nothing by default. The hooks of `()`, which every lifo uses unless constructed with other hooks,
+/// do nothing either and compile to nothing.
+///
+/// The hooks are never called inside the compare and swap loops, a slow hook therefore only delays the calling thread
+/// and cannot keep other threads from making progress. They are called by the thread that performs the operation,
+/// possibly by many threads at the same time.
+///
+/// ```rust
+/// use atomic_lifo::{AtomicLifo, LifoHooks};
+/// use std::sync::atomic::AtomicUsize;
+/// use std::sync::atomic::Ordering::Relaxed;
+///
+/// #[derive(Default)]
+/// struct Depth(AtomicUsize);
+///
+/// impl LifoHooks<u32> for Depth {
+///     fn on_push(&self, _: &u32) {
+///         self.0.fetch_add(1, Relaxed);
+///     }
+///
+///     fn on_pop(&self, _: &u32) {
+///         self.0.fetch_sub(1, Relaxed);
+///     }
+/// }
+///
+/// let lifo = AtomicLifo::with_hooks(Depth::default());
+/// lifo.push(1);
+/// lifo.push(2);
+/// lifo.pop();
+/// assert_eq!(lifo.hooks().0.load(Relaxed), 1);
+/// ```
+///
+pub trait LifoHooks<T>: Send + Sync + 'static {
+    ///
+    /// Called for every value that is pushed, right before it becomes visible to other threads.
+    ///
+    /// Values that are pushed together, for example by `extend`, are passed one after another
+    /// before any of them becomes visible.
+    ///
+    fn on_push(&self, value: &T) {
+        _ = value;
+    }
+
+    ///
+    /// Called for every value that is removed from the lifo, right after it was removed.
+    ///
+    /// This includes values removed by `take_all` or `clear`, which are passed when the returned iterator yields or drops them.
+    /// Fns that remove values and push most of them back, for example `retain` or `pop_bottom`,
+    /// only call this for the values they do not push back. The values pushed back are passed to neither hook.
+    /// Values that are still in the lifo when it is dropped or consumed by `into_iter` are not passed.
+    ///
+    fn on_pop(&self, value: &T) {
+        _ = value;
+    }
+
+    /// Called whenever `pop` or a similar fn found the lifo empty.
+    fn on_empty_pop(&self) {}
+}
+
+impl<T> LifoHooks<T> for () {}
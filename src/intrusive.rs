@@ -0,0 +1,190 @@
+//! Lifo whose nodes are owned by the caller.
+use crate::atomic::{AtomicBool, AtomicPtr};
+use crate::backoff::Backoff;
+use core::fmt::{Debug, Formatter};
+use core::marker::PhantomData;
+use core::ptr::null_mut;
+use core::sync::atomic::Ordering::SeqCst;
+
+/// Link that is embedded in an item to make it part of an `IntrusiveAtomicLifo`.
+#[derive(Debug, Default)]
+pub struct Link {
+    /// the link of the next item.
+    next: AtomicPtr<Self>,
+}
+
+impl Link {
+    /// Constructs a new link that is not part of any lifo.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            next: AtomicPtr::new(null_mut()),
+        }
+    }
+}
+
+///
+/// Item that embeds a `Link` and can therefore be pushed to an `IntrusiveAtomicLifo`.
+///
+/// # Safety
+/// `link` must always return the same `Link`, which must be a field of the item and must not be used for anything else.
+/// `from_link` must return a pointer to the item that contains the given link,
+/// usually by subtracting `core::mem::offset_of!` of the link field.
+///
+pub unsafe trait Intrusive {
+    /// Returns the link of this item.
+    fn link(&self) -> &Link;
+
+    /// Returns the item that contains the link.
+    ///
+    /// # Safety
+    /// The link must have been returned by `link` of an item of this type.
+    unsafe fn from_link(link: *mut Link) -> *mut Self;
+}
+
+///
+/// Thread Safe LIFO Stack whose nodes are provided by the caller.
+///
+/// Pushing does not allocate, the item is linked into the lifo using its embedded `Link`.
+/// The lifo never frees items, reclaiming them is up to the caller. It therefore does not have a hazard list.
+///
+/// The safe fns only accept items that live forever and serialize concurrent calls to `pop` with a spin lock,
+/// which rules out use after free and the ABA problem.
+/// The unsafe `push_raw` and `pop_raw` do neither, see their documentation.
+///
+pub struct IntrusiveAtomicLifo<U: Intrusive + Send> {
+    /// the link of the top item.
+    head: AtomicPtr<Link>,
+    /// provides mutual exclusion for `pop`.
+    pop_lock: AtomicBool,
+    /// we hand out exclusive references to items, like a `&mut U` that need not be `'static`.
+    _marker: PhantomData<U>,
+}
+
+//Safe, items are only handed out as exclusive references, so sharing the lifo only moves them between threads.
+unsafe impl<U: Intrusive + Send> Sync for IntrusiveAtomicLifo<U> {}
+
+impl<U: Intrusive + Send> Default for IntrusiveAtomicLifo<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U: Intrusive + Send> Debug for IntrusiveAtomicLifo<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IntrusiveAtomicLifo").finish_non_exhaustive()
+    }
+}
+
+impl<U: Intrusive + Send> IntrusiveAtomicLifo<U> {
+    /// Constructs a new empty `IntrusiveAtomicLifo`
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            head: AtomicPtr::new(null_mut()),
+            pop_lock: AtomicBool::new(false),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Pushes an item on top of the lifo stack
+    pub fn push(&self, item: &'static mut U)
+    where
+        U: 'static,
+    {
+        //Safe, the item lives forever and we have the only reference to it, so it is not part of any lifo.
+        unsafe {
+            self.push_raw(item);
+        }
+    }
+
+    ///
+    /// Pops the top of the lifo stack
+    ///
+    /// Concurrent calls to this fn wait for each other, the lock is only held for a single compare and swap.
+    ///
+    pub fn pop(&self) -> Option<&'static mut U>
+    where
+        U: 'static,
+    {
+        let mut backoff = Backoff::new();
+        while self
+            .pop_lock
+            .compare_exchange_weak(false, true, SeqCst, SeqCst)
+            .is_err()
+        {
+            backoff.snooze();
+        }
+
+        //Safe, no other thread pops while we hold the lock. pop_raw never panics, so the lock is always released.
+        let item = unsafe { self.pop_raw() };
+        self.pop_lock.store(false, SeqCst);
+
+        //Safe, pushed items live forever.
+        unsafe { item.as_mut() }
+    }
+
+    ///
+    /// Pushes an item on top of the lifo stack
+    ///
+    /// # Safety
+    /// The item must stay valid and must not be moved until it was popped and no call to `pop_raw` that
+    /// started before it was popped is still running.
+    /// The item must not be part of any lifo and must not be accessed mutably until it was popped.
+    ///
+    pub unsafe fn push_raw(&self, item: *mut U) {
+        //The link pointer is derived from the item pointer, so from_link can turn it back into a pointer to the whole item.
+        let offset = core::ptr::from_ref((*item).link()).byte_offset_from(item.cast_const());
+        let link = item.byte_offset(offset).cast::<Link>();
+        loop {
+            let head = self.head.load(SeqCst);
+            (*link).next.store(head, SeqCst);
+            if self
+                .head
+                .compare_exchange(head, link, SeqCst, SeqCst)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+
+    ///
+    /// Pops the top of the lifo stack, returns null if the lifo is empty.
+    ///
+    /// The caller owns the returned item as if it was never pushed.
+    ///
+    /// # Safety
+    /// If an item that this fn may be looking at is popped and pushed again by another thread while this fn is running
+    /// then the lifo is corrupted (ABA problem).
+    /// The caller must therefore ensure that no popped item is pushed again while another call to this fn may be running,
+    /// for example by never popping concurrently.
+    ///
+    pub unsafe fn pop_raw(&self) -> *mut U {
+        loop {
+            let head = self.head.load(SeqCst);
+            let Some(link) = head.as_ref() else {
+                return null_mut();
+            };
+
+            if self
+                .head
+                .compare_exchange(head, link.next.load(SeqCst), SeqCst, SeqCst)
+                .is_ok()
+            {
+                return U::from_link(head);
+            }
+        }
+    }
+
+    ///
+    /// Returns true if the lifo contains no items.
+    ///
+    /// This is only a snapshot, other threads may push or pop items at any time
+    /// so the returned value may already be stale once this fn returns.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.head.load(SeqCst).is_null()
+    }
+}
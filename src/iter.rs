@@ -0,0 +1,299 @@
+//! Iterators over the elements of an `AtomicLifo`.
+use crate::allocator::{free_in, Allocator, Global};
+#[cfg(not(loom))]
+use crate::atomic::WithMut;
+use crate::hazard::Linked;
+use crate::reclaim::Reclaimer;
+use crate::{zst, AtomicLifo, HazardReclaim, LifoHooks, Node, Reclaim};
+use core::fmt::{Debug, Formatter};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+/// Iterator that pops elements from an `AtomicLifo` until it is empty.
+///
+/// Created by `AtomicLifo::drain`. Once the lifo was observed to be empty the iterator
+/// keeps returning None even if other threads push new elements.
+/// Elements that were not yielded when this iterator is dropped remain in the lifo.
+pub struct Drain<
+    'a,
+    T: Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// the lifo we pop from.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// true once the lifo was observed to be empty.
+    done: bool,
+}
+
+impl<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drain<'a, T, A, R, H> {
+    /// Constructs a new `Drain` that pops from the given lifo.
+    pub(crate) const fn new(lifo: &'a AtomicLifo<T, A, R, H>) -> Self {
+        Self { lifo, done: false }
+    }
+}
+
+impl<T: Sync + Send + Debug, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for Drain<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Drain")
+            .field("lifo", &self.lifo)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Iterator
+    for Drain<'_, T, A, R, H>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let value = self.lifo.pop();
+        self.done = value.is_none();
+        value
+    }
+
+    #[cfg(feature = "count")]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+
+        //This is only a hint, other threads may pop or push elements concurrently.
+        (self.lifo.len(), None)
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> FusedIterator
+    for Drain<'_, T, A, R, H>
+{
+}
+
+/// Iterator over a chain of elements that was removed from an `AtomicLifo`.
+///
+/// Created by `AtomicLifo::take_all`. Yields the elements in the order `pop` would have returned them.
+/// The elements that were not yielded are dropped when this iterator is dropped.
+/// The elements are no longer counted by `AtomicLifo::len` once `take_all` returns.
+///
+/// The nodes of the chain may still be looked at by threads that were accessing the lifo when the chain was removed.
+/// They are therefore handed to the hazard list of the lifo once this iterator is dropped.
+/// The iterator can be sent to another thread if the lifo can be shared with it.
+pub struct OwnedChain<
+    'a,
+    T: Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// the lifo the chain was removed from.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// first node of the chain.
+    first: *mut Node<T, R>,
+    /// node whose value is returned next.
+    current: *mut Node<T, R>,
+    /// amount of nodes whose value was already returned.
+    consumed: usize,
+    /// amount of values of a zero sized type that are returned before the chain.
+    zst: usize,
+}
+
+impl<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> OwnedChain<'a, T, A, R, H> {
+    /// Constructs a new `OwnedChain` from a null terminated chain of nodes that was removed from the lifo
+    /// and `zst` values of a zero sized type that were removed from the lifo.
+    pub(crate) const fn new(
+        lifo: &'a AtomicLifo<T, A, R, H>,
+        chain: *mut Node<T, R>,
+        zst: usize,
+    ) -> Self {
+        Self {
+            lifo,
+            first: chain,
+            current: chain,
+            consumed: 0,
+            zst,
+        }
+    }
+}
+
+//Safe, the chain was removed from the lifo, so its values are owned by the iterator and only moved to the thread it is sent to.
+//The lifo is only used through a shared reference to retire the nodes to its hazard list, which any thread may do.
+unsafe impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Send
+    for OwnedChain<'_, T, A, R, H>
+where
+    AtomicLifo<T, A, R, H>: Sync,
+{
+}
+
+/// Prints only the state of the chain, not the elements or the lifo it was removed from.
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for OwnedChain<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("OwnedChain")
+            .field("first", &self.first)
+            .field("current", &self.current)
+            .field("consumed", &self.consumed)
+            .field("zst", &self.zst)
+            .finish()
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Iterator
+    for OwnedChain<'_, T, A, R, H>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = if self.zst != 0 {
+            self.zst -= 1;
+            //Safe, the lifo forgot a value for every value it counted.
+            unsafe { zst() }
+        } else {
+            let node = unsafe { self.current.as_ref()? };
+            self.current = node.next();
+            self.consumed += 1;
+            //Safe, we are the only owner of the chain.
+            unsafe { node.take() }
+        };
+
+        self.lifo.hooks.on_pop(&value);
+        Some(value)
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> FusedIterator
+    for OwnedChain<'_, T, A, R, H>
+{
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop
+    for OwnedChain<'_, T, A, R, H>
+{
+    fn drop(&mut self) {
+        for _ in 0..self.zst {
+            self.lifo.hooks.on_pop(&unsafe { zst::<T>() });
+        }
+
+        if self.first.is_null() {
+            return;
+        }
+
+        let mut count = self.consumed;
+        while let Some(node) = unsafe { self.current.as_ref() } {
+            self.current = node.next();
+            count += 1;
+            self.lifo.hooks.on_pop(&unsafe { node.take() });
+        }
+
+        let _entered = self
+            .lifo
+            .hazard
+            .enter(&self.lifo.allocator, self.lifo.hazard_limit);
+        unsafe {
+            self.lifo
+                .hazard
+                .retire(&self.lifo.allocator, self.first, count);
+        }
+    }
+}
+
+/// Iterator over the elements of a consumed `AtomicLifo`.
+///
+/// Yields the elements in the order `pop` would have returned them.
+/// The elements that were not yielded are dropped when this iterator is dropped.
+#[derive(Debug)]
+pub struct IntoIter<T: Send, A: Allocator + Clone = Global, R: Reclaim = HazardReclaim> {
+    /// node whose value is returned next.
+    current: *mut Node<T, R>,
+    /// amount of values of a zero sized type that are returned before the chain.
+    zst: usize,
+    /// allocator of the nodes.
+    allocator: A,
+    /// we own the values of the nodes.
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send, A: Allocator + Clone + Send, R: Reclaim> Send for IntoIter<T, A, R> {}
+unsafe impl<T: Sync + Send, A: Allocator + Clone + Sync, R: Reclaim> Sync for IntoIter<T, A, R> {}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> IntoIter<T, A, R> {
+    /// Constructs a new `IntoIter` from a null terminated chain of nodes allocated by the allocator that no other thread can access
+    /// and `zst` values of a zero sized type.
+    pub(crate) const fn new(chain: *mut Node<T, R>, zst: usize, allocator: A) -> Self {
+        Self {
+            current: chain,
+            zst,
+            allocator,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> Iterator for IntoIter<T, A, R> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.zst != 0 {
+            self.zst -= 1;
+            //Safe, the lifo forgot a value for every value it counted.
+            return Some(unsafe { zst() });
+        }
+
+        if self.current.is_null() {
+            return None;
+        }
+
+        //Safe, nobody else can access the chain, so we can free the nodes right away.
+        unsafe {
+            let node = self.current;
+            self.current = (*node).next.with_mut(|next| *next);
+            let value = (*node).value.get_mut().assume_init_read();
+            free_in(&self.allocator, node);
+            Some(value)
+        }
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> FusedIterator for IntoIter<T, A, R> {}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> Drop for IntoIter<T, A, R> {
+    fn drop(&mut self) {
+        for value in self.by_ref() {
+            drop(value);
+        }
+    }
+}
+
+/// Iterator over the elements that were removed from an `AtomicLifo`, oldest element first.
+///
+/// Created by `AtomicLifo::drain_fifo`. Yields the elements in the order they were pushed.
+/// The elements that were not yielded are dropped when this iterator is dropped.
+#[derive(Debug)]
+pub struct OwnedChainFifo<T: Send, A: Allocator + Clone = Global, R: Reclaim = HazardReclaim> {
+    /// the reversed chain, no other thread can access it.
+    inner: IntoIter<T, A, R>,
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> OwnedChainFifo<T, A, R> {
+    /// Constructs a new `OwnedChainFifo` that yields the elements of the already reversed chain.
+    pub(crate) const fn new(inner: IntoIter<T, A, R>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> Iterator for OwnedChainFifo<T, A, R> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> FusedIterator for OwnedChainFifo<T, A, R> {}
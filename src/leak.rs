@@ -0,0 +1,192 @@
+//! Bookkeeping that never frees removed nodes while the data structure is alive.
+use crate::allocator::{alloc_in, free_in, Allocator};
+#[cfg(not(loom))]
+use crate::atomic::WithMut;
+use crate::atomic::{AtomicPtr, AtomicUsize};
+use crate::error::PopContention;
+use crate::hazard::FreeList;
+use crate::reclaim::{LeakReclaim, Reclaimer, Retire};
+#[cfg(feature = "stats")]
+use crate::stats::ReclaimCounters;
+use core::ptr::{null_mut, NonNull};
+use core::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+///
+/// List of nodes that were removed from a data structure, they are freed when the data structure is dropped.
+///
+/// Retired nodes are linked through their link instead of their next pointer,
+/// threads that loaded a node before it was removed may still read its next pointer.
+/// Since no retired node is ever freed while threads access the data structure, there is nothing to register in `enter`.
+///
+pub struct LeakList<N: Retire<LeakReclaim>> {
+    /// the most recently retired node, older nodes follow through their links.
+    retired_head: AtomicPtr<N>,
+    /// amount of retired nodes that were not freed yet.
+    deferred: AtomicUsize,
+    /// nodes that are reused by `alloc` instead of allocating new ones, only filled by `reserve`.
+    free_nodes: FreeList<N>,
+    /// counters of retired and freed nodes.
+    #[cfg(feature = "stats")]
+    counters: ReclaimCounters,
+}
+
+impl<N: Retire<LeakReclaim>> LeakList<N> {
+    const_fn! {
+        /// Constructs a new list that has not retired any node yet.
+        fn empty() -> Self {
+            Self {
+                retired_head: AtomicPtr::new(null_mut()),
+                deferred: AtomicUsize::new(0),
+                free_nodes: FreeList::new(),
+                #[cfg(feature = "stats")]
+                counters: ReclaimCounters::new(),
+            }
+        }
+    }
+
+    /// Frees the retired nodes starting at `node`.
+    unsafe fn free_retired<A: Allocator>(&self, allocator: &A, mut node: *mut N) {
+        while let Some(node_ref) = node.as_ref() {
+            let next = node_ref.link().load(Relaxed);
+            self.deferred.fetch_sub(1, Relaxed);
+            #[cfg(feature = "stats")]
+            self.counters.freed.add(1);
+            free_in(allocator, node);
+            node = next;
+        }
+    }
+}
+
+impl<N: Retire<LeakReclaim>> Reclaimer<N> for LeakList<N> {
+    type Entered<'a, A: Allocator + 'a>
+        = ()
+    where
+        Self: 'a;
+
+    #[cfg(not(loom))]
+    const NEW: Self = Self::empty();
+
+    #[cfg(loom)]
+    fn new() -> Self {
+        Self::empty()
+    }
+
+    fn alloc<A: Allocator>(&self, allocator: &A, node: N) -> NonNull<N> {
+        self.free_nodes.alloc(allocator, node)
+    }
+
+    fn try_alloc<A: Allocator>(&self, allocator: &A, node: N) -> Result<NonNull<N>, N> {
+        self.free_nodes.try_alloc(allocator, node)
+    }
+
+    /// Retiring needs no bookkeeping nodes.
+    fn reserve<A: Allocator>(&self, allocator: &A, additional: usize, empty: impl Fn() -> N) {
+        for _ in 0..additional {
+            unsafe {
+                self.free_nodes.push(alloc_in(allocator, empty()));
+            }
+        }
+    }
+
+    fn free_node_count(&self) -> usize {
+        self.free_nodes.len()
+    }
+
+    fn deferred_count(&self) -> usize {
+        self.deferred.load(Relaxed)
+    }
+
+    /// Threads are not registered.
+    fn entered_count(&self) -> usize {
+        0
+    }
+
+    /// Nothing registers, so there is nothing to tell whether a thread is accessing the nodes.
+    fn is_quiescent(&self) -> bool {
+        false
+    }
+
+    /// Retired nodes are linked through their link, there is no bookkeeping.
+    fn bookkeeping_bytes(&self) -> usize {
+        0
+    }
+
+    #[cfg(feature = "stats")]
+    fn counters(&self) -> &ReclaimCounters {
+        &self.counters
+    }
+
+    fn try_enter<'a, A: Allocator + 'a>(&'a self, _: &'a A, _: usize) -> Result<(), PopContention> {
+        Ok(())
+    }
+
+    unsafe fn retire<A: Allocator>(&self, _: &A, node: *mut N, count: usize) {
+        #[cfg(feature = "stats")]
+        self.counters.retired.add(count);
+
+        let mut last = &*node;
+        for _ in 1..count {
+            let next = last.next();
+            last.link().store(next, Relaxed);
+            last = &*next;
+        }
+
+        //Relaxed, incremented before the chain is linked so the thread that frees it cannot decrement it below 0.
+        self.deferred.fetch_add(count, Relaxed);
+
+        let mut head = self.retired_head.load(Relaxed);
+        loop {
+            last.link().store(head, Relaxed);
+            //Release, the thread that frees the nodes must see the links of the chain.
+            match self
+                .retired_head
+                .compare_exchange_weak(head, node, Release, Relaxed)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Nothing can be freed without knowing which threads access the nodes.
+    fn collect<A: Allocator>(&self, _: &A) {}
+
+    /// Only returns the nodes kept for reuse, the retired nodes may still be accessed by other threads.
+    fn quiesce<A: Allocator>(&self, allocator: &A) {
+        //Safe, the nodes in the free list were never part of the data structure.
+        unsafe {
+            self.free_nodes.clear(allocator);
+        }
+    }
+
+    unsafe fn quiesce_unchecked<A: Allocator>(&self, allocator: &A) {
+        //Acquire, synchronizes with the retires so we see the links of their chains.
+        self.free_retired(allocator, self.retired_head.swap(null_mut(), Acquire));
+        self.free_nodes.clear(allocator);
+    }
+
+    #[cfg(any(test, feature = "debug-validate"))]
+    fn assert_invariants(&mut self, mut visit: impl FnMut(*mut N)) {
+        let mut count = 0;
+        let mut node = self.retired_head.with_mut(|head| *head);
+        //Safe, retired nodes stay allocated until the list is dropped.
+        while let Some(node_ref) = unsafe { node.as_ref() } {
+            visit(node);
+            count += 1;
+            node = node_ref.link().load(Relaxed);
+        }
+
+        assert_eq!(
+            count,
+            self.deferred.with_mut(|deferred| *deferred),
+            "deferred does not match the retired nodes"
+        );
+        self.free_nodes.assert_invariants(visit);
+    }
+
+    unsafe fn free_all<A: Allocator>(&mut self, allocator: &A) {
+        let retired_head = self.retired_head.with_mut(|head| *head);
+        self.free_retired(allocator, retired_head);
+        self.free_nodes.free_all(allocator);
+    }
+}
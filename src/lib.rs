@@ -40,243 +40,3401 @@
     clippy::used_underscore_binding
 )]
 extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
 
+/// Declares a `const fn`, or a plain fn when built with `--cfg loom` whose atomics cannot be constructed in a const context.
+macro_rules! const_fn {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident $($rest:tt)*) => {
+        $(#[$attr])*
+        #[cfg(not(loom))]
+        $vis const fn $name $($rest)*
+
+        $(#[$attr])*
+        #[cfg(loom)]
+        $vis fn $name $($rest)*
+    };
+}
+
+mod allocator;
+//Loom only models AtomicLifo and AtomicFifo, the other data structures are not built with `--cfg loom`.
+#[cfg(not(loom))]
+mod array;
+mod atomic;
+mod backoff;
+#[cfg(all(feature = "std", not(loom)))]
+mod blocking;
+#[cfg(not(loom))]
+mod bounded;
+#[cfg(all(feature = "capi", not(loom)))]
+mod capi;
+//The channel shares its state through an `Arc`, which needs compare and swap.
+#[cfg(all(target_has_atomic = "ptr", not(loom)))]
+mod channel;
+#[cfg(all(feature = "critical-section", not(loom)))]
+mod critical;
+mod elimination;
+#[cfg(feature = "epoch")]
+mod epoch;
+mod error;
+mod fifo;
+#[cfg(feature = "async")]
+mod future;
+mod guard;
+mod hazard;
+mod head;
+mod hooks;
+#[cfg(not(loom))]
+mod intrusive;
+mod iter;
+mod leak;
+mod local;
+#[cfg(all(feature = "rayon", not(loom)))]
+mod par;
+mod pin;
+#[cfg(not(loom))]
+mod pool;
+mod reclaim;
+mod recycle;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(not(loom))]
+mod sharded;
+#[cfg(not(loom))]
+mod single_consumer;
+#[cfg(feature = "stats")]
+mod stats;
+mod trace;
+#[cfg(feature = "async")]
+mod waker;
+
+pub use allocator::Global;
+#[cfg(not(loom))]
+pub use array::ArrayLifo;
+#[cfg(all(feature = "std", not(loom)))]
+pub use blocking::BlockingLifo;
+#[cfg(not(loom))]
+pub use bounded::BoundedAtomicLifo;
+#[cfg(all(feature = "capi", not(loom)))]
+pub use capi::{
+    atomic_lifo_free, atomic_lifo_new, atomic_lifo_pop, atomic_lifo_push, AtomicLifoHandle,
+};
+#[cfg(all(target_has_atomic = "ptr", not(loom)))]
+pub use channel::{channel, LifoReceiver, LifoSender, TryRecvError};
+#[cfg(all(feature = "critical-section", not(loom)))]
+pub use critical::CriticalSectionLifo;
+pub use error::{PopContention, PopIfEqError, PushError};
+pub use fifo::AtomicFifo;
+#[cfg(feature = "async")]
+pub use future::{LifoStream, PopFuture};
+pub use guard::PopGuard;
+pub use hooks::LifoHooks;
+#[cfg(not(loom))]
+pub use intrusive::{Intrusive, IntrusiveAtomicLifo, Link};
+pub use iter::{Drain, IntoIter, OwnedChain, OwnedChainFifo};
+pub use local::LocalLifo;
+pub use pin::{Iter, PinGuard, PinnedIter, PinnedRef};
+#[cfg(not(loom))]
+pub use pool::{Pool, PoolGuard};
+#[cfg(feature = "epoch")]
+pub use reclaim::EpochReclaim;
+pub use reclaim::{HazardReclaim, LeakReclaim, Reclaim};
+#[cfg(not(loom))]
+pub use sharded::ShardedLifo;
+#[cfg(not(loom))]
+pub use single_consumer::AtomicLifoSc;
+#[cfg(feature = "stats")]
+pub use stats::LifoStats;
+
+#[cfg(not(loom))]
+use crate::atomic::WithMut;
+use crate::atomic::{AtomicPtr, AtomicUsize};
 use alloc::boxed::Box;
-use core::ptr::null_mut;
-use core::sync::atomic::Ordering::SeqCst;
-use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
-use defer_heavy::defer;
+use alloc::vec::Vec;
+use allocator::{alloc_in, free_in, Allocator};
+use backoff::Backoff;
+use core::cell::UnsafeCell;
+use core::fmt::{Debug, Formatter};
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::Deref;
+use core::ptr::{null_mut, NonNull};
+use core::sync::atomic::Ordering::{self, Acquire, Relaxed, Release, SeqCst};
+use elimination::{Elimination, Slot};
+use hazard::{Linked, DEFAULT_HAZARD_LIMIT};
+use head::Head;
+use reclaim::{Reclaimer, Retire};
+use recycle::{Recycle, RecycleList};
+#[cfg(feature = "stats")]
+use stats::LifoCounters;
+
+///
+/// Ordering of the compare and swap that links pushed nodes into the lifo.
+///
+/// Release would be enough to make the nodes and their values visible to the thread that pops them,
+/// every pop loads the head with at least Acquire before it reads a node.
+/// Freeing popped nodes relies on the single total order of `SeqCst` operations however:
+/// a thread that entered pop after the node was removed must not load the head as it was before the removal.
+/// A pop that loads the head with `SeqCst` is only guaranteed to see the removal if every store to the head is `SeqCst`.
+/// Waiting in `pop_blocking` or `pop_async` is a store-load handshake as well:
+/// the waiter registers itself and then checks the head, while a push links its nodes and then checks for waiters.
+///
+const PUBLISH: Ordering = SeqCst;
 
 /// Thread Safe LIFO Stack/Single linked list.
-#[derive(Debug, Default)]
-pub struct AtomicLifo<T: Sync + Send + 'static> {
-    /// amount of concurrent ongoing calls to pop.
-    concurrent_pop_count: AtomicUsize,
-    /// current generation of hazard nodes
-    hazard_generation: AtomicUsize,
-    /// threshold counter to catch an edge case when generation never increments to force it to increment and the hazard list to be freed.
-    hazard_threshold: AtomicUsize,
-    /// provides mutual exclusion to free some elements in the hazard list.
-    hazard_lock: AtomicBool,
-    /// the head of the hazard list
-    hazard_head: AtomicPtr<HazardNode<T>>,
+///
+/// Values are stored inline in the nodes, so no type, including small `Copy` types such as `u64`, needs an allocation of its own.
+/// Trait objects are stored as their box, for example `AtomicLifo<Box<dyn FnOnce() + Send>>`,
+/// the node holds the pointer of the box and the object is not boxed a second time.
+///
+/// Values of zero sized types, for example `()` used as permits, are not stored in nodes at all.
+/// The lifo only counts them, pushing and popping them never allocates.
+///
+/// Nodes of popped elements are kept in a free list of up to 1024 nodes once no thread can access them anymore.
+/// `push` reuses these nodes, so a lifo that is pushed to and popped from in a loop does not allocate new nodes.
+/// With the `dwcas` feature on `x86_64` and `aarch64` popped nodes are reused right away unless another thread
+/// is peeking or traversing the lifo at the same time.
+///
+/// When the nodes of popped elements are freed is decided by the strategy `R`, by default `HazardReclaim`.
+/// `with_reclaim` constructs a lifo with another strategy, for example `LeakReclaim`.
+///
+/// Elements only have to be `Send`, values are moved into and out of the lifo but never shared.
+/// Only the fns that look at elements without popping them, such as `peek_with`, `iter` and `contains`, require `T: Sync`.
+/// Elements may borrow data that outlives the lifo, for example references shared between the threads of `std::thread::scope`.
+pub struct AtomicLifo<
+    T: Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// nodes that were removed from the lifo but may still be accessed by other threads.
+    hazard: R::List<Node<T, R>>,
+    /// amount of popped nodes that may wait to be freed before `pop` helps freeing them.
+    hazard_limit: usize,
+    /// allocator of all nodes and of the bookkeeping of popped nodes.
+    allocator: A,
     /// the head of the queue
-    head: AtomicPtr<Node<T>>,
+    head: Head<Node<T, R>>,
+    /// popped nodes that pushes reuse right away, only used if `RECYCLE` is true.
+    recycled: RecycleList<Node<T, R>>,
+    /// slots where pushes and pops whose compare and swap failed exchange nodes, see `new_with_elimination`.
+    elimination: Option<Elimination<Node<T, R>>>,
+    /// slot where `pop_waiting` waits for `push_or_exchange` to hand it a node.
+    exchange: Slot<Node<T, R>>,
+    /// amount of elements of a zero sized type, these are not stored in nodes.
+    zst_len: AtomicUsize,
+    /// amount of elements in the lifo.
+    #[cfg(feature = "count")]
+    len: AtomicUsize,
+    /// largest amount of elements in the lifo since it was constructed or `reset_max_len` was called.
+    #[cfg(feature = "count")]
+    max_len: AtomicUsize,
+    /// counters of the operations of the lifo.
+    #[cfg(feature = "stats")]
+    counters: LifoCounters,
+    /// threads that are parked in `pop_blocking` until an element is pushed.
+    #[cfg(feature = "std")]
+    waiters: std::sync::Mutex<Vec<std::thread::Thread>>,
+    /// amount of threads in `waiters`, allows push to skip locking `waiters` if nobody is waiting.
+    #[cfg(feature = "std")]
+    waiter_count: AtomicUsize,
+    /// waker slots of tasks that wait for an element, allocated once the first task has to wait.
+    #[cfg(feature = "async")]
+    wakers: AtomicPtr<waker::Wakers>,
+    /// callbacks for the elements that are pushed and popped.
+    hooks: H,
+    /// receives the elements that are still in the lifo when it is dropped, see `set_drop_handler`.
+    drop_handler: Option<fn(T)>,
+}
+
+//Values only ever move between threads: push moves them in and pop moves them out again.
+//The fns that hand out references to elements that are still in the lifo, such as `peek_with`, `iter` and `Debug`,
+//require `T: Sync` themselves, so `T: Send` is enough to share the lifo, like for the channels of `std::sync::mpsc`.
+//The lifo is invariant in `T` because the head is an atomic pointer to the nodes,
+//so a shared lifo of borrowed elements can never accept elements that borrow shorter.
+unsafe impl<T: Send, A: Allocator + Clone + Send, R: Reclaim, H: LifoHooks<T>> Send
+    for AtomicLifo<T, A, R, H>
+{
+}
+unsafe impl<T: Send, A: Allocator + Clone + Sync, R: Reclaim, H: LifoHooks<T>> Sync
+    for AtomicLifo<T, A, R, H>
+{
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop for AtomicLifo<T, A, R, H> {
+    fn drop(&mut self) {
+        let handler = self.drop_handler.take();
+        let lifo = FreeOnDrop(self);
+        if let Some(handler) = handler {
+            lifo.0.drain_into_handler(handler);
+        }
+    }
 }
 
-impl<T: Sync + Send + 'static> Drop for AtomicLifo<T> {
+/// Frees all nodes of the lifo and drops the values that are still in it once dropped,
+/// this also happens if the drop handler of the lifo panics.
+struct FreeOnDrop<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>>(
+    &'a mut AtomicLifo<T, A, R, H>,
+);
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop
+    for FreeOnDrop<'_, T, A, R, H>
+{
     fn drop(&mut self) {
+        let lifo = &mut *self.0;
         unsafe {
-            let mut current_free = self.head.load(SeqCst);
+            for _ in 0..lifo.zst_len.with_mut(|len| *len) {
+                drop(zst::<T>());
+            }
+
+            let mut current_free = lifo.head.get_mut();
             loop {
                 if current_free.is_null() {
                     break;
                 }
 
-                let node = Box::from_raw(current_free);
-                current_free = node.next;
-                _ = Box::from_raw(node.value);
+                let node = current_free;
+                current_free = (*node).next.with_mut(|next| *next);
+                (*node).value.get_mut().assume_init_drop();
+                free_in(&lifo.allocator, node);
             }
 
-            let hazard_head = self.hazard_head.load(SeqCst);
-            if !hazard_head.is_null() {
-                _ = Box::from_raw(hazard_head);
+            lifo.recycled.free_all(&lifo.allocator);
+            lifo.hazard.free_all(&lifo.allocator);
+
+            #[cfg(feature = "async")]
+            {
+                let wakers = lifo.wakers.load(SeqCst);
+                if !wakers.is_null() {
+                    _ = Box::from_raw(wakers);
+                }
             }
         }
     }
 }
 
-/// Node that contains normal nodes that should be freed later.
-#[derive(Debug)]
-struct HazardNode<T: Sync + Send + 'static> {
-    /// the generation of this hazard node
-    generation: usize,
-    /// the node we want to free later
-    node: *mut Node<T>,
-    /// next hazard node
-    next: *mut HazardNode<T>,
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Extend<T>
+    for &AtomicLifo<T, A, R, H>
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        AtomicLifo::extend(self, iter);
+    }
 }
 
-impl<T: Sync + Send + 'static> Drop for HazardNode<T> {
-    fn drop(&mut self) {
-        if !self.node.is_null() {
-            unsafe {
-                _ = Box::from_raw(self.node);
-            }
+/// The first element of the iterator ends up at the bottom of the lifo and is popped last,
+/// the order is the same as if all elements were pushed one by one.
+impl<T: Send> FromIterator<T> for AtomicLifo<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut chain = Chain::new(Global);
+        for value in iter {
+            chain.push_top(value);
         }
 
-        let mut cur_free = self.next;
-        while !cur_free.is_null() {
-            unsafe {
-                let mut cur_free_unbox = Box::from_raw(cur_free);
-                cur_free = cur_free_unbox.next;
-                cur_free_unbox.next = null_mut();
+        Self::from_chain(chain, DEFAULT_HAZARD_LIMIT, ())
+    }
+}
+
+/// Clones all elements using a traversal from top to bottom, the clone pops the elements in the same order.
+///
+/// Elements pushed or popped by other threads while the clone is made may or may not be part of the clone.
+/// A concurrent call to `pop` that removes the element that is currently being cloned
+/// will wait until the element was cloned.
+impl<T: Sync + Send + Clone, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T> + Clone> Clone
+    for AtomicLifo<T, A, R, H>
+{
+    fn clone(&self) -> Self {
+        let hooks = self.hooks.clone();
+        let mut chain = self.chain();
+        self.traverse(|element| {
+            let value = element.clone();
+            hooks.on_push(&value);
+            chain.push_bottom(value);
+            true
+        });
+
+        let mut lifo = Self::from_chain(chain, self.hazard_limit, hooks);
+        lifo.drop_handler = self.drop_handler;
+        lifo.elimination = self
+            .elimination
+            .as_ref()
+            .map(|elimination| Elimination::new(elimination.len()));
+        lifo
+    }
+}
+
+/// Prints the approximate amount of elements and the first elements from top to bottom using a traversal.
+///
+/// Only the first 32 elements are printed, a trailing `..` indicates that there are more.
+/// Elements pushed or popped by other threads while formatting may or may not be printed.
+/// `debug_len` prints only the approximate amount of elements, also if the elements do not implement `Debug`.
+impl<T: Sync + Send + Debug, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for AtomicLifo<T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AtomicLifo")
+            .field("len", &format_args!("~{}", self.approx_len()))
+            .field("items", &DebugItems(self))
+            .finish()
+    }
+}
+
+/// Helper returned by `AtomicLifo::debug_len` that only prints the approximate amount of elements.
+struct DebugLen<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>>(
+    &'a AtomicLifo<T, A, R, H>,
+);
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for DebugLen<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AtomicLifo")
+            .field("len", &format_args!("~{}", self.0.approx_len()))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Maximum amount of elements printed by the `Debug` impl of `AtomicLifo`.
+const DEBUG_LIMIT: usize = 32;
+
+/// Amount of elements a `LocalLifo` returned by `AtomicLifo::local` buffers before it pushes them.
+const LOCAL_CAPACITY: usize = 64;
+
+/// Amount of nodes `detach` walks before it checks whether the head changed, only if nodes are recycled.
+const DETACH_CHECK_INTERVAL: usize = 64;
+
+/// Helper that prints the elements of a lifo as a list.
+struct DebugItems<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>>(
+    &'a AtomicLifo<T, A, R, H>,
+);
+
+impl<T: Sync + Send + Debug, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for DebugItems<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut list = f.debug_list();
+        let mut printed = 0;
+        let mut truncated = false;
+        self.0.traverse(|element| {
+            if printed == DEBUG_LIMIT {
+                truncated = true;
+                return false;
             }
+
+            list.entry(element);
+            printed += 1;
+            true
+        });
+
+        if truncated {
+            return list.finish_non_exhaustive();
         }
+
+        list.finish()
     }
 }
 
-/// Lifo node
-#[derive(Debug)]
-struct Node<T: Sync + Send + 'static> {
-    /// the next node
-    next: *mut Node<T>,
-    /// the value pointer
-    value: *mut T,
+/// The last element of the vec ends up on top of the lifo and is popped first.
+impl<T: Send> From<Vec<T>> for AtomicLifo<T> {
+    fn from(value: Vec<T>) -> Self {
+        value.into_iter().collect()
+    }
 }
 
-impl<T: Sync + Send + 'static> AtomicLifo<T> {
-    /// Constructs a new empty `AtomicLifo`
-    #[must_use]
-    pub const fn new() -> Self {
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> IntoIterator
+    for AtomicLifo<T, A, R, H>
+{
+    type Item = T;
+    type IntoIter = IntoIter<T, A, R>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        //Dropping self afterward frees the hazard list.
+        let zst = self.zst_len.with_mut(core::mem::take);
+        let head = self.head.replace_mut(null_mut());
+        IntoIter::new(head, zst, self.allocator.clone())
+    }
+}
+
+/// Chain of nodes that is owned by a single thread and not part of the lifo.
+struct Chain<T: Send, A: Allocator + Clone, R: Reclaim> {
+    /// allocator of the nodes, the same allocator as the one of the lifo the chain is published to.
+    allocator: A,
+    /// the top node of the chain.
+    top: *mut Node<T, R>,
+    /// the bottom node of the chain.
+    bottom: *mut Node<T, R>,
+    /// amount of nodes in the chain.
+    count: usize,
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> Chain<T, A, R> {
+    /// Constructs a new empty chain whose nodes are allocated by the allocator.
+    const fn new(allocator: A) -> Self {
         Self {
-            concurrent_pop_count: AtomicUsize::new(0),
-            hazard_generation: AtomicUsize::new(0),
-            hazard_threshold: AtomicUsize::new(0),
-            hazard_lock: AtomicBool::new(false),
-            hazard_head: AtomicPtr::new(null_mut()),
-            head: AtomicPtr::new(null_mut()),
+            allocator,
+            top: null_mut(),
+            bottom: null_mut(),
+            count: 0,
         }
     }
 
-    /// Free the hazard list if possible.
-    unsafe fn free_hazard_list(&self, count: usize) {
-        /// To handle overflow we only consider elements to be of an old generation
-        /// If the abs diff to the current generation is less than half the possible values.
-        const MAX_DIFF: usize = usize::MAX / 2;
+    /// Adds a value on top of the chain.
+    fn push_top(&mut self, value: T) {
+        if AtomicLifo::<T>::ZST {
+            core::mem::forget(value);
+            self.count += 1;
+            return;
+        }
+
+        let node = alloc_in(&self.allocator, Node::new(value)).as_ptr();
+        if self.count == 0 {
+            self.bottom = node;
+        } else {
+            unsafe {
+                (*node).next.with_mut(|next| *next = self.top);
+            }
+        }
+
+        self.top = node;
+        self.count += 1;
+    }
 
-        if self.hazard_lock.swap(true, SeqCst) {
+    /// Adds a value to the bottom of the chain.
+    fn push_bottom(&mut self, value: T) {
+        if AtomicLifo::<T>::ZST {
+            core::mem::forget(value);
+            self.count += 1;
             return;
         }
 
-        defer! {
-            self.hazard_lock.store(false, SeqCst);
+        let node = alloc_in(&self.allocator, Node::new(value)).as_ptr();
+        //Safe, the node is new.
+        unsafe {
+            self.link_bottom(node);
         }
+    }
 
-        self.hazard_threshold.store(0, SeqCst);
+    /// Links the node below the bottom of the chain.
+    ///
+    /// # Safety
+    /// The node must be exclusively owned by the caller, have no next node and hold a value.
+    unsafe fn link_bottom(&mut self, node: *mut Node<T, R>) {
+        if self.count == 0 {
+            self.top = node;
+        } else {
+            (*self.bottom).next.with_mut(|next| *next = node);
+        }
 
-        //The hazard head may be in flux and I don't bother trying to free it here.
-        //The drop of the entire thing will free it.
-        let mut cur_ptr = self.hazard_head.load(SeqCst);
+        self.bottom = node;
+        self.count += 1;
+    }
 
-        //This is unlikely to iterate too many elements as we call this fn right after we increment count.
-        //Meaning the only elements that we have to skip are the ones that other threads pop in the meantime!
-        while let Some(cur) = cur_ptr.as_mut() {
-            let next_ptr = cur.next;
-            let Some(next) = next_ptr.as_ref() else {
-                return;
-            };
+    /// Returns the value on top of the chain.
+    fn top(&self) -> Option<&T> {
+        if self.count == 0 {
+            return None;
+        }
 
-            //Second check prevents funny overflow things.
-            if next.generation < count && next.generation.abs_diff(count) <= MAX_DIFF {
-                cur.next = null_mut();
-                _ = Box::from_raw(next_ptr);
-                return;
+        if AtomicLifo::<T>::ZST {
+            //Safe, we forgot a value for every counted value.
+            return Some(unsafe { zst_ref() });
+        }
+
+        //Safe, we own the nodes.
+        Some(unsafe { (*self.top).value() })
+    }
+
+    /// Moves the top node to the bottom of the other chain without moving its value.
+    fn move_top(&mut self, other: &mut Self) {
+        if self.count == 0 {
+            return;
+        }
+
+        self.count -= 1;
+        if AtomicLifo::<T>::ZST {
+            other.count += 1;
+            return;
+        }
+
+        //Safe, we own the nodes and hand the node over to the other chain.
+        unsafe {
+            let node = self.top;
+            self.top = (*node)
+                .next
+                .with_mut(|next| core::mem::replace(next, null_mut()));
+            other.link_bottom(node);
+        }
+    }
+
+    /// Links the nodes of the other chain below the bottom of this chain.
+    fn append(&mut self, other: Self) {
+        let (top, bottom, count, _) = other.into_parts();
+        if count == 0 {
+            return;
+        }
+
+        if !AtomicLifo::<T>::ZST {
+            if self.count == 0 {
+                self.top = top;
+            } else {
+                //Safe, we own the nodes.
+                unsafe {
+                    (*self.bottom).next.with_mut(|next| *next = top);
+                }
             }
 
-            cur_ptr = next_ptr;
+            self.bottom = bottom;
         }
+
+        self.count += count;
     }
 
-    pub fn push(&self, value: T) {
-        let node = Box::into_raw(Box::new(Node {
-            value: Box::into_raw(Box::new(value)),
-            next: self.head.load(SeqCst),
-        }));
+    /// Reverses the order of the nodes by relinking them.
+    fn reverse(&mut self) {
+        if AtomicLifo::<T>::ZST {
+            return;
+        }
 
-        let node_ref = unsafe { node.as_mut().unwrap_unchecked() };
+        let mut above = null_mut();
+        let mut cur = self.top;
+        for _ in 0..self.count {
+            //Safe, we own the nodes.
+            unsafe {
+                let next = (*cur).next.with_mut(|next| core::mem::replace(next, above));
+                above = cur;
+                cur = next;
+            }
+        }
 
-        loop {
-            if self
-                .head
-                .compare_exchange(node_ref.next, node, SeqCst, SeqCst)
-                .is_err()
-            {
-                node_ref.next = self.head.load(SeqCst);
+        self.bottom = self.top;
+        self.top = above;
+    }
+
+    /// Passes the values from the top to the bottom of the chain to the fn.
+    fn for_each(&self, mut f: impl FnMut(&T)) {
+        let mut cur = self.top;
+        for _ in 0..self.count {
+            if AtomicLifo::<T>::ZST {
+                //Safe, we forgot a value for every counted value.
+                f(unsafe { zst_ref() });
                 continue;
             }
 
+            //Safe, we own the nodes.
+            unsafe {
+                f((*cur).value());
+                cur = (*cur).next();
+            }
+        }
+    }
+
+    /// Removes the value on top of the chain.
+    fn pop_top(&mut self) -> Option<T> {
+        if self.count == 0 {
+            return None;
+        }
+
+        self.count -= 1;
+        if AtomicLifo::<T>::ZST {
+            //Safe, we forgot a value for every counted value.
+            return Some(unsafe { zst() });
+        }
+
+        unsafe {
+            let node = self.top;
+            self.top = (*node).next.with_mut(|next| *next);
+            let value = (*node).value.get_mut().assume_init_read();
+            free_in(&self.allocator, node);
+            Some(value)
+        }
+    }
+
+    /// Removes the value on the bottom of the chain, this is O(n).
+    fn pop_bottom(&mut self) -> Option<T> {
+        if self.count <= 1 {
+            return self.pop_top();
+        }
+
+        self.count -= 1;
+        if AtomicLifo::<T>::ZST {
+            //Safe, we forgot a value for every counted value.
+            return Some(unsafe { zst() });
+        }
+
+        unsafe {
+            let node = self.bottom;
+            let mut above = self.top;
+            for _ in 1..self.count {
+                above = (*above).next.with_mut(|next| *next);
+            }
+
+            (*above).next.with_mut(|next| *next = null_mut());
+            self.bottom = above;
+            let value = (*node).value.get_mut().assume_init_read();
+            free_in(&self.allocator, node);
+            Some(value)
+        }
+    }
+
+    /// Releases ownership of the nodes and returns the top, the bottom, the amount of nodes and the allocator.
+    fn into_parts(self) -> (*mut Node<T, R>, *mut Node<T, R>, usize, A) {
+        let chain = ManuallyDrop::new(self);
+        //Safe, the chain is never dropped so the allocator is only moved out once.
+        let allocator = unsafe { core::ptr::read(&raw const chain.allocator) };
+        (chain.top, chain.bottom, chain.count, allocator)
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> Drop for Chain<T, A, R> {
+    fn drop(&mut self) {
+        if AtomicLifo::<T>::ZST {
+            for _ in 0..self.count {
+                drop(unsafe { zst::<T>() });
+            }
+
             return;
         }
+
+        let mut cur = self.top;
+        for _ in 0..self.count {
+            unsafe {
+                let node = cur;
+                cur = (*node).next.with_mut(|next| *next);
+                (*node).value.get_mut().assume_init_drop();
+                free_in(&self.allocator, node);
+            }
+        }
+    }
+}
+
+/// Nodes a fn removed from the lifo, they are pushed back when it returns or unwinds.
+/// The examined nodes that are kept end up on top of the nodes that were not examined yet, preserving their order.
+/// The values never left the lifo from the point of view of the caller, so they are not passed to the hooks.
+struct PushBack<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> {
+    /// the lifo the nodes were removed from.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// the examined nodes that are pushed back.
+    kept: Chain<T, A, R>,
+    /// the nodes that were not examined yet.
+    rest: Chain<T, A, R>,
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop for PushBack<'_, T, A, R, H> {
+    fn drop(&mut self) {
+        let mut chain = core::mem::replace(&mut self.kept, self.lifo.chain());
+        chain.append(core::mem::replace(&mut self.rest, self.lifo.chain()));
+        self.lifo.publish_chain(chain);
+    }
+}
+
+/// Returns a value of a zero sized type.
+///
+/// # Safety
+/// `T` must be zero sized and a value of `T` must have been forgotten for every value returned by this fn.
+const unsafe fn zst<T>() -> T {
+    NonNull::<T>::dangling().as_ptr().read()
+}
+
+/// Returns a reference to a value of a zero sized type.
+///
+/// # Safety
+/// `T` must be zero sized and a value of `T` must have been forgotten that outlives the reference.
+const unsafe fn zst_ref<'a, T>() -> &'a T {
+    &*NonNull::<T>::dangling().as_ptr()
+}
+
+/// Lifo node
+#[derive(Debug)]
+struct Node<T: Send, R: Reclaim> {
+    /// the next node, atomic because threads that loaded a recycled node may read it while it is pushed again.
+    next: AtomicPtr<Self>,
+    /// the value, it is moved out once the node was removed from the lifo.
+    /// Nodes that are retired or reused therefore never drop it.
+    /// A recycled node gets a new value while threads that loaded it before may still access its next pointer.
+    value: UnsafeCell<MaybeUninit<T>>,
+    /// borrow state of the value.
+    /// The lowest bit is set once a thread took ownership of the value, the other bits count the ongoing borrows.
+    state: AtomicUsize,
+    /// link used by the strategy to retire the node.
+    link: R::Link<Self>,
+}
+
+impl<T: Send, R: Reclaim> Linked for Node<T, R> {
+    fn next(&self) -> *mut Self {
+        self.next.load(Relaxed)
+    }
+
+    fn set_next(&mut self, next: *mut Self) {
+        self.next.with_mut(|current| *current = next);
+    }
+}
+
+impl<T: Send, R: Reclaim> Recycle for Node<T, R> {
+    fn next_ptr(&self) -> &AtomicPtr<Self> {
+        &self.next
+    }
+}
+
+impl<T: Send, R: Reclaim> Retire<R> for Node<T, R> {
+    fn link(&self) -> &R::Link<Self> {
+        &self.link
+    }
+}
+
+impl<T: Send, R: Reclaim> Node<T, R> {
+    /// Bit in `state` that marks the value as taken.
+    const TAKEN: usize = 1;
+    /// Amount `state` is incremented by for every ongoing borrow.
+    const BORROW: usize = 2;
+
+    /// Constructs a new node that does not have a next node.
+    fn new(value: T) -> Self {
+        Self {
+            next: AtomicPtr::new(null_mut()),
+            value: UnsafeCell::new(MaybeUninit::new(value)),
+            state: AtomicUsize::new(0),
+            link: R::Link::default(),
+        }
+    }
+
+    /// Constructs a node without a value, used to reserve nodes.
+    fn empty() -> Self {
+        Self {
+            next: AtomicPtr::new(null_mut()),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            state: AtomicUsize::new(0),
+            link: R::Link::default(),
+        }
     }
 
+    /// Moves the value out of a node that was never published.
     ///
-    /// Pops the top of the lifo stack
+    /// # Safety
+    /// The node must have been constructed by `new`.
+    unsafe fn into_value(self) -> T {
+        self.value.into_inner().assume_init()
+    }
+
+    /// Returns a reference to the value.
     ///
-    /// # Panics
-    /// if more than `usize::MAX` concurrent calls in different threads to this fn are made.
+    /// # Safety
+    /// The value must not have been taken yet and must not be taken while the reference is alive.
+    /// The caller must either own the node or hold a `Borrow` of it.
+    const unsafe fn value(&self) -> &T {
+        (*self.value.get()).assume_init_ref()
+    }
+
+    /// Tries to borrow the value.
+    /// Returns None if some other thread has already taken the value.
+    /// The borrow is released once the returned `Borrow` is dropped.
+    fn try_borrow(&self) -> Option<Borrow<'_, T, R>> {
+        //All accesses to the state are ordered by the modification order of state alone,
+        //either the borrow is counted before the taker sets TAKEN, then the taker waits for it, or we see TAKEN.
+        if self.state.fetch_add(Self::BORROW, Relaxed) & Self::TAKEN == 0 {
+            return Some(Borrow { node: self });
+        }
+
+        self.state.fetch_sub(Self::BORROW, Relaxed);
+        None
+    }
+
+    /// Takes ownership of the value.
+    /// Spins until all ongoing borrows of the value have been released.
     ///
-    pub fn pop(&self) -> Option<T> {
-        while self.hazard_threshold.load(SeqCst) > 500_000 {
-            //This is an edge case where we have an absurd amount of threads spinning
-            //on pop and actually succeed in removing elements.
-            //This will make acc_count never reach 0 all while the hazard list grows without it ever being freed.
-            //To break this we just spin here until the acc_count reaches 0 and the hazard free is invoked by some thread currently still in pop.
-            core::hint::spin_loop();
-        }
-
-        assert_ne!(
-            self.concurrent_pop_count.fetch_add(1, SeqCst),
-            usize::MAX,
-            "Too many threads calling pop concurrently"
-        );
-
-        defer! {
-            let sub = self.concurrent_pop_count.fetch_sub(1, SeqCst);
-            debug_assert_ne!(sub, 0, "AtomicLifo::poll UNDERFLOW");
-            if sub != 1 {
-                return;
-            }
+    /// # Safety
+    /// Only the thread that removed this node from the lifo may call this fn and only once.
+    unsafe fn take(&self) -> T {
+        self.state.fetch_or(Self::TAKEN, Acquire);
+        //Acquire, synchronizes with the release of every borrow that was ongoing.
+        let mut backoff = Backoff::new();
+        while self.state.load(Acquire) != Self::TAKEN {
+            //Some thread is currently looking at the value in peek_with, it will be done soon.
+            backoff.snooze();
+        }
 
-            let haz_cnt = self.hazard_generation.fetch_add(1, SeqCst);
-            unsafe {
-                self.free_hazard_list(haz_cnt);
-            }
+        (*self.value.get()).assume_init_read()
+    }
+
+    /// Moves the value into a recycled node and marks it as not taken.
+    ///
+    /// # Safety
+    /// The node must have been taken out of a `RecycleList` by the calling thread.
+    /// Other threads may only read its next pointer.
+    unsafe fn reuse(node: NonNull<Self>, value: T) {
+        let node = node.as_ref();
+        //The value was taken, no other thread accesses it.
+        (*node.value.get()).write(value);
+        //Relaxed, no thread reads the state before the node was published again.
+        node.state.store(0, Relaxed);
+    }
+}
+
+/// Borrow of the value of a node, obtained from `Node::try_borrow`.
+/// The value is not taken before the borrow is released by dropping this, also if the borrowing thread unwinds.
+struct Borrow<'a, T: Send, R: Reclaim> {
+    /// the borrowed node.
+    node: &'a Node<T, R>,
+}
+
+impl<T: Send, R: Reclaim> Deref for Borrow<'_, T, R> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        //Safe, the value cannot be taken while we hold the borrow.
+        unsafe { self.node.value() }
+    }
+}
+
+impl<T: Send, R: Reclaim> Drop for Borrow<'_, T, R> {
+    fn drop(&mut self) {
+        //Release, our reads of the value must happen before the taker moves it out.
+        self.node.state.fetch_sub(Node::<T, R>::BORROW, Release);
+    }
+}
+
+impl<T: Send> AtomicLifo<T> {
+    const_fn! {
+        /// Constructs a new empty `AtomicLifo`
+        #[must_use]
+        pub fn new() -> Self {
+            Self::new_in(Global)
         }
+    }
 
-        let removed = loop {
-            let head = self.head.load(SeqCst);
-            let next = unsafe { head.as_ref()?.next };
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicLifo` that helps freeing popped nodes in `pop`
+        /// once more than `hazard_limit` popped nodes are waiting to be freed.
+        ///
+        /// See `with_hazard_limit_in` for how to choose the limit.
+        ///
+        #[must_use]
+        pub fn with_hazard_limit(hazard_limit: usize) -> Self {
+            Self::with_hazard_limit_in(hazard_limit, Global)
+        }
+    }
 
-            if self
-                .head
-                .compare_exchange(head, next, SeqCst, SeqCst)
-                .is_err()
-            {
-                continue;
-            }
+    ///
+    /// Constructs a new empty `AtomicLifo` with an elimination array of `SLOTS` slots.
+    ///
+    /// A push and a pop whose compare and swap on the head failed because another thread changed the head
+    /// wait a few spins in one of the slots for an operation of the other kind. If one comes along
+    /// the pop takes the node of the push directly and neither of them touches the head again.
+    /// This pays off when many threads push and pop at the same time, without contention the slots are never used.
+    /// Fns that push or pop several elements at once, such as `extend` and `pop_n`, do not use the slots.
+    /// A few slots are enough, more slots make it less likely that a push and a pop meet. 0 slots disable elimination.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::new_with_elimination::<4>();
+    /// lifo.push(1);
+    /// assert_eq!(lifo.pop(), Some(1));
+    /// ```
+    ///
+    #[must_use]
+    pub fn new_with_elimination<const SLOTS: usize>() -> Self {
+        Self::with_elimination_in::<SLOTS>(Global)
+    }
+}
 
-            break head;
-        };
+impl<T: Send> Default for AtomicLifo<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        //Safe, removed must be non-null and we "own" it here for a very short time. Other thread may be currently looking at the next pointer only.
-        let removed_obj = unsafe { Box::from_raw(removed.as_ref().unwrap_unchecked().value) };
+impl<T: Send, R: Reclaim> AtomicLifo<T, Global, R> {
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicLifo` that frees popped nodes using the given strategy.
+        ///
+        /// ```rust
+        /// use atomic_lifo::{AtomicLifo, LeakReclaim};
+        ///
+        /// let lifo = AtomicLifo::with_reclaim(LeakReclaim);
+        /// lifo.push(1);
+        /// assert_eq!(lifo.pop(), Some(1));
+        /// ```
+        ///
+        #[must_use]
+        pub fn with_reclaim(reclaim: R) -> Self {
+            Self::with_reclaim_in(reclaim, Global)
+        }
+    }
+}
 
-        let count = self.hazard_generation.load(SeqCst);
+impl<T: Send> AtomicLifo<T, Global, LeakReclaim> {
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicLifo` that never frees the nodes of popped elements before it is dropped.
+        ///
+        /// `pop` then only removes the node, moves the value out and links the node into a list of retired nodes,
+        /// it never allocates bookkeeping and never frees. Dropping the lifo frees the retired nodes together with the nodes
+        /// of the elements that are still in the lifo.
+        ///
+        /// Popped nodes are not reused, every `push` allocates a new node of the size of `T` plus three pointers.
+        /// The memory of the lifo is therefore proportional to the amount of elements that were ever pushed,
+        /// not to the amount of elements it contains. This suits lifos that live for a short time and are then dropped as a whole,
+        /// a long living lifo should use the default strategy instead. `quiesce_unchecked` frees the retired nodes
+        /// if the calling thread is known to be the only one that uses the lifo.
+        ///
+        #[must_use]
+        pub fn new_leaky() -> Self {
+            Self::with_reclaim(LeakReclaim)
+        }
+    }
+}
 
-        let hazard_node = Box::into_raw(Box::new(HazardNode {
-            generation: count,
-            node: removed,
-            next: self.hazard_head.load(SeqCst),
-        }));
+impl<T: Send, H: LifoHooks<T>> AtomicLifo<T, Global, HazardReclaim, H> {
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicLifo` that passes every pushed and popped element to the given hooks.
+        ///
+        /// `on_push` is called by every fn that adds an element, `on_pop` by every fn that removes one
+        /// and `on_empty_pop` whenever a pop found the lifo empty. Fns that move elements to another lifo,
+        /// for example `steal_batch_into` or `swap_contents`, call `on_pop` of this lifo and `on_push` of the other.
+        /// Clones of the lifo and lifos returned by `take` use clones of the hooks.
+        /// See `LifoHooks` for an example.
+        ///
+        #[must_use]
+        pub fn with_hooks(hooks: H) -> Self {
+            Self::with_hooks_in(hooks, HazardReclaim, Global)
+        }
+    }
+}
 
-        loop {
-            let node_ref = unsafe { hazard_node.as_mut().unwrap_unchecked() };
+impl<T: Send, A: Allocator + Clone> AtomicLifo<T, A> {
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicLifo` whose nodes are allocated by the given allocator.
+        ///
+        /// All allocations of the lifo, including the bookkeeping of popped nodes, go through the allocator
+        /// and are returned to it at the latest when the lifo is dropped.
+        /// Operations that build a new chain of nodes, for example `extend` or `clone`, use clones of the allocator,
+        /// all clones must therefore be able to free memory allocated by each other.
+        ///
+        #[must_use]
+        pub fn new_in(allocator: A) -> Self {
+            Self::with_hazard_limit_in(DEFAULT_HAZARD_LIMIT, allocator)
+        }
+    }
 
-            if self
-                .hazard_head
-                .compare_exchange(node_ref.next, hazard_node, SeqCst, SeqCst)
-                .is_err()
-            {
-                node_ref.next = self.hazard_head.load(SeqCst);
-                continue;
-            }
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicLifo` whose nodes are allocated by the given allocator
+        /// and that helps freeing popped nodes in `pop` once more than `hazard_limit` popped nodes are waiting to be freed.
+        ///
+        /// Popped nodes are usually freed by the pop that ends the next but one generation of concurrent pops.
+        /// The limit only matters if that is skipped, for example because a thread stays in `pop` for a long time.
+        /// Up to roughly `hazard_limit` nodes, each of the size of `T` plus two pointers, may then be kept alive.
+        /// A small limit bounds this memory for large `T` at the cost of more frequent attempts to free in `pop`,
+        /// a large limit suits small `T`. `new` and `new_in` use a limit of 500000.
+        ///
+        /// Clones of the lifo and lifos returned by `take` use the same limit.
+        ///
+        #[must_use]
+        pub fn with_hazard_limit_in(hazard_limit: usize, allocator: A) -> Self {
+            Self::from_parts(hazard_limit, allocator, ())
+        }
+    }
 
-            break;
+    ///
+    /// Constructs a new empty `AtomicLifo` with an elimination array of `SLOTS` slots that allocates all nodes using the allocator.
+    ///
+    /// See `new_with_elimination`. The slots themselves are allocated by the global allocator.
+    ///
+    #[must_use]
+    pub fn with_elimination_in<const SLOTS: usize>(allocator: A) -> Self {
+        let mut lifo = Self::new_in(allocator);
+        lifo.elimination = (SLOTS != 0).then(|| Elimination::new(SLOTS));
+        lifo
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim> AtomicLifo<T, A, R> {
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicLifo` whose nodes are allocated by the given allocator
+        /// and that frees popped nodes using the given strategy.
+        ///
+        /// See `new_in` for the requirements on the allocator.
+        /// `EpochReclaim` flushes the deferred frees of the popping thread once 500000 popped nodes wait to be freed,
+        /// `LeakReclaim` never frees them.
+        ///
+        #[must_use]
+        pub fn with_reclaim_in(reclaim: R, allocator: A) -> Self {
+            Self::with_hooks_in((), reclaim, allocator)
         }
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> AtomicLifo<T, A, R, H> {
+    /// True if `T` is zero sized, such values are only counted in `zst_len`.
+    const ZST: bool = size_of::<T>() == 0;
 
-        self.hazard_threshold.fetch_add(1, SeqCst);
+    /// True if popped nodes are pushed again right away instead of being retired, see `RecycleList`.
+    const RECYCLE: bool = Head::<Node<T, R>>::TAGGED && R::RECYCLE;
+
+    const_fn! {
+        ///
+        /// Constructs a new empty `AtomicLifo` that passes its elements to the given hooks,
+        /// whose nodes are allocated by the given allocator and that frees popped nodes using the given strategy.
+        ///
+        /// See `with_hooks` for when the hooks are called.
+        ///
+        #[must_use]
+        pub fn with_hooks_in(hooks: H, reclaim: R, allocator: A) -> Self {
+            //The strategy is a marker, only its type is used.
+            core::mem::forget(reclaim);
+            Self::from_parts(DEFAULT_HAZARD_LIMIT, allocator, hooks)
+        }
+    }
 
-        Some(*removed_obj)
+    const_fn! {
+        /// Constructs a new empty `AtomicLifo`.
+        fn from_parts(hazard_limit: usize, allocator: A, hooks: H) -> Self {
+            Self {
+                #[cfg(not(loom))]
+                hazard: R::List::NEW,
+                #[cfg(loom)]
+                hazard: R::List::new(),
+                hazard_limit,
+                allocator,
+                head: Head::new(),
+                recycled: RecycleList::new(),
+                elimination: None,
+                exchange: Slot::new(),
+                zst_len: AtomicUsize::new(0),
+                #[cfg(feature = "count")]
+                len: AtomicUsize::new(0),
+                #[cfg(feature = "count")]
+                max_len: AtomicUsize::new(0),
+                #[cfg(feature = "stats")]
+                counters: LifoCounters::new(),
+                #[cfg(feature = "std")]
+                waiters: std::sync::Mutex::new(Vec::new()),
+                #[cfg(feature = "std")]
+                waiter_count: AtomicUsize::new(0),
+                #[cfg(feature = "async")]
+                wakers: AtomicPtr::new(null_mut()),
+                hooks,
+                drop_handler: None,
+            }
+        }
     }
-}
\ No newline at end of file
+
+    /// Constructs a new `AtomicLifo` that contains the nodes of the chain.
+    fn from_chain(chain: Chain<T, A, R>, hazard_limit: usize, hooks: H) -> Self {
+        let (top, _, count, allocator) = chain.into_parts();
+        let mut lifo = Self::from_parts(hazard_limit, allocator, hooks);
+        lifo.head.replace_mut(top);
+        if Self::ZST {
+            lifo.zst_len.with_mut(|len| *len = count);
+        }
+        #[cfg(feature = "count")]
+        {
+            lifo.len.with_mut(|len| *len = count);
+            lifo.max_len.with_mut(|max_len| *max_len = count);
+        }
+        #[cfg(feature = "stats")]
+        lifo.counters.pushes.add(count);
+
+        lifo
+    }
+
+    /// Pushes a value on top of the lifo stack
+    pub fn push(&self, value: T) {
+        self.hooks.on_push(&value);
+        if Self::ZST {
+            core::mem::forget(value);
+            self.push_zst(1);
+            return;
+        }
+
+        let node = self.alloc(value).as_ptr();
+        unsafe {
+            self.publish(node, node, 1);
+        }
+    }
+
+    ///
+    /// Hands the value directly to a thread that is waiting in `pop_waiting`, pushes it if no thread is waiting.
+    ///
+    /// Returns true if the value was handed off, it then never becomes visible to other threads.
+    /// For a lifo that is mostly empty, for example one producer handing elements to one consumer in turns,
+    /// the consumer gets the value without a compare and swap on the head.
+    /// If no thread is waiting this costs a single additional atomic load over `push`.
+    /// Values of zero sized types are always pushed.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::new();
+    /// assert!(!lifo.push_or_exchange(1));
+    /// assert_eq!(lifo.pop_waiting(), Some(1));
+    /// ```
+    ///
+    pub fn push_or_exchange(&self, value: T) -> bool {
+        if Self::ZST || !self.exchange.is_requested() {
+            self.push(value);
+            return false;
+        }
+
+        self.hooks.on_push(&value);
+        let node = self.alloc(value).as_ptr();
+        if self.exchange.give(node) {
+            #[cfg(feature = "stats")]
+            self.counters.pushes.add(1);
+            return true;
+        }
+
+        //Safe, the waiting thread withdrew or another pusher was faster, the node is not published yet.
+        unsafe {
+            self.publish(node, node, 1);
+        }
+
+        false
+    }
+
+    /// Moves the value into a recycled node or into a node allocated by the strategy.
+    fn alloc(&self, value: T) -> NonNull<Node<T, R>> {
+        if let Some(node) = self.take_recycled() {
+            //Safe, we took the node out of the recycle list.
+            unsafe { Node::reuse(node, value) };
+            return node;
+        }
+
+        self.hazard.alloc(&self.allocator, Node::new(value))
+    }
+
+    /// Takes a popped node that can be pushed again right away if there is one.
+    fn take_recycled(&self) -> Option<NonNull<Node<T, R>>> {
+        if !Self::RECYCLE {
+            return None;
+        }
+
+        self.recycled.take()
+    }
+
+    /// Pushes a node whose value was taken again right away if nodes are recycled, retires it otherwise.
+    ///
+    /// # Safety
+    /// The node must no longer be part of the lifo, its value must have been taken
+    /// and the calling thread must have entered the hazard list.
+    unsafe fn release(&self, node: *mut Node<T, R>) {
+        if !(Self::RECYCLE && self.recycled.recycle(node)) {
+            self.hazard.retire(&self.allocator, node, 1);
+        }
+    }
+
+    ///
+    /// Pushes a value on top of the lifo stack, returns the value if the node for it could not be allocated.
+    ///
+    /// Unlike `push` this fn does not call the allocation error handler, which aborts the process by default.
+    /// Zero sized values are counted and never fail to be pushed.
+    ///
+    /// Popping the value later may have to allocate a small bookkeeping node to free the node of the value.
+    /// If that allocation fails then pop calls the allocation error handler.
+    /// `reserve` allocates these bookkeeping nodes up front, pops reuse them unless another thread is reusing one at the same time.
+    ///
+    /// # Errors
+    /// returns the value if the allocator failed to allocate the node.
+    ///
+    pub fn try_push(&self, value: T) -> Result<(), PushError<T>> {
+        if Self::ZST {
+            self.push(value);
+            return Ok(());
+        }
+
+        let node = if let Some(node) = self.take_recycled() {
+            //Safe, we took the node out of the recycle list.
+            unsafe { Node::reuse(node, value) };
+            node
+        } else {
+            //Safe, the node was constructed by new.
+            self.hazard
+                .try_alloc(&self.allocator, Node::new(value))
+                .map_err(|node| PushError(unsafe { node.into_value() }))?
+        }
+        .as_ptr();
+
+        //Safe, the node is not published yet.
+        self.hooks.on_push(unsafe { (*node).value() });
+        unsafe {
+            self.publish(node, node, 1);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Pushes a boxed value on top of the lifo stack.
+    ///
+    /// Values are stored inline in the nodes, so the value is moved out of the box into the node
+    /// and the allocation of the box is freed, this is the same as `push(*value)`.
+    /// To keep large values in their own allocation and only move the pointer, use an `AtomicLifo<Box<T>>` instead.
+    ///
+    #[deprecated = "values are stored inline in the nodes, use `push(*value)` or an `AtomicLifo<Box<T>>` to keep the allocation"]
+    #[allow(clippy::boxed_local)]
+    pub fn push_boxed(&self, value: Box<T>) {
+        self.push(*value);
+    }
+
+    ///
+    /// Allocates `additional` nodes up front, pushes do not allocate until the reserved nodes are used up.
+    ///
+    /// Also reserves the bookkeeping needed to pop the same amount of elements, so a loop that pushes and pops
+    /// without exceeding the reserved amount of elements in the lifo does not allocate at all.
+    /// A popped node is reused right away if no other thread accesses the lifo at the same time.
+    /// Otherwise it is only reused once no thread can access it anymore, which is usually after the next two pops,
+    /// so such a loop should keep at least two elements less in the lifo than it reserved.
+    /// Reserved nodes are kept even if they exceed the 1024 nodes the lifo normally keeps for reuse.
+    /// Values of zero sized types never need nodes, this fn then does nothing.
+    ///
+    pub fn reserve(&self, additional: usize) {
+        if Self::ZST {
+            return;
+        }
+
+        self.hazard
+            .reserve(&self.allocator, additional, Node::empty);
+    }
+
+    ///
+    /// Returns the amount of nodes that pushes can use without allocating.
+    ///
+    /// This is only a snapshot, other threads may push or pop elements at any time.
+    ///
+    #[must_use]
+    pub fn free_node_count(&self) -> usize {
+        self.hazard.free_node_count() + self.recycled.len()
+    }
+
+    ///
+    /// Returns the amount of nodes of popped elements that wait to be freed.
+    ///
+    /// A popped node waits until no thread that was popping, peeking or traversing the lifo when it was popped can access it anymore.
+    /// If this keeps growing then a thread is likely stuck inside a fn of this lifo, for example in the closure of `peek_with`,
+    /// see `in_flight_pop_count`. With `LeakReclaim` every popped node waits until `quiesce_unchecked` or until the lifo is dropped.
+    /// Nodes that are reused right away with the `dwcas` feature never wait.
+    ///
+    /// This is only a snapshot, other threads may pop or free nodes at any time.
+    ///
+    #[must_use]
+    pub fn deferred_node_count(&self) -> usize {
+        self.hazard.deferred_count()
+    }
+
+    ///
+    /// Returns the amount of threads that are currently popping, peeking or traversing the lifo.
+    ///
+    /// Nodes popped while a thread is counted are not freed before it is done, a thread that stays counted
+    /// for a long time therefore makes `deferred_node_count` grow. Always 0 with `LeakReclaim` and `EpochReclaim`,
+    /// which do not count these threads.
+    ///
+    /// This is only a snapshot, other threads may enter or leave at any time.
+    ///
+    #[must_use]
+    pub fn in_flight_pop_count(&self) -> usize {
+        self.hazard.entered_count()
+    }
+
+    ///
+    /// Checks the internal structure of the lifo and panics if it is broken, meant for tests and fuzzing.
+    ///
+    /// Walks the nodes of the elements, the nodes of popped elements that wait to be freed and the nodes kept for reuse.
+    /// It checks that none of these lists contains a cycle, that no node is in two of them, that no element was taken out of
+    /// or is borrowed from a node that is still in the lifo and that the counters match the nodes.
+    /// With `HazardReclaim` it also checks that the generations of the popped nodes are ordered.
+    ///
+    /// The walk needs the lifo to be quiescent, which the mutable reference guarantees.
+    /// It takes time proportional to the amount of nodes and is only available with the `debug-validate` feature.
+    ///
+    /// # Panics
+    /// if the structure of the lifo is broken.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let mut lifo = AtomicLifo::new();
+    /// lifo.push(1);
+    /// lifo.push(2);
+    /// assert_eq!(lifo.pop(), Some(2));
+    /// lifo.assert_invariants();
+    /// ```
+    ///
+    #[cfg(any(test, feature = "debug-validate"))]
+    pub fn assert_invariants(&mut self) {
+        let mut nodes = alloc::collections::BTreeSet::new();
+        let mut count = 0;
+        let mut node = self.head.get_mut();
+        //Safe, the nodes in the lifo are allocated and no other thread can access them.
+        while let Some(node_ref) = unsafe { node.as_ref() } {
+            assert!(nodes.insert(node), "the lifo contains a cycle");
+            assert_eq!(
+                node_ref.state.load(Relaxed),
+                0,
+                "value of a node in the lifo was taken or is still borrowed"
+            );
+            count += 1;
+            node = node_ref.next.load(Relaxed);
+        }
+
+        let zst_len = self.zst_len.with_mut(|len| *len);
+        if Self::ZST {
+            assert_eq!(count, 0, "zero sized elements are stored in nodes");
+        } else {
+            assert_eq!(zst_len, 0, "elements are counted as zero sized");
+        }
+
+        #[cfg(feature = "count")]
+        {
+            let len = self.len.with_mut(|len| *len);
+            assert_eq!(len, count + zst_len, "len does not match the elements");
+            assert!(
+                self.max_len.with_mut(|max_len| *max_len) >= len,
+                "max_len is smaller than len"
+            );
+        }
+
+        if let Some(elimination) = &mut self.elimination {
+            elimination.assert_invariants();
+        }
+        self.exchange.assert_invariants();
+
+        let mut visit = |node| {
+            assert!(
+                nodes.insert(node),
+                "node {node:p} is in two lists or a list contains a cycle"
+            );
+        };
+        self.recycled.assert_invariants(&mut visit);
+        self.hazard.assert_invariants(visit);
+    }
+
+    ///
+    /// Returns the approximate amount of bytes of heap memory the lifo currently holds.
+    ///
+    /// This counts the nodes of the elements in the lifo, the nodes of popped elements that wait to be freed,
+    /// the nodes kept for reuse and the bookkeeping of the popped nodes. It is computed from counters
+    /// the lifo maintains anyway and does not traverse the lifo.
+    /// Values are counted by the size of `T` only, memory a value owns itself, for example the buffer of a `String` or a `Vec`,
+    /// is not counted. Values of zero sized types are not stored in nodes and take no memory.
+    ///
+    /// This is only a snapshot, other threads may push, pop or free nodes at any time.
+    ///
+    #[cfg(feature = "count")]
+    #[must_use]
+    pub fn approximate_heap_bytes(&self) -> usize {
+        if Self::ZST {
+            return self.hazard.bookkeeping_bytes();
+        }
+
+        let nodes = self.len() + self.deferred_node_count() + self.free_node_count();
+        nodes * size_of::<Node<T, R>>() + self.hazard.bookkeeping_bytes()
+    }
+
+    ///
+    /// Frees the nodes of all popped elements and returns the nodes kept for reuse, including reserved nodes, to the allocator.
+    ///
+    /// Popped nodes are usually freed a little later than the pop, some may be kept for longer if a thread
+    /// stays in `pop` for a long time. This fn frees all of them, for example after a phase in which many threads popped.
+    /// Afterwards the lifo only holds the allocations of the elements it contains.
+    ///
+    /// Waits until no thread is popping, peeking or traversing the lifo, other threads may push at any time.
+    /// This fn never returns if it is called from within a closure passed to a fn of this lifo, for example `peek_with`,
+    /// and may wait for a long time if other threads pop all the time.
+    ///
+    /// With `LeakReclaim` popped nodes are only freed by `quiesce_unchecked` or when the lifo is dropped,
+    /// this fn then only returns the nodes kept for reuse.
+    ///
+    pub fn quiesce(&self) {
+        if Self::RECYCLE {
+            //Threads that loaded the recycled nodes before they were popped may still read their next pointers,
+            //so they are retired and freed by the strategy like any other popped node.
+            let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+            let (chain, count) = self.recycled.take_all();
+            if count != 0 {
+                //Safe, the nodes were popped with SeqCst and their values were taken.
+                unsafe {
+                    self.hazard.retire(&self.allocator, chain, count);
+                }
+            }
+        }
+
+        self.hazard.quiesce(&self.allocator);
+    }
+
+    ///
+    /// Same as `quiesce` but does not wait, for example because the calling thread is the only one that uses the lifo.
+    ///
+    /// # Safety
+    /// No other thread may pop, peek or traverse the lifo, for example with `pop`, `peek_with`, `contains` or `clone`,
+    /// while this fn is running. Pushes are fine.
+    ///
+    pub unsafe fn quiesce_unchecked(&self) {
+        self.recycled.clear(&self.allocator);
+        self.hazard.quiesce_unchecked(&self.allocator);
+    }
+
+    ///
+    /// Pushes all values of the iterator on top of the lifo stack.
+    ///
+    /// The last value of the iterator ends up on top of the lifo,
+    /// the order is the same as if all values were pushed one by one.
+    /// All values become visible to other threads at once.
+    ///
+    pub fn extend(&self, iter: impl IntoIterator<Item = T>) {
+        let mut chain = self.chain();
+        for value in iter {
+            self.hooks.on_push(&value);
+            chain.push_top(value);
+        }
+
+        self.publish_chain(chain);
+    }
+
+    ///
+    /// Pushes clones of all elements of the slice on top of the lifo stack.
+    ///
+    /// The last element of the slice ends up on top of the lifo and is popped first,
+    /// the order is the same as if the elements were pushed one by one from first to last.
+    /// All elements become visible to other threads at once, like for `extend`.
+    /// An empty slice returns right away without accessing the lifo.
+    /// For `Copy` types cloning an element is a plain copy.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::new();
+    /// lifo.extend_from_slice(&["first", "last"]);
+    /// assert_eq!(lifo.pop(), Some("last"));
+    /// assert_eq!(lifo.pop(), Some("first"));
+    /// ```
+    ///
+    pub fn extend_from_slice(&self, items: &[T])
+    where
+        T: Clone,
+    {
+        if items.is_empty() {
+            return;
+        }
+
+        self.extend(items.iter().cloned());
+    }
+
+    ///
+    /// Returns a handle that buffers pushes of the calling thread and pushes them to this lifo in batches of 64.
+    ///
+    /// See `local_with_capacity`.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::new();
+    /// let mut local = lifo.local();
+    /// local.push(1);
+    /// local.push(2);
+    /// assert!(lifo.is_empty());
+    /// assert_eq!(local.pop(), Some(2));
+    /// local.flush();
+    /// assert_eq!(lifo.pop(), Some(1));
+    /// ```
+    ///
+    #[must_use]
+    pub fn local(&self) -> LocalLifo<'_, T, A, R, H> {
+        self.local_with_capacity(LOCAL_CAPACITY)
+    }
+
+    ///
+    /// Returns a handle that buffers pushes of the calling thread and pushes them to this lifo in batches of `capacity`.
+    ///
+    /// The buffered elements are linked into a chain that no other thread can see, once `capacity` elements are buffered
+    /// the whole chain is pushed to this lifo with a single compare and swap, as if they were pushed one by one.
+    /// `LocalLifo::flush` pushes the buffered elements earlier, dropping the handle pushes them as well,
+    /// including when the thread unwinds because of a panic. Pops of the handle take the buffered elements first.
+    ///
+    /// A capacity of 0 or 1 pushes every element right away.
+    ///
+    #[must_use]
+    pub fn local_with_capacity(&self, capacity: usize) -> LocalLifo<'_, T, A, R, H> {
+        LocalLifo::new(self, capacity)
+    }
+
+    ///
+    /// Pushes all elements of the other lifo on top of this lifo.
+    ///
+    /// The elements of the other lifo are popped before the elements that were previously in this lifo,
+    /// their relative order is preserved. All elements become visible to other threads at once.
+    /// The nodes of the other lifo are reused, this is O(n) only to find the bottom of the other lifo.
+    ///
+    pub fn append(&self, mut other: Self) {
+        let zst = other.zst_len.with_mut(core::mem::take);
+        if zst != 0 {
+            for _ in 0..zst {
+                //Safe, other forgot a value for every counted value.
+                self.hooks.on_push(unsafe { zst_ref() });
+            }
+            self.push_zst(zst);
+        }
+
+        let top = other.head.replace_mut(null_mut());
+        if top.is_null() {
+            return;
+        }
+
+        let mut bottom = top;
+        let mut count = 1;
+        //Safe, we own other so no other thread can access its nodes.
+        unsafe {
+            self.hooks.on_push((*top).value());
+            while let Some(next) = (*bottom).next.with_mut(|next| next.as_mut()) {
+                self.hooks.on_push(next.value());
+                bottom = next;
+                count += 1;
+            }
+
+            self.publish(top, bottom, count);
+        }
+    }
+
+    ///
+    /// Removes all elements for which the predicate returns false.
+    ///
+    /// This removes all elements from the lifo, filters them and then pushes the retained elements back
+    /// with a single atomic operation, preserving their relative order.
+    /// While this fn is running the retained elements cannot be observed by other threads.
+    /// Elements pushed by other threads while this fn is running are not passed to the predicate
+    /// and end up below the retained elements.
+    /// If the predicate panics then the retained elements and the elements it has not returned for are pushed back.
+    ///
+    /// Only the removed elements are passed to `on_pop` of the hooks. The retained elements never left the lifo
+    /// from the point of view of the caller, they are passed to neither `on_pop` nor `on_push`.
+    ///
+    /// The nodes of the retained elements are relinked and pushed back. If other threads are popping from or looking at
+    /// this lifo at the moment the elements are removed, a new node is allocated for every retained element instead
+    /// and the old nodes are freed once those threads are done, because they may still look at the old nodes
+    /// or mistake them for the nodes they loaded earlier once they are pushed again.
+    /// Only the default `HazardReclaim` can tell, with the other strategies new nodes are always allocated.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn retain(&self, mut f: impl FnMut(&T) -> bool) {
+        let mut removed = PushBack {
+            lifo: self,
+            kept: self.chain(),
+            rest: self.detach_exclusive(),
+        };
+
+        while let Some(value) = removed.rest.top() {
+            if f(value) {
+                removed.rest.move_top(&mut removed.kept);
+            } else if let Some(value) = removed.rest.pop_top() {
+                self.hooks.on_pop(&value);
+            }
+        }
+    }
+
+    ///
+    /// Removes the first element, in the order `pop` would return them, for which the predicate returns true.
+    ///
+    /// This is O(n). It removes all elements from the lifo, searches them and then pushes all other elements back
+    /// with a single atomic operation, preserving their relative order.
+    /// This is not linearizable with concurrent calls to `pop`: while this fn is running the lifo may appear
+    /// to be empty to other threads even though it contains elements.
+    /// Elements pushed by other threads while this fn is running are not passed to the predicate
+    /// and end up below the elements that are pushed back.
+    /// If the predicate panics then all elements are pushed back.
+    ///
+    /// Only the removed element is passed to `on_pop` of the hooks, the other elements are passed to neither hook.
+    ///
+    /// The nodes of the other elements are relinked and pushed back, see `retain` for when they are not.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn remove_first(&self, mut pred: impl FnMut(&T) -> bool) -> Option<T> {
+        let mut removed = PushBack {
+            lifo: self,
+            kept: self.chain(),
+            rest: self.detach_exclusive(),
+        };
+
+        while let Some(value) = removed.rest.top() {
+            if pred(value) {
+                let value = removed.rest.pop_top()?;
+                self.hooks.on_pop(&value);
+                return Some(value);
+            }
+
+            removed.rest.move_top(&mut removed.kept);
+        }
+
+        None
+    }
+
+    ///
+    /// Exchanges the elements of this lifo with the elements of the other lifo.
+    ///
+    /// This is not a single atomic operation. All elements are removed from both lifos first
+    /// and then pushed onto the respective other lifo, preserving their relative order.
+    /// While this fn is running both lifos may appear to be empty to other threads.
+    /// Elements pushed by other threads while this fn is running stay in the lifo they were pushed to
+    /// and end up below the swapped elements.
+    /// Every element is passed to `on_pop` of the hooks of the lifo it leaves and to `on_push` of the lifo it ends up in.
+    ///
+    /// The nodes are moved to the other lifo, see `retain` for when they are not.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn swap_contents(&self, other: &Self) {
+        if core::ptr::eq(self, other) {
+            return;
+        }
+
+        let mine = self.take_chain(&other.hooks);
+        let theirs = other.take_chain(&self.hooks);
+        other.publish_chain(mine);
+        self.publish_chain(theirs);
+    }
+
+    /// Removes all elements from the lifo into a chain owned by the calling thread, see `take_exclusive`.
+    /// The values are passed to the given hooks of the lifo the chain is published to.
+    fn take_chain(&self, hooks: &H) -> Chain<T, A, R> {
+        let chain = self.take_exclusive();
+        chain.for_each(|value| hooks.on_push(value));
+        chain
+    }
+
+    /// Removes all elements from the lifo with a single atomic operation and returns them as a chain
+    /// that no other thread can access. The values are passed to `on_pop`.
+    fn take_exclusive(&self) -> Chain<T, A, R> {
+        let chain = self.detach_exclusive();
+        chain.for_each(|value| self.hooks.on_pop(value));
+        chain
+    }
+
+    /// Removes all elements from the lifo with a single atomic operation and returns them as a chain
+    /// that no other thread can access, for fns that push most of them back.
+    /// The values are not passed to the hooks, the caller passes the values it does not push back to `on_pop`.
+    ///
+    /// The removed nodes are reused if no thread was accessing the lifo once they were removed.
+    /// Otherwise the values are moved into new nodes and the removed nodes are retired,
+    /// the threads may still look at them or compare and swap the head with them once they are pushed again.
+    fn detach_exclusive(&self) -> Chain<T, A, R> {
+        let mut chain = self.chain();
+        if self.is_empty() {
+            return chain;
+        }
+
+        if Self::ZST {
+            chain.count = self.pop_zst(usize::MAX, false);
+            return chain;
+        }
+
+        let top = self.head.take(SeqCst);
+        //Checked after the take, a thread that enters afterward cannot load the removed nodes anymore.
+        if self.hazard.is_quiescent() {
+            chain.top = top;
+            let mut cur = top;
+            //Safe, no other thread can access the nodes.
+            unsafe {
+                while let Some(node) = cur.as_ref() {
+                    chain.bottom = cur;
+                    chain.count += 1;
+                    cur = node.next();
+                }
+            }
+        } else if !top.is_null() {
+            let mut cur = top;
+            //Safe, we removed the nodes, other threads may only look at them.
+            unsafe {
+                while let Some(node) = cur.as_ref() {
+                    cur = node.next();
+                    chain.push_bottom(node.take());
+                }
+            }
+
+            let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+            unsafe {
+                self.hazard.retire(&self.allocator, top, chain.count);
+            }
+        }
+
+        #[cfg(feature = "count")]
+        self.len.fetch_sub(chain.count, Relaxed);
+        #[cfg(feature = "stats")]
+        self.counters.pops.add(chain.count);
+        chain
+    }
+
+    ///
+    /// Pops the bottom of the lifo stack, which is the element that has been in the lifo the longest.
+    ///
+    /// This is O(n). It removes all elements from the lifo, takes the bottom element and then pushes all other elements back
+    /// with a single atomic operation, preserving their relative order.
+    /// This is not linearizable with concurrent calls to `pop`: while this fn is running the lifo may appear
+    /// to be empty to other threads even though it contains elements.
+    /// Elements pushed by other threads while this fn is running end up below the elements that are pushed back.
+    ///
+    /// Only the bottom element is passed to `on_pop` of the hooks, the other elements are passed to neither hook.
+    ///
+    /// The nodes of the other elements are relinked and pushed back, see `retain` for when they are not.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_bottom(&self) -> Option<T> {
+        let mut others = self.detach_exclusive();
+        let bottom = others.pop_bottom();
+        self.publish_chain(others);
+        let bottom = bottom?;
+        self.hooks.on_pop(&bottom);
+        Some(bottom)
+    }
+
+    /// Constructs a new empty chain that uses the allocator of this lifo.
+    fn chain(&self) -> Chain<T, A, R> {
+        Chain::new(self.allocator.clone())
+    }
+
+    /// Links all nodes of the chain on top of the lifo.
+    fn publish_chain(&self, chain: Chain<T, A, R>) {
+        let (top, bottom, count, _) = chain.into_parts();
+        if count == 0 {
+            return;
+        }
+
+        if Self::ZST {
+            self.push_zst(count);
+            return;
+        }
+
+        //Safe, the nodes of a chain were never part of the lifo or no other thread can access them anymore.
+        unsafe {
+            self.publish(top, bottom, count);
+        }
+    }
+
+    /// Adds `count` values of a zero sized type that the caller has forgotten.
+    fn push_zst(&self, count: usize) {
+        #[cfg(feature = "count")]
+        self.count_pushed(count);
+        #[cfg(feature = "stats")]
+        self.counters.pushes.add(count);
+        //Same as the head, pops of the values must see everything that happened before they were pushed.
+        self.zst_len.fetch_add(count, PUBLISH);
+        self.notify_pushed(count);
+    }
+
+    /// Removes up to `max` values of a zero sized type and returns how many were removed.
+    /// If `exact` is true nothing is removed unless there are at least `max` values.
+    /// The caller must create a value for every removed value.
+    fn pop_zst(&self, max: usize, exact: bool) -> usize {
+        let mut current = self.zst_len.load(SeqCst);
+        let removed = loop {
+            let removed = current.min(max);
+            if removed == 0 || (exact && removed < max) {
+                return 0;
+            }
+
+            match self
+                .zst_len
+                .compare_exchange_weak(current, current - removed, SeqCst, SeqCst)
+            {
+                Ok(_) => break removed,
+                Err(actual) => {
+                    #[cfg(feature = "stats")]
+                    self.counters.pop_retries.add(1);
+                    current = actual;
+                }
+            }
+        };
+
+        #[cfg(feature = "count")]
+        self.len.fetch_sub(removed, Relaxed);
+        #[cfg(feature = "stats")]
+        self.counters.pops.add(removed);
+        removed
+    }
+
+    /// Links the chain of `count` nodes from `top` to `bottom` on top of the lifo.
+    ///
+    /// # Safety
+    /// The chain must be exclusively owned by the caller. Its nodes may only have been part of this lifo before
+    /// if they were taken out of the recycle list or removed by `take_exclusive`.
+    #[cfg_attr(
+        not(any(feature = "count", feature = "stats")),
+        allow(unused_variables)
+    )]
+    unsafe fn publish(&self, top: *mut Node<T, R>, bottom: *mut Node<T, R>, count: usize) {
+        //Not a mutable reference, threads that loaded a recycled node may still read its next pointer.
+        let bottom_ref = &*bottom;
+
+        //Incremented before the nodes become visible so a concurrent pop can never decrement it below 0.
+        //Relaxed is enough for that, the pop acquires the head that we release, so our increment happens before its decrement.
+        #[cfg(feature = "count")]
+        self.count_pushed(count);
+        #[cfg(feature = "stats")]
+        self.counters.pushes.add(count);
+
+        //Relaxed, the head is only used as a guess of the next pointer, the compare and swap checks it.
+        let mut head = self.head.load(Relaxed);
+        let mut backoff = Backoff::new();
+        loop {
+            bottom_ref.next.store(head.ptr, Relaxed);
+            match self.head.publish_weak(head, top, PUBLISH, Relaxed) {
+                Ok(()) => break,
+                Err(current) => {
+                    #[cfg(feature = "stats")]
+                    self.counters.push_retries.add(1);
+                    //A pop took the node, nothing was added to the lifo that waiting threads could pop.
+                    if count == 1 && self.eliminate_push(top, backoff.attempts()) {
+                        trace::retries("push", backoff.attempts());
+                        return;
+                    }
+
+                    head = current;
+                    backoff.snooze();
+                }
+            }
+        }
+
+        trace::retries("push", backoff.attempts());
+        self.notify_pushed(count);
+    }
+
+    /// Hands the node of a push whose compare and swap failed to a pop in the elimination array.
+    /// Returns true if a pop took the node, the push is then done.
+    fn eliminate_push(&self, node: *mut Node<T, R>, attempt: u32) -> bool {
+        self.elimination
+            .as_ref()
+            .is_some_and(|elimination| elimination.push(node, attempt))
+    }
+
+    /// Takes the node of a push from the elimination array for a pop whose compare and swap failed.
+    fn eliminate_pop(&self, attempt: u32) -> Option<*mut Node<T, R>> {
+        let node = self.elimination.as_ref()?.pop(attempt)?;
+        #[cfg(feature = "stats")]
+        self.counters.eliminations.add(1);
+        Some(node)
+    }
+
+    /// Counts `count` elements that are about to be pushed and raises the largest amount of elements seen.
+    #[cfg(feature = "count")]
+    fn count_pushed(&self, count: usize) {
+        let len = self.len.fetch_add(count, Relaxed) + count;
+        //Fast path, once the maximum was reached pushes usually stay below it and only read it.
+        if len > self.max_len.load(Relaxed) {
+            self.max_len.fetch_max(len, Relaxed);
+        }
+    }
+
+    /// Wakes threads and tasks waiting for elements after `count` elements were pushed.
+    #[cfg_attr(
+        not(any(feature = "std", feature = "async")),
+        allow(unused_variables, clippy::unused_self, clippy::missing_const_for_fn)
+    )]
+    fn notify_pushed(&self, count: usize) {
+        #[cfg(feature = "std")]
+        self.wake(count);
+        #[cfg(feature = "async")]
+        self.wake_tasks(count);
+    }
+
+    /// Wakes up to `count` tasks that are waiting for an element.
+    #[cfg(feature = "async")]
+    fn wake_tasks(&self, count: usize) {
+        //Fast path, no task ever had to wait.
+        let Some(wakers) = (unsafe { self.wakers.load(SeqCst).as_ref() }) else {
+            return;
+        };
+
+        wakers.wake(count);
+    }
+
+    /// Returns the waker slots of waiting tasks, allocates them if they do not exist yet.
+    #[cfg(feature = "async")]
+    fn wakers(&self) -> &waker::Wakers {
+        let current = self.wakers.load(SeqCst);
+        if let Some(wakers) = unsafe { current.as_ref() } {
+            return wakers;
+        }
+
+        let new = Box::into_raw(Box::new(waker::Wakers::new()));
+        match self
+            .wakers
+            .compare_exchange(null_mut(), new, SeqCst, SeqCst)
+        {
+            //Safe, the list is only freed when the lifo is dropped.
+            Ok(_) => unsafe { &*new },
+            Err(existing) => unsafe {
+                _ = Box::from_raw(new);
+                &*existing
+            },
+        }
+    }
+
+    ///
+    /// Returns a future that pops the top of the lifo stack once an element is available.
+    ///
+    /// The future does not depend on any particular executor.
+    /// Pushing only has to wake tasks if some task ever had to wait for an element,
+    /// otherwise it costs a single additional atomic load.
+    /// The future never resolves if no element is ever pushed.
+    ///
+    #[cfg(feature = "async")]
+    pub const fn pop_async(&self) -> PopFuture<'_, T, A, R, H> {
+        PopFuture::new(self)
+    }
+
+    ///
+    /// Returns a stream that pops elements as they become available.
+    ///
+    /// The stream never terminates, an empty lifo makes it wait for the next element instead.
+    /// Multiple streams may pop from the same lifo, every element is only returned by one of them.
+    ///
+    #[cfg(feature = "async")]
+    pub const fn stream(&self) -> LifoStream<'_, T, A, R, H> {
+        LifoStream::new(self)
+    }
+
+    /// Pops the top of the lifo stack or registers the waker of the task to be woken once an element is pushed.
+    ///
+    /// The waker is registered in the slot of the calling future, which is acquired the first time it has to wait
+    /// and has to be released with `release_waker` when the future is dropped.
+    #[cfg(feature = "async")]
+    pub(crate) fn poll_pop<'s>(
+        &'s self,
+        cx: &core::task::Context<'_>,
+        slot: &mut Option<&'s waker::WakerSlot>,
+    ) -> core::task::Poll<T> {
+        if let Some(value) = self.pop() {
+            return core::task::Poll::Ready(value);
+        }
+
+        let wakers = self.wakers();
+        let slot = *slot.get_or_insert_with(|| wakers.acquire());
+        //Safe, every future owns the slot it acquired.
+        unsafe {
+            wakers.register(slot, cx.waker());
+        }
+
+        //An element pushed before the waker was registered would not wake us.
+        if let Some(value) = self.pop() {
+            if !self.is_empty() {
+                //Our waker may have been woken for an element that we did not take, pass that on.
+                self.wake_tasks(1);
+            }
+
+            return core::task::Poll::Ready(value);
+        }
+
+        core::task::Poll::Pending
+    }
+
+    /// Releases the waker slot of a future that is dropped.
+    /// If the future was woken but did not pop an element then another waiting task is woken instead.
+    ///
+    /// # Safety
+    /// The slot must have been acquired by `poll_pop` of the dropped future.
+    #[cfg(feature = "async")]
+    pub(crate) unsafe fn release_waker(&self, slot: &waker::WakerSlot, popped: bool) {
+        if self.wakers().release(slot) && !popped && !self.is_empty() {
+            self.wake_tasks(1);
+        }
+    }
+
+    /// Unparks up to `count` threads that are waiting in `pop_blocking`.
+    #[cfg(feature = "std")]
+    fn wake(&self, count: usize) {
+        if self.waiter_count.load(SeqCst) == 0 {
+            //Fast path, nobody is waiting.
+            return;
+        }
+
+        for _ in 0..count {
+            let Some(waiter) = self.lock_waiters().pop() else {
+                break;
+            };
+
+            self.waiter_count.fetch_sub(1, SeqCst);
+            waiter.unpark();
+        }
+    }
+
+    /// Locks the list of waiting threads, the list is always consistent so poisoning is ignored.
+    #[cfg(feature = "std")]
+    fn lock_waiters(&self) -> std::sync::MutexGuard<'_, Vec<std::thread::Thread>> {
+        self.waiters
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Removes the calling thread from the list of waiting threads.
+    /// Returns false if the thread was already removed by a call to `wake`.
+    #[cfg(feature = "std")]
+    fn remove_waiter(&self) -> bool {
+        let id = std::thread::current().id();
+        let mut waiters = self.lock_waiters();
+        let removed = waiters
+            .iter()
+            .position(|waiter| waiter.id() == id)
+            .map(|index| waiters.swap_remove(index));
+        drop(waiters);
+
+        if removed.is_none() {
+            return false;
+        }
+
+        self.waiter_count.fetch_sub(1, SeqCst);
+        true
+    }
+
+    ///
+    /// Pops the top of the lifo stack, parks the calling thread until an element is pushed if the lifo is empty.
+    ///
+    /// Pushing only has to unpark threads if some thread is waiting in this fn,
+    /// otherwise it costs a single additional atomic load.
+    /// This fn never returns if no element is ever pushed,
+    /// to shut down threads waiting in this fn push a sentinel element for each of them.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    #[cfg(feature = "std")]
+    pub fn pop_blocking(&self) -> T {
+        //Safe, without a deadline pop_until never returns None.
+        unsafe { self.pop_until(None).unwrap_unchecked() }
+    }
+
+    ///
+    /// Pops the top of the lifo stack, parks the calling thread for up to `timeout` until an element is pushed if the lifo is empty.
+    ///
+    /// Returns None if no element could be popped before the timeout elapsed.
+    /// A zero timeout makes this behave like `pop`.
+    /// An element pushed right as the timeout elapses is either returned or remains in the lifo.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    #[cfg(feature = "std")]
+    pub fn pop_timeout(&self, timeout: core::time::Duration) -> Option<T> {
+        //A timeout too large to be represented is as good as no timeout.
+        let deadline = std::time::Instant::now().checked_add(timeout);
+        self.pop_until(deadline)
+    }
+
+    /// Pops the top of the lifo stack, parks the calling thread until an element is pushed or the deadline is reached.
+    /// Only returns None once the deadline is reached.
+    #[cfg(feature = "std")]
+    fn pop_until(&self, deadline: Option<std::time::Instant>) -> Option<T> {
+        loop {
+            if let Some(value) = self.pop() {
+                return Some(value);
+            }
+
+            let timeout = match deadline {
+                None => None,
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+                    if remaining.is_zero() {
+                        return None;
+                    }
+
+                    Some(remaining)
+                }
+            };
+
+            //Incremented first so it is never smaller than the amount of registered threads.
+            self.waiter_count.fetch_add(1, SeqCst);
+            self.lock_waiters().push(std::thread::current());
+
+            //An element pushed before we were registered would not unpark us.
+            if let Some(value) = self.pop() {
+                if !self.remove_waiter() {
+                    //We were unparked for an element that we did not take, pass that on.
+                    self.wake(1);
+                }
+
+                return Some(value);
+            }
+
+            match timeout {
+                None => std::thread::park(),
+                Some(timeout) => std::thread::park_timeout(timeout),
+            }
+
+            //We are still registered after a spurious wake-up or a timeout.
+            self.remove_waiter();
+        }
+    }
+
+    ///
+    /// Pops the top of the lifo stack
+    ///
+    /// If another thread is currently inspecting the top element using `peek_with` then
+    /// this fn waits until the closure passed to `peek_with` has returned.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made, see `try_pop`.
+    ///
+    pub fn pop(&self) -> Option<T> {
+        match self.try_pop() {
+            Ok(value) => value,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    ///
+    /// Same as `pop` but returns an error instead of panicking if too many threads are popping at the same time.
+    ///
+    /// Popping threads are counted to know when popped nodes can be freed, see `PopContention`.
+    /// The lifo is left unchanged if the calling thread could not be counted, so it may simply try again later.
+    ///
+    /// # Errors
+    /// if more than `isize::MAX` threads are popping, peeking or traversing the lifo at the same time.
+    ///
+    pub fn try_pop(&self) -> Result<Option<T>, PopContention> {
+        if self.is_empty() {
+            //Fast path, no need to do any hazard bookkeeping if there is nothing to pop.
+            self.count_empty_pop();
+            return Ok(None);
+        }
+
+        if Self::ZST {
+            if self.pop_zst(1, false) == 0 {
+                self.count_empty_pop();
+                return Ok(None);
+            }
+
+            //Safe, we forgot a value for every counted value.
+            let value = unsafe { zst() };
+            self.hooks.on_pop(&value);
+            return Ok(Some(value));
+        }
+
+        let _entered = self.hazard.try_enter(&self.allocator, self.hazard_limit)?;
+
+        //The head is loaded with SeqCst, not just Acquire. Nodes may only be freed once every thread that loaded them has left,
+        //which relies on our increment in enter being ordered before this load in the single total order of SeqCst operations
+        //that the last thread to leave observes. The same goes for the waiter handshake, see PUBLISH.
+        let mut head = self.head.load(SeqCst);
+        let mut backoff = Backoff::new();
+        let removed = loop {
+            //The node may have been popped and recycled since we loaded it, then the compare and swap fails.
+            let Some(node) = (unsafe { head.ptr.as_ref() }) else {
+                self.count_empty_pop();
+                return Ok(None);
+            };
+            let next = node.next.load(Relaxed);
+
+            match self.head.remove_weak(head, next, SeqCst, SeqCst) {
+                Ok(()) => break head.ptr,
+                //The failure ordering is SeqCst as well, we load the next pointer of the current head.
+                Err(current) => {
+                    #[cfg(feature = "stats")]
+                    self.counters.pop_retries.add(1);
+                    //The node of a push that was never part of the lifo, it is retired like a popped node.
+                    if let Some(node) = self.eliminate_pop(backoff.attempts()) {
+                        break node;
+                    }
+
+                    head = current;
+                    backoff.snooze();
+                }
+            }
+        };
+
+        trace::retries("pop", backoff.attempts());
+        #[cfg(feature = "count")]
+        self.len.fetch_sub(1, Relaxed);
+        #[cfg(feature = "stats")]
+        self.counters.pops.add(1);
+
+        //Safe, removed must be non-null and we "own" it here for a very short time. Other thread may be currently looking at the next pointer only.
+        let removed_obj = unsafe { (*removed).take() };
+
+        //Safe, the node was removed with SeqCst and its value was taken.
+        unsafe {
+            self.release(removed);
+        }
+
+        self.hooks.on_pop(&removed_obj);
+        Ok(Some(removed_obj))
+    }
+
+    ///
+    /// Pops the top of the lifo stack, waits briefly for `push_or_exchange` to hand over a value if the lifo is empty.
+    ///
+    /// Only one thread at a time can wait for a value, other threads that find the lifo empty
+    /// meanwhile behave like `pop`. The thread spins instead of parking, so it only waits for a few microseconds.
+    /// Values pushed with `push` or another fn are not handed over, they are popped after the wait.
+    /// Returns None if no value was handed over and the lifo is still empty afterward.
+    ///
+    /// A waiting thread that gives up while a pusher hands it a value either gets the value
+    /// or the pusher pushes the value to the lifo, the value is never lost and never returned twice.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_waiting(&self) -> Option<T> {
+        if let Some(value) = self.pop() {
+            return Some(value);
+        }
+
+        if Self::ZST {
+            return None;
+        }
+
+        let Some(node) = self.exchange.request() else {
+            return self.pop();
+        };
+
+        #[cfg(feature = "stats")]
+        {
+            self.counters.pops.add(1);
+            self.counters.exchanges.add(1);
+        }
+
+        //The node may have been recycled, threads that loaded it before may still read its next pointer.
+        let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+        //Safe, the pusher handed the node to us and never published it.
+        let value = unsafe { (*node).take() };
+        unsafe {
+            self.release(node);
+        }
+
+        self.hooks.on_pop(&value);
+        Some(value)
+    }
+
+    ///
+    /// Replaces the top of the lifo stack with the value and returns the former top.
+    ///
+    /// Unlike a `pop` followed by a `push` no other thread can push or pop in between, the value takes the place
+    /// of the former top with a single atomic operation. A concurrent `pop` returns either the former top or the value.
+    /// The lifo keeps the same amount of elements. The hooks see the value pushed once it replaced the former top
+    /// and the former top popped, they are not called if the lifo was empty.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::new();
+    /// assert_eq!(lifo.swap_top(1), Err(1));
+    /// lifo.push(1);
+    /// assert_eq!(lifo.swap_top(2), Ok(1));
+    /// assert_eq!(lifo.pop(), Some(2));
+    /// ```
+    ///
+    /// # Errors
+    /// returns the value if the lifo is empty, it is not pushed then.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn swap_top(&self, value: T) -> Result<T, T> {
+        if self.is_empty() {
+            return Err(value);
+        }
+
+        if Self::ZST {
+            //All values are equal, replacing one of them changes nothing.
+            self.hooks.on_push(&value);
+            self.hooks.on_pop(&value);
+            return Ok(value);
+        }
+
+        //Not a mutable reference, threads that loaded a recycled node may still read its next pointer.
+        let node = self.alloc(value).as_ptr();
+        let node_ref = unsafe { &*node };
+        //Borrowed before the node is published, a pop that removes it right away waits until on_push has returned.
+        let borrow = node_ref.try_borrow();
+
+        let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+        //SeqCst for the same reasons as in pop, the former top is removed from the lifo.
+        let mut head = self.head.load(SeqCst);
+        let mut backoff = Backoff::new();
+        let replaced = loop {
+            let Some(top) = (unsafe { head.ptr.as_ref() }) else {
+                //Became empty in the meantime, the value was never visible to other threads or the hooks.
+                drop(borrow);
+                //Safe, the node was never published.
+                let value = unsafe { node_ref.take() };
+                unsafe {
+                    self.release(node);
+                }
+
+                return Err(value);
+            };
+
+            node_ref.next.store(top.next.load(Relaxed), Relaxed);
+            //A removal, the former top may be reused once it was released, which the dwcas head has to detect.
+            match self.head.remove_weak(head, node, SeqCst, SeqCst) {
+                Ok(()) => break head.ptr,
+                Err(current) => {
+                    #[cfg(feature = "stats")]
+                    self.counters.pop_retries.add(1);
+                    head = current;
+                    backoff.snooze();
+                }
+            }
+        };
+
+        trace::retries("swap_top", backoff.attempts());
+        #[cfg(feature = "stats")]
+        {
+            self.counters.pushes.add(1);
+            self.counters.pops.add(1);
+        }
+
+        if let Some(borrow) = borrow {
+            self.hooks.on_push(&borrow);
+        }
+
+        //Safe, the former top was removed with SeqCst.
+        let replaced_obj = unsafe { (*replaced).take() };
+        unsafe {
+            self.release(replaced);
+        }
+
+        self.hooks.on_pop(&replaced_obj);
+        Ok(replaced_obj)
+    }
+
+    ///
+    /// Pops the top of the lifo stack and moves it into a new box.
+    ///
+    /// Values are stored inline in the nodes, so this allocates the box and moves the value into it,
+    /// this is the same as `pop().map(Box::new)`.
+    /// To keep large values in their own allocation and only move the pointer, use an `AtomicLifo<Box<T>>` instead.
+    ///
+    /// If another thread is currently inspecting the top element using `peek_with` then
+    /// this fn waits until the closure passed to `peek_with` has returned.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    #[deprecated = "values are stored inline in the nodes, use `pop().map(Box::new)` or an `AtomicLifo<Box<T>>` to keep the allocation"]
+    pub fn pop_boxed(&self) -> Option<Box<T>> {
+        self.pop().map(Box::new)
+    }
+
+    ///
+    /// Pops the top of the lifo stack and returns a guard that pushes it back once it is dropped.
+    ///
+    /// Use `PopGuard::commit` to keep the value. If processing the value fails, for example due to an early return
+    /// or a panic, the guard pushes the value back on top of the lifo.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_guarded(&self) -> Option<PopGuard<'_, T, A, R, H>> {
+        self.pop().map(|value| PopGuard::new(self, value))
+    }
+
+    ///
+    /// Pops the top of the lifo stack or returns the default value if the lifo is empty.
+    ///
+    /// An empty lifo is detected without any hazard bookkeeping, making the fallback path cheap.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_or_default(&self) -> T
+    where
+        T: Default,
+    {
+        self.pop_or_else(T::default)
+    }
+
+    ///
+    /// Pops the top of the lifo stack or returns the result of the closure if the lifo is empty.
+    ///
+    /// An empty lifo is detected without any hazard bookkeeping, making the fallback path cheap.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_or_else(&self, f: impl FnOnce() -> T) -> T {
+        //pop checks for an empty lifo before entering.
+        self.pop().unwrap_or_else(f)
+    }
+
+    ///
+    /// Returns an iterator that pops elements until the lifo is empty.
+    ///
+    /// The iterator is fused, once it returned None it will keep returning None
+    /// even if other threads push elements afterward.
+    /// Dropping the iterator leaves all elements it has not yet returned in the lifo.
+    ///
+    pub const fn drain(&self) -> Drain<'_, T, A, R, H> {
+        Drain::new(self)
+    }
+
+    ///
+    /// Returns a parallel iterator that pops elements on the threads of the current rayon thread pool until the lifo is empty.
+    ///
+    /// Every rayon task pops a batch of elements with `pop_n` and pops the next batch once it used up its own,
+    /// while elements are left further tasks are split off to idle threads. Every element is yielded exactly once,
+    /// in no particular order. The iterator ends once a task finds the lifo empty, so elements pushed by other threads
+    /// at the same time may or may not be yielded.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let lifo: AtomicLifo<u64> = (1..=1000).collect();
+    /// assert_eq!(lifo.par_drain().sum::<u64>(), 500_500);
+    /// assert!(lifo.is_empty());
+    /// ```
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to `pop_n` are made.
+    ///
+    #[cfg(all(feature = "rayon", not(loom)))]
+    pub fn par_drain(&self) -> impl rayon::iter::ParallelIterator<Item = T> + '_
+    where
+        A: Sync,
+    {
+        par::drain(self)
+    }
+
+    ///
+    /// Removes all elements from the lifo with a single atomic operation and returns an iterator over them.
+    ///
+    /// The iterator yields the elements in the same order `pop` would have returned them.
+    /// Elements not yielded by the iterator are dropped when the iterator is dropped.
+    /// Elements pushed by other threads after this call remain in the lifo.
+    ///
+    /// This is a lot cheaper than calling `pop` for every element.
+    /// The removed elements are counted as popped right away, with the `count` or `stats` feature
+    /// this walks the removed chain once and is O(n), without them it is O(1).
+    ///
+    pub fn take_all(&self) -> OwnedChain<'_, T, A, R, H> {
+        if self.is_empty() {
+            return OwnedChain::new(self, null_mut(), 0);
+        }
+
+        if Self::ZST {
+            return OwnedChain::new(self, null_mut(), self.pop_zst(usize::MAX, false));
+        }
+
+        let chain = self.head.take(SeqCst);
+
+        #[cfg(any(feature = "count", feature = "stats"))]
+        {
+            let mut removed = 0;
+            let mut cur = chain;
+            //Safe, the chain was removed from the lifo and its nodes are only retired once the iterator is dropped.
+            while let Some(node) = unsafe { cur.as_ref() } {
+                removed += 1;
+                cur = node.next();
+            }
+
+            self.count_popped(removed);
+        }
+
+        OwnedChain::new(self, chain, 0)
+    }
+
+    ///
+    /// Removes all elements from the lifo with a single atomic operation and returns a new lifo containing them.
+    ///
+    /// The new lifo pops the elements in the same order this lifo would have.
+    /// Elements pushed by other threads after this call remain in this lifo.
+    ///
+    /// The nodes are moved into the new lifo, see `retain` for when they are not.
+    ///
+    #[must_use]
+    pub fn take(&self) -> Self
+    where
+        H: Clone,
+    {
+        let hooks = self.hooks.clone();
+        Self::from_chain(self.take_chain(&hooks), self.hazard_limit, hooks)
+    }
+
+    ///
+    /// Removes all elements from the lifo with a single atomic operation and returns an iterator over them
+    /// that yields the oldest element first.
+    ///
+    /// The iterator yields the elements in the order they were pushed, which is the reverse of the order `pop` would have returned them.
+    /// Elements not yielded by the iterator are dropped when the iterator is dropped.
+    /// Elements pushed by other threads after this call remain in the lifo.
+    ///
+    /// The nodes are relinked in reverse order, see `retain` for when they are not.
+    ///
+    pub fn drain_fifo(&self) -> OwnedChainFifo<T, A, R> {
+        let mut reversed = self.take_exclusive();
+        reversed.reverse();
+        let (top, _, count, allocator) = reversed.into_parts();
+        let zst = if Self::ZST { count } else { 0 };
+        OwnedChainFifo::new(IntoIter::new(top, zst, allocator))
+    }
+
+    ///
+    /// Removes and drops all elements of the lifo.
+    ///
+    /// Elements pushed by other threads after the elements were removed remain in the lifo.
+    ///
+    pub fn clear(&self) {
+        drop(self.take_all());
+    }
+
+    ///
+    /// Consumes the lifo and returns all elements in the order `pop` would have returned them.
+    ///
+    /// Owning the lifo, this walks the nodes with plain reads instead of popping the elements one by one,
+    /// moves every value out once and frees the nodes as well as the nodes of popped elements that were waiting to be freed.
+    ///
+    /// `AtomicLifo::from(vec).into_vec()` returns the elements of `vec` in reverse order.
+    ///
+    #[must_use]
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    ///
+    /// Consumes the lifo and returns all elements in the order `pop` would have returned them.
+    ///
+    /// Same as `into_vec`, named like the `into_inner` of other containers that give up their contents.
+    ///
+    #[must_use]
+    pub fn into_inner(self) -> Vec<T> {
+        self.into_vec()
+    }
+
+    ///
+    /// Returns true if the lifo contains no elements.
+    ///
+    /// This is only a snapshot, other threads may push or pop elements at any time
+    /// so the returned value may already be stale once this fn returns.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        if Self::ZST {
+            return self.zst_len.load(SeqCst) == 0;
+        }
+
+        self.head.ptr(SeqCst).is_null()
+    }
+
+    ///
+    /// Returns the amount of elements in the lifo.
+    ///
+    /// This is only a snapshot, other threads may push or pop elements at any time.
+    /// Elements that are currently being pushed by other threads are already counted
+    /// while they cannot be popped yet. Once all concurrent calls have returned the value is exact.
+    ///
+    #[cfg(feature = "count")]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len.load(Relaxed)
+    }
+
+    ///
+    /// Returns the largest amount of elements the lifo contained since it was constructed or `reset_max_len` was called.
+    ///
+    /// Like `len` this counts elements that are currently being pushed by other threads,
+    /// so it may be slightly larger than the amount of elements that could have been popped at once.
+    ///
+    #[cfg(feature = "count")]
+    #[must_use]
+    pub fn max_len_seen(&self) -> usize {
+        self.max_len.load(Relaxed)
+    }
+
+    ///
+    /// Resets the largest amount of elements seen to the current amount of elements.
+    ///
+    /// Elements pushed by other threads while this fn is running may be missed by the maximum.
+    ///
+    #[cfg(feature = "count")]
+    pub fn reset_max_len(&self) {
+        self.max_len.store(self.len(), Relaxed);
+    }
+
+    ///
+    /// Returns a `Debug` representation that only prints the approximate amount of elements,
+    /// for lifos whose elements do not implement `Debug`.
+    ///
+    /// The elements are not looked at, so unlike the `Debug` impl of the lifo this does not require `T: Debug + Sync`.
+    /// Without the `count` feature the amount is determined by a traversal.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::<Box<dyn FnOnce() + Send>>::new();
+    /// lifo.push(Box::new(|| {}));
+    /// assert_eq!(format!("{:?}", lifo.debug_len()), "AtomicLifo { len: ~1, .. }");
+    /// ```
+    ///
+    #[must_use]
+    pub fn debug_len(&self) -> impl Debug + '_ {
+        DebugLen(self)
+    }
+
+    /// Returns the amount of elements in the lifo without requiring the `count` feature.
+    /// Without the feature this is O(n) and elements popped during the traversal are not counted.
+    #[cfg(feature = "count")]
+    fn approx_len(&self) -> usize {
+        self.len()
+    }
+
+    /// Returns the amount of elements in the lifo without requiring the `count` feature.
+    /// Without the feature this is O(n) and elements popped during the traversal are not counted.
+    #[cfg(not(feature = "count"))]
+    fn approx_len(&self) -> usize {
+        if Self::ZST {
+            return self.zst_len.load(SeqCst);
+        }
+
+        let mut count = 0;
+        self.walk(|node| {
+            //Relaxed, the value is not accessed, a node that is being popped concurrently may or may not be counted.
+            if node.state.load(Relaxed) & Node::<T, R>::TAKEN == 0 {
+                count += 1;
+            }
+
+            true
+        });
+
+        count
+    }
+
+    /// Counts `count` elements that were removed from the lifo as popped, see `take_all`.
+    #[cfg(any(feature = "count", feature = "stats"))]
+    fn count_popped(&self, count: usize) {
+        #[cfg(feature = "count")]
+        self.len.fetch_sub(count, Relaxed);
+        #[cfg(feature = "stats")]
+        self.counters.pops.add(count);
+    }
+
+    /// Counts a pop that found the lifo empty and passes it to the hooks.
+    fn count_empty_pop(&self) {
+        #[cfg(feature = "stats")]
+        self.counters.empty_pops.add(1);
+        self.hooks.on_empty_pop();
+    }
+
+    /// Returns the hooks this lifo passes its elements to, see `with_hooks`.
+    #[must_use]
+    pub const fn hooks(&self) -> &H {
+        &self.hooks
+    }
+
+    ///
+    /// Sets the fn that receives every element that is still in the lifo when it is dropped,
+    /// for example to fail requests that were never processed instead of only running their destructors.
+    ///
+    /// The elements are passed in the order `pop` would have returned them, each exactly once.
+    /// Elements that are moved out of the lifo by `into_iter`, `into_vec`, `append` or `take` are not passed to it.
+    /// A clone of the lifo passes its elements to the same handler.
+    /// Popped elements are never passed to it either, the nodes waiting to be freed no longer contain them.
+    ///
+    /// If the handler panics the elements it did not receive yet are dropped without it
+    /// and all nodes are freed before the panic continues.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let mut lifo = AtomicLifo::new();
+    /// lifo.set_drop_handler(|request: u32| println!("request {request} was not processed"));
+    /// lifo.push(1);
+    /// drop(lifo);
+    /// ```
+    ///
+    pub fn set_drop_handler(&mut self, handler: fn(T)) {
+        self.drop_handler = Some(handler);
+    }
+
+    /// Removes the elements one by one and passes them to the drop handler.
+    /// Every element is removed before the handler is called, so a panicking handler leaves only the elements it did not get.
+    fn drain_into_handler(&mut self, handler: fn(T)) {
+        while self.zst_len.with_mut(|len| *len != 0) {
+            self.zst_len.with_mut(|len| *len -= 1);
+            //Safe, a value was forgotten for every counted value.
+            handler(unsafe { zst() });
+        }
+
+        loop {
+            let node = self.head.get_mut();
+            if node.is_null() {
+                return;
+            }
+
+            //Safe, we have exclusive access, the node is unlinked before its value is moved out and it is freed.
+            let value = unsafe {
+                self.head.replace_mut((*node).next.with_mut(|next| *next));
+                let value = (*node).value.get_mut().assume_init_read();
+                free_in(&self.allocator, node);
+                value
+            };
+            handler(value);
+        }
+    }
+
+    ///
+    /// Returns a snapshot of the counters of the operations of the lifo and of its reclamation strategy.
+    ///
+    /// The counters are only incremented with Relaxed, they cost almost nothing but may be inconsistent
+    /// with each other while other threads use the lifo. Once all concurrent calls have returned they are exact.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::new();
+    /// lifo.push(1);
+    /// lifo.pop();
+    /// lifo.pop();
+    /// let stats = lifo.stats();
+    /// assert_eq!((stats.pushes, stats.pops, stats.empty_pops), (1, 1, 1));
+    /// ```
+    ///
+    #[cfg(feature = "stats")]
+    #[must_use]
+    pub fn stats(&self) -> LifoStats {
+        let reclaim = self.hazard.counters();
+        LifoStats {
+            pushes: self.counters.pushes.get(),
+            pops: self.counters.pops.get(),
+            empty_pops: self.counters.empty_pops.get(),
+            push_retries: self.counters.push_retries.get(),
+            pop_retries: self.counters.pop_retries.get(),
+            retired: reclaim.retired.get(),
+            freed: reclaim.freed.get(),
+            hazard_limit_hits: reclaim.limit_hits.get(),
+            eliminations: self.counters.eliminations.get(),
+            exchanges: self.counters.exchanges.get(),
+        }
+    }
+
+    ///
+    /// Pops the top of the lifo stack if the predicate returns true for it.
+    ///
+    /// Returns None if the lifo is empty or if the predicate returned false for the top element.
+    /// If another thread changes the top of the lifo after the predicate was evaluated
+    /// then the predicate is evaluated again for the new top element.
+    /// The predicate may therefore be called more than once.
+    ///
+    /// A concurrent call to `pop` that removes the element the predicate is looking at
+    /// will wait until the predicate has returned.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_if(&self, pred: impl FnMut(&T) -> bool) -> Option<T>
+    where
+        T: Sync,
+    {
+        self.pop_top_if(pred).ok()
+    }
+
+    ///
+    /// Pops the top of the lifo stack if it is equal to the expected value.
+    ///
+    /// The top element is compared while it is protected from being freed, the removing compare and swap
+    /// only succeeds if the compared element is still on top. If another thread changed the top in the meantime
+    /// then the new top element is compared again. Together with `peek_with` this allows optimistic algorithms
+    /// that look at the top first and only remove it if no other thread did so in the meantime.
+    /// Like for `pop_if` the element may have been popped and an equal element pushed in between.
+    ///
+    /// ```rust
+    /// use atomic_lifo::{AtomicLifo, PopIfEqError};
+    ///
+    /// let lifo = AtomicLifo::new();
+    /// assert_eq!(lifo.pop_if_eq(&1), Err(PopIfEqError::Empty));
+    /// lifo.extend([1, 2]);
+    /// let top = lifo.peek_with(|top| *top).unwrap();
+    /// assert_eq!(lifo.pop_if_eq(&1), Err(PopIfEqError::Mismatch));
+    /// assert_eq!(lifo.pop_if_eq(&top), Ok(2));
+    /// ```
+    ///
+    /// # Errors
+    /// `PopIfEqError::Empty` if the lifo is empty and `PopIfEqError::Mismatch` if the top element is not equal to the expected value.
+    /// The lifo is not modified in both cases.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_if_eq(&self, expected: &T) -> Result<T, PopIfEqError>
+    where
+        T: PartialEq + Sync,
+    {
+        self.pop_top_if(|top| top == expected)
+    }
+
+    ///
+    /// Pops elements from the top of the lifo stack and passes them to the sink as long as the predicate returns true for them.
+    ///
+    /// Stops at the first element the predicate returns false for, which stays in the lifo, or once the lifo is empty.
+    /// Every element is passed to the predicate while it is protected from being freed and only removed if it is still
+    /// on top afterward, unlike a loop of `peek_with` and `pop` that may pop an element another thread put on top in between.
+    /// If another thread changes the top after the predicate was evaluated then the predicate is evaluated again
+    /// for the new top element, so like for `pop_if` it may be called more than once for the same element.
+    /// The calling thread is only counted once for all elements, see `in_flight_pop_count`,
+    /// so no node popped by any thread is freed before the sink returned for the last element.
+    ///
+    /// A concurrent call to `pop` that removes the element the predicate is looking at
+    /// will wait until the predicate has returned.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::from(vec![5, 1, 2, 3]);
+    /// let mut expired = Vec::new();
+    /// lifo.pop_while(|deadline| *deadline < 4, |deadline| expired.push(deadline));
+    /// assert_eq!(expired, [3, 2, 1]);
+    /// assert_eq!(lifo.pop(), Some(5));
+    /// ```
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_while(&self, mut pred: impl FnMut(&T) -> bool, mut sink: impl FnMut(T))
+    where
+        T: Sync,
+    {
+        if self.is_empty() {
+            self.count_empty_pop();
+            return;
+        }
+
+        if Self::ZST {
+            //Safe, a value was forgotten for every counted value.
+            while pred(unsafe { zst_ref() }) {
+                let Some(value) = self.pop() else {
+                    return;
+                };
+
+                sink(value);
+            }
+
+            return;
+        }
+
+        let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+        let _reading = Self::RECYCLE.then(|| self.recycled.read());
+
+        let mut popped = false;
+        //Safe, we entered and read.
+        loop {
+            match unsafe { self.pop_entered_if(&mut pred) } {
+                Ok(value) => {
+                    popped = true;
+                    sink(value);
+                }
+                Err(PopIfEqError::Empty) if !popped => {
+                    self.count_empty_pop();
+                    return;
+                }
+                Err(_) => return,
+            }
+        }
+    }
+
+    /// Pops the top of the lifo stack if the predicate returns true for it, see `pop_if`.
+    fn pop_top_if(&self, mut pred: impl FnMut(&T) -> bool) -> Result<T, PopIfEqError>
+    where
+        T: Sync,
+    {
+        if self.is_empty() {
+            self.count_empty_pop();
+            return Err(PopIfEqError::Empty);
+        }
+
+        if Self::ZST {
+            //All values are equal, so if the predicate matches one it matches whatever we pop.
+            //Safe, a value was forgotten for every counted value.
+            if !pred(unsafe { zst_ref() }) {
+                return Err(PopIfEqError::Mismatch);
+            }
+
+            return self.pop().ok_or(PopIfEqError::Empty);
+        }
+
+        let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+        let _reading = Self::RECYCLE.then(|| self.recycled.read());
+
+        //Safe, we entered and read.
+        let result = unsafe { self.pop_entered_if(&mut pred) };
+        if matches!(result, Err(PopIfEqError::Empty)) {
+            self.count_empty_pop();
+        }
+
+        result
+    }
+
+    /// Pops the top node if the predicate returns true for its value, does not count a pop that found the lifo empty.
+    ///
+    /// # Safety
+    /// Must only be called between `enter` and `leave` and while popped nodes are not recycled.
+    unsafe fn pop_entered_if(&self, pred: &mut impl FnMut(&T) -> bool) -> Result<T, PopIfEqError>
+    where
+        T: Sync,
+    {
+        let mut retries = 0;
+        let removed = loop {
+            let head = self.head.load(SeqCst);
+            let Some(node) = head.ptr.as_ref() else {
+                return Err(PopIfEqError::Empty);
+            };
+
+            let Some(borrow) = node.try_borrow() else {
+                //The node was popped by another thread in the meantime, the head has already moved on.
+                continue;
+            };
+
+            let matches = pred(&borrow);
+            drop(borrow);
+
+            if !matches {
+                return Err(PopIfEqError::Mismatch);
+            }
+
+            if self.head.remove(head, node.next(), SeqCst, SeqCst).is_err() {
+                #[cfg(feature = "stats")]
+                self.counters.pop_retries.add(1);
+                retries += 1;
+                continue;
+            }
+
+            break head.ptr;
+        };
+
+        trace::retries("pop_if", retries);
+        #[cfg(feature = "count")]
+        self.len.fetch_sub(1, Relaxed);
+        #[cfg(feature = "stats")]
+        self.counters.pops.add(1);
+
+        let removed_obj = (*removed).take();
+        self.hazard.retire(&self.allocator, removed, 1);
+
+        self.hooks.on_pop(&removed_obj);
+        Ok(removed_obj)
+    }
+
+    ///
+    /// Returns true if the lifo contains an element that is equal to the given value.
+    ///
+    /// This is O(n) and only a snapshot, other threads may push or pop elements at any time
+    /// so the element may already have been popped once this fn returns.
+    /// No removed elements are freed while this fn is running.
+    ///
+    /// A concurrent call to `pop` that removes the element that is currently being compared
+    /// will wait until the comparison has finished.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: Sync + PartialEq,
+    {
+        let mut found = false;
+        self.traverse(|element| {
+            found = element == value;
+            !found
+        });
+
+        found
+    }
+
+    ///
+    /// Calls the closure for every element from top to bottom without removing them.
+    ///
+    /// This is O(n) and only a snapshot: the traversal starts at the top of the lifo at the time of this call,
+    /// elements pushed afterward are not visited and elements popped by other threads before the traversal reaches them are skipped.
+    /// Every element is visited at most once, none is visited after it was moved out of the lifo.
+    /// No removed elements are freed while this fn is running, so unlike `iter` the reclamation is only stalled
+    /// for the duration of the call.
+    ///
+    /// A concurrent call to `pop` that removes the element the closure is looking at
+    /// will wait until the closure has returned.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::from(vec![1, 2, 3]);
+    /// let mut sum = 0;
+    /// lifo.visit(|element| sum += element);
+    /// assert_eq!(sum, 6);
+    /// ```
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn visit(&self, mut f: impl FnMut(&T))
+    where
+        T: Sync,
+    {
+        self.traverse(|element| {
+            f(element);
+            true
+        });
+    }
+
+    /// Calls `f` for the elements of the lifo from top to bottom until it returns false.
+    /// Elements that are popped by other threads during the traversal may be skipped.
+    fn traverse(&self, mut f: impl FnMut(&T) -> bool)
+    where
+        T: Sync,
+    {
+        if self.is_empty() {
+            return;
+        }
+
+        if Self::ZST {
+            for _ in 0..self.zst_len.load(SeqCst) {
+                //Safe, a value was forgotten for every counted value.
+                if !f(unsafe { zst_ref() }) {
+                    return;
+                }
+            }
+
+            return;
+        }
+
+        self.walk(|node| {
+            let Some(borrow) = node.try_borrow() else {
+                //Popped by another thread in the meantime
+                return true;
+            };
+
+            f(&borrow)
+        });
+    }
+
+    /// Calls `f` for every node reachable from the head from top to bottom until it returns false.
+    /// The nodes may be popped by other threads while `f` looks at them, their values must be borrowed before they are read.
+    fn walk(&self, mut f: impl FnMut(&Node<T, R>) -> bool) {
+        let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+        //Nodes popped while we read are not recycled, so their next pointers do not change.
+        let _reading = Self::RECYCLE.then(|| self.recycled.read());
+
+        //All nodes reachable from head are protected from being freed until we leave,
+        //because the next pointer of a node never changes once it is part of the lifo.
+        let mut cur = self.head.ptr(SeqCst);
+        while let Some(node) = unsafe { cur.as_ref() } {
+            cur = node.next();
+            if !f(node) {
+                return;
+            }
+        }
+    }
+
+    ///
+    /// Pops up to `n` elements from the top of the lifo stack.
+    ///
+    /// The elements are returned in the order `pop` would have returned them.
+    /// Fewer than `n` elements are returned if the lifo contains fewer elements.
+    /// All elements are removed with a single atomic operation, other threads will never observe only a part of them being removed.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_n(&self, n: usize) -> Vec<T> {
+        let mut result = Vec::new();
+        self.pop_batch(n, false, |value| result.push(value));
+        result
+    }
+
+    ///
+    /// Pops exactly `K` elements from the top of the lifo stack, or none at all if the lifo contains fewer.
+    ///
+    /// The array holds the elements in the order `pop` would have returned them.
+    /// All elements are removed with a single atomic operation, like for `pop_n`, so they were consecutive
+    /// in the lifo and a group of `K` elements pushed with `extend` is either popped as a whole or left untouched.
+    /// If the lifo contains fewer than `K` elements it is not modified and None is returned.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::new();
+    /// lifo.extend([1, 2, 3]);
+    /// assert_eq!(lifo.pop_exact::<4>(), None);
+    /// assert_eq!(lifo.pop_exact::<2>(), Some([3, 2]));
+    /// assert_eq!(lifo.pop(), Some(1));
+    /// ```
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_exact<const K: usize>(&self) -> Option<[T; K]> {
+        let mut values = [const { MaybeUninit::uninit() }; K];
+        let mut slots = values.iter_mut();
+        let count = self.pop_batch(K, true, |value| {
+            //Safe, pop_batch never produces more than the requested amount of values.
+            unsafe { slots.next().unwrap_unchecked() }.write(value);
+        });
+
+        if count != K {
+            return None;
+        }
+
+        //Safe, all K values were written.
+        Some(values.map(|value| unsafe { value.assume_init() }))
+    }
+
+    ///
+    /// Pops elements from the top of the lifo stack into the given slice.
+    ///
+    /// The slice is filled from the front in the order `pop` would have returned the elements.
+    /// Returns the amount of elements written, which is less than the length of the slice if the lifo contains fewer elements.
+    /// Only the returned amount of elements at the start of the slice is initialized and
+    /// the caller is responsible for dropping them.
+    /// All elements are removed with a single atomic operation.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop_into(&self, out: &mut [MaybeUninit<T>]) -> usize {
+        let mut slots = out.iter_mut();
+        self.pop_batch(slots.len(), false, |value| {
+            //Safe, pop_batch never produces more than the requested amount of values.
+            unsafe { slots.next().unwrap_unchecked() }.write(value);
+        })
+    }
+
+    ///
+    /// Moves up to `max` elements from the top of this lifo to the top of the destination lifo.
+    ///
+    /// Returns the amount of moved elements.
+    /// The elements are removed with a single atomic operation and pushed with a single atomic operation,
+    /// preserving their relative order. While they are moved the elements cannot be observed by other threads.
+    /// The values are not moved, but a new node is allocated for every element
+    /// because threads accessing this lifo may still look at the old nodes.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn steal_batch_into(&self, dest: &Self, max: usize) -> usize {
+        let mut chain = self.chain();
+        let count = self.pop_batch(max, false, |value| {
+            dest.hooks.on_push(&value);
+            chain.push_bottom(value);
+        });
+        dest.publish_chain(chain);
+        count
+    }
+
+    /// Pops up to `max` elements with a single CAS and passes them to `sink` in the order `pop` would have returned them.
+    /// If `exact` is true nothing is popped unless the lifo contains at least `max` elements.
+    /// Returns the amount of popped elements.
+    fn pop_batch(&self, max: usize, exact: bool, mut sink: impl FnMut(T)) -> usize {
+        if max == 0 {
+            return 0;
+        }
+
+        if self.is_empty() {
+            self.count_empty_pop();
+            return 0;
+        }
+
+        if Self::ZST {
+            let count = self.pop_zst(max, exact);
+            if count == 0 {
+                self.count_empty_pop();
+            }
+
+            for _ in 0..count {
+                //Safe, we forgot a value for every counted value.
+                let value = unsafe { zst() };
+                self.hooks.on_pop(&value);
+                sink(value);
+            }
+
+            return count;
+        }
+
+        let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+
+        let (chain, count) = unsafe { self.detach(max, exact) };
+        let mut cur = chain;
+        for _ in 0..count {
+            unsafe {
+                let node = &*cur;
+                cur = node.next();
+                let value = node.take();
+                self.hooks.on_pop(&value);
+                sink(value);
+            }
+        }
+
+        if count == 0 {
+            self.count_empty_pop();
+        } else {
+            unsafe {
+                self.hazard.retire(&self.allocator, chain, count);
+            }
+        }
+
+        count
+    }
+
+    /// Removes up to `max` nodes from the top of the lifo with a single CAS.
+    /// If `exact` is true nothing is removed unless the lifo contains at least `max` nodes.
+    /// Returns the first removed node and the amount of removed nodes.
+    ///
+    /// # Safety
+    /// `max` must not be 0. Must only be called between `enter` and `leave`.
+    /// The caller must take the values of the removed nodes and retire them.
+    unsafe fn detach(&self, max: usize, exact: bool) -> (*mut Node<T, R>, usize) {
+        let mut head = self.head.load(SeqCst);
+        let mut backoff = Backoff::new();
+        'detach: loop {
+            let Some(mut last) = head.ptr.as_ref() else {
+                return (null_mut(), 0);
+            };
+
+            let mut count = 1;
+            while count < max {
+                let Some(next) = last.next().as_ref() else {
+                    if !exact {
+                        break;
+                    }
+
+                    //The walk only saw the whole lifo if the head did not change in the meantime.
+                    let current = self.head.load(SeqCst);
+                    if current == head {
+                        return (null_mut(), 0);
+                    }
+
+                    head = current;
+                    continue 'detach;
+                };
+
+                last = next;
+                count += 1;
+
+                //Nodes popped by other threads may be recycled and pushed again while we walk,
+                //the walk could then run in circles until it reaches max. Checking the head now and then bounds it.
+                if Self::RECYCLE && count % DETACH_CHECK_INTERVAL == 0 {
+                    let current = self.head.load(SeqCst);
+                    if current != head {
+                        head = current;
+                        continue 'detach;
+                    }
+                }
+            }
+
+            //The next pointers of nodes never change once they are part of the lifo,
+            //so if head did not change then the entire chain we walked is still on top of the lifo.
+            if let Err(current) = self.head.remove_weak(head, last.next(), SeqCst, SeqCst) {
+                #[cfg(feature = "stats")]
+                self.counters.pop_retries.add(1);
+                head = current;
+                backoff.snooze();
+                continue;
+            }
+
+            trace::retries("pop", backoff.attempts());
+            #[cfg(feature = "count")]
+            self.len.fetch_sub(count, Relaxed);
+            #[cfg(feature = "stats")]
+            self.counters.pops.add(count);
+
+            return (head.ptr, count);
+        }
+    }
+
+    ///
+    /// Calls the closure with a reference to the top of the lifo stack without removing it.
+    /// Returns None if the lifo is empty, otherwise the result of the closure.
+    ///
+    /// The element stays valid for the entire duration of the closure.
+    /// A concurrent call to `pop` that removes this element will wait until the closure has returned,
+    /// so the closure should not do anything expensive.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn peek_with<U>(&self, f: impl FnOnce(&T) -> U) -> Option<U>
+    where
+        T: Sync,
+    {
+        if Self::ZST {
+            //Safe, a value was forgotten for every counted value.
+            return (!self.is_empty()).then(|| f(unsafe { zst_ref() }));
+        }
+
+        let _entered = self.hazard.enter(&self.allocator, self.hazard_limit);
+        let _reading = Self::RECYCLE.then(|| self.recycled.read());
+
+        loop {
+            let node = unsafe { self.head.ptr(SeqCst).as_ref()? };
+
+            let Some(borrow) = node.try_borrow() else {
+                //The node was popped by another thread in the meantime, the head has already moved on.
+                continue;
+            };
+
+            return Some(f(&borrow));
+        }
+    }
+
+    ///
+    /// Pins the lifo and returns a guard that gives out references to its elements until it is dropped.
+    ///
+    /// No node that is part of the lifo while the guard is alive is freed before the guard is dropped.
+    /// The guard is counted like a pop that is in progress, **holding it for a long time keeps every node popped
+    /// in the meantime from being freed**, see `PinGuard`.
+    /// Pops never wait for the guard itself, only a pop that removes an element that is still referenced waits for the reference.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::from(vec![1, 2, 3]);
+    /// let guard = lifo.pin();
+    /// assert_eq!(guard.head().as_deref(), Some(&3));
+    /// assert_eq!(guard.iter().map(|element| *element).sum::<i32>(), 6);
+    /// ```
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pin(&self) -> PinGuard<'_, T, A, R, H>
+    where
+        T: Sync,
+    {
+        PinGuard::new(self)
+    }
+
+    ///
+    /// Returns an iterator over references to the elements from top to bottom without removing them.
+    ///
+    /// The iterator starts at the top of the lifo at the time of this call, elements pushed afterward are never yielded.
+    /// Every element below that top is yielded exactly once unless another thread pops it before the iterator reaches it.
+    /// An element that a concurrent pop has already removed but not yet moved out may still be yielded.
+    ///
+    /// The references borrow the guard, which keeps every node the iterator may still visit from being freed, see `pin`.
+    /// A pop that removes a yielded element waits until its `PinnedRef` is dropped, so a thread must not pop
+    /// an element while it holds a reference to it. Elements whose references were dropped can be popped right away.
+    /// The iterator never accesses freed memory, also if all elements are popped while it iterates.
+    ///
+    /// ```rust
+    /// use atomic_lifo::AtomicLifo;
+    ///
+    /// let lifo = AtomicLifo::from(vec![1, 2, 3]);
+    /// let guard = lifo.pin();
+    /// let elements = lifo.iter(&guard).map(|element| *element).collect::<Vec<_>>();
+    /// assert_eq!(elements, [3, 2, 1]);
+    ///
+    /// //The references were dropped, the guard does not keep the elements from being popped.
+    /// assert_eq!(lifo.pop(), Some(3));
+    /// ```
+    ///
+    /// # Panics
+    /// if the guard pins another lifo.
+    ///
+    pub fn iter<'g>(&self, guard: &'g PinGuard<'_, T, A, R, H>) -> Iter<'g, T, R>
+    where
+        T: Sync,
+    {
+        Iter::new(self, guard)
+    }
+}
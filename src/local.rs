@@ -0,0 +1,116 @@
+//! Handle that buffers the pushes of a thread and pushes them to a shared lifo in batches.
+use crate::allocator::{Allocator, Global};
+use crate::{AtomicLifo, Chain, HazardReclaim, LifoHooks, Reclaim};
+use core::fmt::{Debug, Formatter};
+
+///
+/// Handle to an `AtomicLifo` that buffers pushes and pushes them to the lifo in batches with a single compare and swap.
+///
+/// Created by `AtomicLifo::local` and `AtomicLifo::local_with_capacity`. The buffer is a plain chain of nodes
+/// that only the owner of the handle can see, so the handle is neither `Send` nor `Sync`.
+/// The buffered elements are pushed once the buffer is full, on `flush` and when the handle is dropped.
+///
+/// Elements become visible to other threads in the same order as if they were pushed one by one, only later.
+/// The hooks of the lifo are called for every element pushed to and popped from the handle,
+/// even for elements that were popped from the buffer and never became visible to other threads.
+///
+pub struct LocalLifo<
+    'a,
+    T: Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// the shared lifo.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// the elements that were pushed to the handle and not yet to the lifo, the newest on top.
+    buffer: Chain<T, A, R>,
+    /// amount of elements that are buffered before they are pushed.
+    capacity: usize,
+}
+
+impl<T: Sync + Send + Debug, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for LocalLifo<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LocalLifo")
+            .field("lifo", &self.lifo)
+            .field("buffered", &self.buffer.count)
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> LocalLifo<'a, T, A, R, H> {
+    /// Constructs a new `LocalLifo` with an empty buffer that pushes to the lifo once `capacity` elements are buffered.
+    pub(crate) fn new(lifo: &'a AtomicLifo<T, A, R, H>, capacity: usize) -> Self {
+        Self {
+            lifo,
+            buffer: lifo.chain(),
+            capacity,
+        }
+    }
+
+    ///
+    /// Pushes a value into the buffer, pushes the whole buffer to the lifo if it is full.
+    ///
+    pub fn push(&mut self, value: T) {
+        self.lifo.hooks.on_push(&value);
+        self.buffer.push_top(value);
+        if self.buffer.count >= self.capacity {
+            self.flush();
+        }
+    }
+
+    ///
+    /// Pops the newest buffered element, or the top of the lifo stack if the buffer is empty.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop(&mut self) -> Option<T> {
+        let Some(value) = self.buffer.pop_top() else {
+            return self.lifo.pop();
+        };
+
+        self.lifo.hooks.on_pop(&value);
+        Some(value)
+    }
+
+    ///
+    /// Pushes all buffered elements to the lifo with a single compare and swap.
+    ///
+    /// Does not access the lifo if the buffer is empty.
+    ///
+    pub fn flush(&mut self) {
+        let buffer = core::mem::replace(&mut self.buffer, self.lifo.chain());
+        self.lifo.publish_chain(buffer);
+    }
+
+    /// Returns the amount of elements that are buffered and not yet visible to other threads.
+    #[must_use]
+    pub const fn buffered(&self) -> usize {
+        self.buffer.count
+    }
+
+    /// Returns the amount of elements that are buffered before they are pushed to the lifo.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the shared lifo.
+    #[must_use]
+    pub const fn lifo(&self) -> &'a AtomicLifo<T, A, R, H> {
+        self.lifo
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Drop
+    for LocalLifo<'_, T, A, R, H>
+{
+    fn drop(&mut self) {
+        //Also runs while unwinding, a panicking thread does not lose the elements it buffered.
+        self.flush();
+    }
+}
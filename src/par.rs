@@ -0,0 +1,58 @@
+//! Parallel draining of an `AtomicLifo` with rayon.
+use crate::allocator::Allocator;
+use crate::{AtomicLifo, LifoHooks, Reclaim};
+use alloc::vec::IntoIter;
+use rayon::iter::ParallelIterator;
+
+/// Amount of elements a rayon task pops at once, enough that a task is worth splitting off.
+const BATCH: usize = 256;
+
+/// Elements popped by one rayon task, the task pops the next batch once they are used up.
+struct Batches<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> {
+    /// the lifo that is drained.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// the elements of the current batch that were not yielded yet.
+    batch: IntoIter<T>,
+}
+
+impl<'a, T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Batches<'a, T, A, R, H> {
+    /// Pops the first batch of a task.
+    fn pop(lifo: &'a AtomicLifo<T, A, R, H>) -> Self {
+        Self {
+            lifo,
+            batch: lifo.pop_n(BATCH).into_iter(),
+        }
+    }
+
+    /// Splits off a new task with a batch of its own, unless the lifo is empty.
+    fn split(self) -> (Self, Option<Self>) {
+        let other = Self::pop(self.lifo);
+        if other.batch.len() == 0 {
+            return (self, None);
+        }
+
+        (self, Some(other))
+    }
+}
+
+impl<T: Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Iterator
+    for Batches<'_, T, A, R, H>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if let Some(value) = self.batch.next() {
+            return Some(value);
+        }
+
+        self.batch = self.lifo.pop_n(BATCH).into_iter();
+        self.batch.next()
+    }
+}
+
+/// See `AtomicLifo::par_drain`.
+pub fn drain<T: Send, A: Allocator + Clone + Sync, R: Reclaim, H: LifoHooks<T>>(
+    lifo: &AtomicLifo<T, A, R, H>,
+) -> impl ParallelIterator<Item = T> + '_ {
+    rayon::iter::split(Batches::pop(lifo), Batches::split).flat_map_iter(|batches| batches)
+}
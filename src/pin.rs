@@ -0,0 +1,263 @@
+//! Guard that keeps the nodes of an `AtomicLifo` from being freed while the caller looks at its elements.
+use crate::allocator::{Allocator, Global};
+use crate::hazard::Linked;
+use crate::reclaim::Reclaimer;
+use crate::recycle::Reading;
+use crate::{zst_ref, AtomicLifo, Borrow, HazardReclaim, LifoHooks, Node, Reclaim};
+use core::fmt::{Debug, Formatter};
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr::null_mut;
+use core::sync::atomic::Ordering::SeqCst;
+
+/// Registration of the pinning thread with the strategy of the lifo.
+type Entered<'a, T, A, R> =
+    <<R as Reclaim>::List<Node<T, R>> as Reclaimer<Node<T, R>>>::Entered<'a, A>;
+
+///
+/// Guard that keeps every node of an `AtomicLifo` from being freed until it is dropped.
+///
+/// Created by `AtomicLifo::pin`. While the guard is alive `head` and `iter` return references to the elements.
+///
+/// **Holding a guard for a long time stalls the reclamation of the lifo.** The guard is counted like a pop that is in progress,
+/// so no node popped by any thread after the guard was created is freed before it is dropped and the memory of the lifo
+/// grows with every pop in the meantime, see `AtomicLifo::in_flight_pop_count`.
+/// With the `dwcas` feature popped nodes are also not reused right away while a guard is alive.
+/// The guard should therefore be dropped as soon as the elements were looked at.
+///
+/// The guard itself does not block other operations, pushes and pops stay lock free while it is alive.
+/// Only a pop that removes an element that is referenced by a `PinnedRef` waits until that reference is dropped,
+/// elements whose references were dropped can be popped right away.
+///
+pub struct PinGuard<
+    'a,
+    T: Sync + Send,
+    A: Allocator + Clone = Global,
+    R: Reclaim = HazardReclaim,
+    H: LifoHooks<T> = (),
+> {
+    /// the pinned lifo.
+    lifo: &'a AtomicLifo<T, A, R, H>,
+    /// keeps the nodes from being freed, None for a zero sized type whose values are not stored in nodes.
+    _entered: Option<Entered<'a, T, A, R>>,
+    /// keeps popped nodes from being reused, only if the lifo recycles nodes.
+    _reading: Option<Reading<'a>>,
+}
+
+impl<'a, T: Sync + Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>>
+    PinGuard<'a, T, A, R, H>
+{
+    /// Pins the lifo.
+    pub(crate) fn new(lifo: &'a AtomicLifo<T, A, R, H>) -> Self {
+        if AtomicLifo::<T, A, R, H>::ZST {
+            return Self {
+                lifo,
+                _entered: None,
+                _reading: None,
+            };
+        }
+
+        Self {
+            lifo,
+            _entered: Some(lifo.hazard.enter(&lifo.allocator, lifo.hazard_limit)),
+            _reading: AtomicLifo::<T, A, R, H>::RECYCLE.then(|| lifo.recycled.read()),
+        }
+    }
+
+    ///
+    /// Returns a reference to the top of the lifo stack, None if the lifo is empty.
+    ///
+    /// The element may be popped by any thread at any time, the reference stays valid regardless.
+    /// A pop that removes the element waits until the reference is dropped.
+    ///
+    #[must_use]
+    pub fn head(&self) -> Option<PinnedRef<'_, T, R>> {
+        if AtomicLifo::<T, A, R, H>::ZST {
+            return (!self.lifo.is_empty()).then_some(PinnedRef { borrow: None });
+        }
+
+        loop {
+            //Safe, the node was loaded after we pinned, so it is not freed before we are dropped.
+            let node = unsafe { self.lifo.head.ptr(SeqCst).as_ref()? };
+            if let Some(borrow) = node.try_borrow() {
+                return Some(PinnedRef {
+                    borrow: Some(borrow),
+                });
+            }
+
+            //The node was popped by another thread in the meantime, the head has already moved on.
+        }
+    }
+
+    ///
+    /// Returns an iterator over references to the elements from top to bottom.
+    ///
+    /// The iterator starts at the top of the lifo when this fn is called. Elements pushed afterward are not yielded,
+    /// elements popped by other threads before the iterator reaches them are skipped.
+    /// A pop that removes an element waits until the reference to it is dropped.
+    ///
+    #[must_use]
+    pub fn iter(&self) -> PinnedIter<'_, T, R> {
+        if AtomicLifo::<T, A, R, H>::ZST {
+            return PinnedIter {
+                current: null_mut(),
+                zst: self.lifo.zst_len.load(SeqCst),
+                _guard: PhantomData,
+            };
+        }
+
+        PinnedIter {
+            current: self.lifo.head.ptr(SeqCst),
+            zst: 0,
+            _guard: PhantomData,
+        }
+    }
+}
+
+impl<'g, T: Sync + Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> IntoIterator
+    for &'g PinGuard<'_, T, A, R, H>
+{
+    type Item = PinnedRef<'g, T, R>;
+    type IntoIter = PinnedIter<'g, T, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Sync + Send, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Debug
+    for PinGuard<'_, T, A, R, H>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PinGuard").finish_non_exhaustive()
+    }
+}
+
+///
+/// Reference to an element of a pinned `AtomicLifo`, returned by `PinGuard::head` and `PinnedIter`.
+///
+/// The element is not moved out of the lifo while this reference is alive, a pop that removes it waits until the reference is dropped.
+/// Popping the element in the thread that holds the reference therefore never returns.
+///
+pub struct PinnedRef<'g, T: Sync + Send, R: Reclaim = HazardReclaim> {
+    /// the borrow of the value, None for a zero sized type whose values are not stored in nodes.
+    borrow: Option<Borrow<'g, T, R>>,
+}
+
+impl<T: Sync + Send, R: Reclaim> Deref for PinnedRef<'_, T, R> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match &self.borrow {
+            Some(borrow) => borrow,
+            //Safe, a value was forgotten for every counted value and we only yield counted values.
+            None => unsafe { zst_ref() },
+        }
+    }
+}
+
+impl<T: Sync + Send + Debug, R: Reclaim> Debug for PinnedRef<'_, T, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+/// Iterator over references to the elements of a pinned `AtomicLifo`, created by `PinGuard::iter`.
+#[derive(Debug)]
+pub struct PinnedIter<'g, T: Sync + Send, R: Reclaim = HazardReclaim> {
+    /// node whose value is yielded next.
+    current: *mut Node<T, R>,
+    /// amount of values of a zero sized type that are yielded instead of nodes.
+    zst: usize,
+    /// the guard that keeps the nodes from being freed.
+    _guard: PhantomData<&'g Node<T, R>>,
+}
+
+impl<'g, T: Sync + Send, R: Reclaim> Iterator for PinnedIter<'g, T, R> {
+    type Item = PinnedRef<'g, T, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        //Safe, the guard outlives 'g.
+        unsafe { next_ref(&mut self.current, &mut self.zst) }
+    }
+}
+
+/// Borrows the value of the next node that was not popped yet, or the next value of a zero sized type.
+///
+/// # Safety
+/// `current` must be null or a node reachable from a head that was loaded while the lifo was pinned,
+/// and the lifo must stay pinned until the value was borrowed.
+/// Once borrowed the value is not taken, so its node is neither retired nor reused, until the returned reference is dropped.
+unsafe fn next_ref<'r, T: Sync + Send, R: Reclaim>(
+    current: &mut *mut Node<T, R>,
+    zst: &mut usize,
+) -> Option<PinnedRef<'r, T, R>> {
+    if *zst != 0 {
+        *zst -= 1;
+        return Some(PinnedRef { borrow: None });
+    }
+
+    loop {
+        //Safe, all nodes reachable from a head loaded after we pinned are not freed before we are unpinned,
+        //because the next pointer of a node never changes once it is part of the lifo.
+        let node: &'r Node<T, R> = current.as_ref()?;
+        *current = node.next();
+        if let Some(borrow) = node.try_borrow() {
+            return Some(PinnedRef {
+                borrow: Some(borrow),
+            });
+        }
+
+        //Popped by another thread in the meantime.
+    }
+}
+
+///
+/// Iterator over references to the elements of an `AtomicLifo` from top to bottom, created by `AtomicLifo::iter`.
+///
+/// Yields the same `PinnedRef`s as `PinGuard::iter`, they borrow the `PinGuard` passed to `AtomicLifo::iter`.
+/// A yielded element is not moved out of the lifo while its reference is alive, a pop that removes it waits until the reference is dropped.
+///
+/// ```compile_fail,E0597
+/// use atomic_lifo::AtomicLifo;
+///
+/// let lifo = AtomicLifo::from(vec![1]);
+/// let top = {
+///     let guard = lifo.pin();
+///     lifo.iter(&guard).next().unwrap()
+/// };
+/// assert_eq!(*top, 1);
+/// ```
+///
+#[derive(Debug)]
+pub struct Iter<'g, T: Sync + Send, R: Reclaim = HazardReclaim> {
+    /// the iterator of the guard.
+    inner: PinnedIter<'g, T, R>,
+}
+
+impl<'g, T: Sync + Send, R: Reclaim> Iter<'g, T, R> {
+    /// Starts at the current top of the lifo pinned by the guard.
+    ///
+    /// # Panics
+    /// if the guard pins another lifo.
+    pub(crate) fn new<A: Allocator + Clone, H: LifoHooks<T>>(
+        lifo: &AtomicLifo<T, A, R, H>,
+        guard: &'g PinGuard<'_, T, A, R, H>,
+    ) -> Self {
+        assert!(
+            core::ptr::eq(guard.lifo, lifo),
+            "the guard does not pin this lifo"
+        );
+        Self {
+            inner: guard.iter(),
+        }
+    }
+}
+
+impl<'g, T: Sync + Send, R: Reclaim> Iterator for Iter<'g, T, R> {
+    type Item = PinnedRef<'g, T, R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
@@ -0,0 +1,112 @@
+//! Object pool built on top of an `AtomicLifo`.
+use crate::AtomicLifo;
+use core::fmt::{Debug, Formatter};
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+///
+/// Thread Safe object pool.
+///
+/// `acquire` pops an object or creates a new one if the pool is empty,
+/// the returned guard pushes the object back into the pool once it is dropped.
+/// The most recently returned object is handed out first, which keeps it warm in the cache.
+///
+pub struct Pool<T: Send, F: Fn() -> T = fn() -> T> {
+    /// the objects that are currently not in use.
+    lifo: AtomicLifo<T>,
+    /// creates a new object if the pool is empty.
+    create: F,
+}
+
+impl<T: Send, F: Fn() -> T> Debug for Pool<T, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pool").finish_non_exhaustive()
+    }
+}
+
+impl<T: Send, F: Fn() -> T> Pool<T, F> {
+    /// Constructs a new empty `Pool` that creates objects using the given fn.
+    #[must_use]
+    pub const fn new(create: F) -> Self {
+        Self {
+            lifo: AtomicLifo::new(),
+            create,
+        }
+    }
+
+    ///
+    /// Takes an object out of the pool or creates a new one if the pool is empty.
+    ///
+    /// The object is returned to the pool once the guard is dropped, even if the thread panics.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn acquire(&self) -> PoolGuard<'_, T, F> {
+        PoolGuard {
+            pool: self,
+            value: ManuallyDrop::new(self.lifo.pop_or_else(&self.create)),
+        }
+    }
+
+    /// Adds an object to the pool.
+    pub fn put(&self, value: T) {
+        self.lifo.push(value);
+    }
+
+    ///
+    /// Returns true if the pool contains no objects, the next call to `acquire` would then create one.
+    ///
+    /// This is only a snapshot, other threads may acquire or return objects at any time.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lifo.is_empty()
+    }
+}
+
+/// Object that was taken out of a `Pool` and is returned to it once this guard is dropped.
+pub struct PoolGuard<'a, T: Send, F: Fn() -> T = fn() -> T> {
+    /// the pool the object is returned to.
+    pool: &'a Pool<T, F>,
+    /// the object, only taken in drop or take.
+    value: ManuallyDrop<T>,
+}
+
+impl<T: Send + Debug, F: Fn() -> T> Debug for PoolGuard<'_, T, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("PoolGuard").field(&*self.value).finish()
+    }
+}
+
+impl<T: Send, F: Fn() -> T> PoolGuard<'_, T, F> {
+    /// Takes the object out of the guard, it is not returned to the pool.
+    #[must_use]
+    pub fn take(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        //Safe, the guard is never dropped so the value is not taken again.
+        unsafe { ManuallyDrop::take(&mut this.value) }
+    }
+}
+
+impl<T: Send, F: Fn() -> T> Deref for PoolGuard<'_, T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Send, F: Fn() -> T> DerefMut for PoolGuard<'_, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: Send, F: Fn() -> T> Drop for PoolGuard<'_, T, F> {
+    fn drop(&mut self) {
+        //Safe, this is the only place besides take that takes the value and take never drops the guard.
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        self.pool.put(value);
+    }
+}
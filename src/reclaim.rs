@@ -0,0 +1,221 @@
+//! Strategies that decide when the nodes of popped elements are freed.
+use crate::allocator::Allocator;
+use crate::atomic::AtomicPtr;
+#[cfg(feature = "epoch")]
+use crate::epoch::{EpochList, Retired};
+use crate::error::PopContention;
+use crate::hazard::{HazardList, Linked};
+use crate::leak::LeakList;
+#[cfg(feature = "stats")]
+use crate::stats::ReclaimCounters;
+use core::fmt::Debug;
+use core::ptr::NonNull;
+
+/// Prevents implementations of `Reclaim` outside of this crate.
+mod sealed {
+    /// Implemented by every strategy of this crate.
+    pub trait Sealed {}
+}
+
+///
+/// Strategy of an `AtomicLifo` to free the nodes of popped elements, which other threads may still be looking at.
+///
+/// The strategy is chosen per lifo by its last type parameter, see `HazardReclaim`, `LeakReclaim`
+/// and `EpochReclaim` with the `epoch` feature. This trait is sealed and cannot be implemented outside of this crate.
+///
+pub trait Reclaim: sealed::Sealed + Debug + Send + Sync + Sized + 'static {
+    /// Link every node contains so the strategy can retire it.
+    #[doc(hidden)]
+    type Link<N>: Default + Debug;
+
+    /// List of the nodes that were retired but not freed yet.
+    #[doc(hidden)]
+    type List<N: Retire<Self>>: Reclaimer<N> + Send + Sync;
+
+    /// True if popped nodes may be pushed again right away when the head detects removals, see the `dwcas` feature.
+    #[doc(hidden)]
+    const RECYCLE: bool;
+}
+
+/// Node that can be retired by the strategy `R`.
+pub trait Retire<R: Reclaim>: Linked + Sized {
+    /// Returns the link of the node used by the strategy.
+    fn link(&self) -> &R::Link<Self>;
+}
+
+///
+/// Bookkeeping of a strategy for the nodes of a data structure.
+///
+/// The allocator is owned by the data structure and passed to every fn that may allocate or free,
+/// so the bookkeeping can be constructed in a const fn regardless of the strategy.
+/// It is not freed when dropped, the data structure calls `free_all` instead.
+///
+pub trait Reclaimer<N>: Sized {
+    /// Registration of a thread that accesses nodes, returned by `enter`.
+    type Entered<'a, A: Allocator + 'a>
+    where
+        Self: 'a;
+
+    /// Bookkeeping that has not retired any node yet.
+    #[cfg(not(loom))]
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW: Self;
+
+    /// Bookkeeping that has not retired any node yet, loom atomics cannot be constructed in a const.
+    #[cfg(loom)]
+    fn new() -> Self;
+
+    /// Moves the node into memory of the allocator, reusing the allocation of a freed node if there is one.
+    fn alloc<A: Allocator>(&self, allocator: &A, node: N) -> NonNull<N>;
+
+    /// Same as `alloc` but returns the node if the allocator failed to allocate.
+    fn try_alloc<A: Allocator>(&self, allocator: &A, node: N) -> Result<NonNull<N>, N>;
+
+    /// Allocates `additional` nodes and the bookkeeping needed to retire them and keeps them for reuse.
+    /// `empty` constructs a node whose content is never used.
+    fn reserve<A: Allocator>(&self, allocator: &A, additional: usize, empty: impl Fn() -> N);
+
+    /// Returns the amount of nodes that can be reused without allocating.
+    fn free_node_count(&self) -> usize;
+
+    /// Returns the amount of retired nodes that were not freed yet.
+    fn deferred_count(&self) -> usize;
+
+    /// Returns the amount of threads that are currently registered by `enter`, 0 if the strategy does not count them.
+    fn entered_count(&self) -> usize;
+
+    /// Returns true if no thread is registered by `enter`. Every thread that registered before this call has left then
+    /// and every thread that registers afterward sees everything that happened before this call.
+    /// Always false if the strategy does not count the registered threads.
+    fn is_quiescent(&self) -> bool;
+
+    /// Returns the approximate amount of bytes allocated for the bookkeeping of retired nodes, not counting the nodes themselves.
+    fn bookkeeping_bytes(&self) -> usize;
+
+    /// Returns the counters of retired and freed nodes.
+    #[cfg(feature = "stats")]
+    fn counters(&self) -> &ReclaimCounters;
+
+    /// Registers the calling thread as accessing the nodes of the data structure.
+    /// No node that is retired after this call is freed until the returned value is dropped.
+    /// `hazard_limit` is the amount of nodes that may wait to be freed before the calling thread helps freeing them.
+    ///
+    /// # Errors
+    /// if too many threads are registered at the same time, the calling thread is then not registered.
+    fn try_enter<'a, A: Allocator + 'a>(
+        &'a self,
+        allocator: &'a A,
+        hazard_limit: usize,
+    ) -> Result<Self::Entered<'a, A>, PopContention>;
+
+    /// Same as `try_enter` but panics if too many threads are registered at the same time.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` threads are registered at the same time.
+    fn enter<'a, A: Allocator + 'a>(
+        &'a self,
+        allocator: &'a A,
+        hazard_limit: usize,
+    ) -> Self::Entered<'a, A> {
+        match self.try_enter(allocator, hazard_limit) {
+            Ok(entered) => entered,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Hands a chain of `count` nodes that was removed from the data structure to the strategy
+    /// so it is freed once no thread can access it anymore.
+    ///
+    /// # Safety
+    /// The nodes must have been removed from the data structure with a `SeqCst` operation and their values must have been taken.
+    /// The calling thread must have entered and may not have dropped the value returned by `enter` yet.
+    unsafe fn retire<A: Allocator>(&self, allocator: &A, node: *mut N, count: usize);
+
+    /// Frees the retired nodes that no thread can access anymore without waiting for any thread.
+    fn collect<A: Allocator>(&self, allocator: &A);
+
+    /// Frees all retired nodes once every thread that is currently entered has left
+    /// and returns all nodes that are kept for reuse to the allocator.
+    ///
+    /// Never returns if the calling thread itself has entered.
+    fn quiesce<A: Allocator>(&self, allocator: &A);
+
+    /// Same as `quiesce` but does not wait for entered threads.
+    ///
+    /// # Safety
+    /// No thread may have entered or enter while this fn is running.
+    unsafe fn quiesce_unchecked<A: Allocator>(&self, allocator: &A);
+
+    /// Passes every retired node that was not freed yet and every node kept for reuse to `visit`
+    /// and panics if the bookkeeping does not match them. Requires exclusive access like `free_all`.
+    /// `visit` has to panic on a node it has seen before, otherwise a cycle in a list is never left.
+    #[cfg(any(test, feature = "debug-validate"))]
+    fn assert_invariants(&mut self, visit: impl FnMut(*mut N));
+
+    /// Frees all retired nodes and all nodes that are kept for reuse.
+    ///
+    /// # Safety
+    /// All nodes must have been allocated by the allocator and must no longer be accessed by any thread.
+    unsafe fn free_all<A: Allocator>(&mut self, allocator: &A);
+}
+
+///
+/// Frees popped nodes once every thread that was popping when they were popped has left `pop`.
+///
+/// This is the default strategy. It needs no thread local state and works without `std`.
+/// A popped node is freed right away if no other thread accesses the lifo at the same time,
+/// otherwise it is usually freed by the pop that ends the next but one generation of concurrent pops.
+/// See `AtomicLifo::with_hazard_limit_in` for the case that a thread stays in `pop` for a long time.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HazardReclaim;
+
+impl sealed::Sealed for HazardReclaim {}
+
+impl Reclaim for HazardReclaim {
+    type Link<N> = ();
+    type List<N: Retire<Self>> = HazardList<N>;
+    const RECYCLE: bool = true;
+}
+
+///
+/// Never frees popped nodes while the lifo is alive, they are freed when the lifo is dropped.
+///
+/// Popping is allocation free and never frees anything, it only links the popped node into a list of retired nodes.
+/// The memory of the lifo therefore grows with every pop until it is dropped or `quiesce_unchecked` is called,
+/// which suits short lived lifos, see `AtomicLifo::new_leaky`. `quiesce` cannot free the retired nodes
+/// because it does not know whether other threads are still looking at them, it only returns the nodes kept for reuse.
+/// Popped nodes are not reused with the `dwcas` feature either.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LeakReclaim;
+
+impl sealed::Sealed for LeakReclaim {}
+
+impl Reclaim for LeakReclaim {
+    type Link<N> = AtomicPtr<N>;
+    type List<N: Retire<Self>> = LeakList<N>;
+    const RECYCLE: bool = false;
+}
+
+///
+/// Frees popped nodes using the epoch based reclamation of crossbeam-epoch.
+///
+/// Popping pins the epoch of the calling thread, which is cheaper than the counting of `HazardReclaim`
+/// when many threads pop at the same time. Popped nodes are freed once every thread that was pinned when they were
+/// popped has unpinned, which may take longer than with `HazardReclaim` because crossbeam-epoch collects lazily.
+/// Popped nodes are therefore reused later as well.
+///
+#[cfg(feature = "epoch")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EpochReclaim;
+
+#[cfg(feature = "epoch")]
+impl sealed::Sealed for EpochReclaim {}
+
+#[cfg(feature = "epoch")]
+impl Reclaim for EpochReclaim {
+    type Link<N> = Retired<N>;
+    type List<N: Retire<Self>> = EpochList<N>;
+    const RECYCLE: bool = true;
+}
@@ -0,0 +1,230 @@
+//! List of removed nodes that are reused right away while other threads may still read their next pointers.
+use crate::allocator::{free_in, Allocator};
+#[cfg(not(loom))]
+use crate::atomic::WithMut;
+use crate::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+use crate::backoff::Backoff;
+use crate::hazard::{Unlock, MAX_ENTERED};
+use core::ptr::{null_mut, NonNull};
+use core::sync::atomic::Ordering::{Acquire, Relaxed, Release, SeqCst};
+
+/// Maximum amount of nodes a `RecycleList` keeps, further nodes are retired instead.
+const RECYCLE_CAPACITY: usize = 1024;
+
+/// Node whose next pointer may be read by other threads while it is recycled.
+pub trait Recycle: Sized {
+    /// Returns the next pointer of the node.
+    fn next_ptr(&self) -> &AtomicPtr<Self>;
+}
+
+///
+/// List of nodes that were removed from a data structure whose head detects removals, see `Head::TAGGED`.
+///
+/// Threads that loaded such a node before it was removed only read its next pointer and then fail their compare and swap,
+/// so the node can be pushed again right away as long as it is not freed. Threads that read the values of nodes instead,
+/// for example to peek, register themselves in `readers` and nodes removed while any thread reads are retired as usual.
+///
+/// Like `FreeList` only one thread at a time takes nodes out of the list, which prevents the ABA problem in the list itself.
+/// Nodes stay allocated while they are in the list, only the owner of the list frees them.
+///
+pub struct RecycleList<N: Recycle> {
+    /// the most recently recycled node.
+    head: AtomicPtr<N>,
+    /// amount of nodes in the list.
+    len: AtomicUsize,
+    /// only one thread at a time may take nodes out of the list.
+    lock: AtomicBool,
+    /// amount of threads that read the values of nodes.
+    readers: AtomicUsize,
+}
+
+impl<N: Recycle> RecycleList<N> {
+    const_fn! {
+        /// Constructs a new empty `RecycleList`.
+        pub(crate) fn new() -> Self {
+            Self {
+                head: AtomicPtr::new(null_mut()),
+                len: AtomicUsize::new(0),
+                lock: AtomicBool::new(false),
+                readers: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    /// Registers the calling thread as reading the values of nodes until the returned value is dropped.
+    /// The thread must register before it loads any node.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` threads read at the same time.
+    pub(crate) fn read(&self) -> Reading<'_> {
+        //SeqCst, ordered before the load of the head, see recycle.
+        if self.readers.fetch_add(1, SeqCst) >= MAX_ENTERED {
+            self.readers.fetch_sub(1, SeqCst);
+            panic!("Too many threads reading concurrently");
+        }
+
+        Reading {
+            readers: &self.readers,
+        }
+    }
+
+    /// Adds a removed node to the list, returns false if the caller has to retire it instead
+    /// because a thread may read its value or because the list is full.
+    ///
+    /// # Safety
+    /// The node must have been removed from the data structure with a `SeqCst` operation and its value must have been taken.
+    pub(crate) unsafe fn recycle(&self, node: *mut N) -> bool {
+        //A reader that was registered before the removal may have loaded the node, a reader that registers later cannot.
+        //This relies on the removal, the increment in read and this load being ordered by the single total order of SeqCst.
+        //A reader that leaves releases its reads, which this load acquires.
+        if self.readers.load(SeqCst) != 0 || self.len.load(Relaxed) >= RECYCLE_CAPACITY {
+            return false;
+        }
+
+        self.len.fetch_add(1, Relaxed);
+        let node_ref = &*node;
+        let mut head = self.head.load(Relaxed);
+        loop {
+            node_ref.next_ptr().store(head, Relaxed);
+            //Release, the thread that takes the node must not access it before we are done with it.
+            match self
+                .head
+                .compare_exchange_weak(head, node, Release, Relaxed)
+            {
+                Ok(_) => return true,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Takes a node out of the list.
+    /// Gives up if another thread is currently taking a node, the caller then allocates instead.
+    ///
+    /// The next pointer of the node may still be read by other threads, only atomic stores to it are allowed.
+    pub(crate) fn take(&self) -> Option<NonNull<N>> {
+        //Relaxed, only a hint to skip the lock, the list is checked again after locking.
+        if self.head.load(Relaxed).is_null() || self.lock.swap(true, Acquire) {
+            return None;
+        }
+
+        let _unlock = Unlock { lock: &self.lock };
+
+        //Acquire, synchronizes with the recycle of the node so we see its next pointer.
+        let mut head = self.head.load(Acquire);
+        loop {
+            let node = NonNull::new(head)?;
+            //Safe, nodes are only removed from the list by the thread that holds the lock.
+            let next = unsafe { node.as_ref().next_ptr().load(Relaxed) };
+            match self
+                .head
+                .compare_exchange_weak(head, next, Relaxed, Acquire)
+            {
+                Ok(_) => {
+                    self.len.fetch_sub(1, Relaxed);
+                    return Some(node);
+                }
+                //Only a concurrent recycle can have changed the head.
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Returns the amount of nodes in the list.
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Relaxed)
+    }
+
+    /// Removes all nodes from the list, waits if another thread is currently taking a node.
+    /// Returns the first node of the chain and the amount of nodes in it.
+    pub(crate) fn take_all(&self) -> (*mut N, usize) {
+        let mut backoff = Backoff::new();
+        while self.lock.swap(true, Acquire) {
+            backoff.snooze();
+        }
+
+        let _unlock = Unlock { lock: &self.lock };
+
+        //Acquire, synchronizes with the recycles of the nodes so we see their next pointers.
+        let chain = self.head.swap(null_mut(), Acquire);
+        let mut count = 0;
+        let mut node = chain;
+        //Safe, only the thread that holds the lock removes nodes and nodes are never freed while they are in the list.
+        while let Some(node_ref) = unsafe { node.as_ref() } {
+            count += 1;
+            node = node_ref.next_ptr().load(Relaxed);
+        }
+
+        self.len.fetch_sub(count, Relaxed);
+        (chain, count)
+    }
+
+    /// Returns all nodes in the list to the allocator, waits if another thread is currently taking a node.
+    ///
+    /// # Safety
+    /// All nodes must have been allocated by the allocator and no thread may read their next pointers anymore.
+    pub(crate) unsafe fn clear<A: Allocator>(&self, allocator: &A) {
+        let (mut node, count) = self.take_all();
+        for _ in 0..count {
+            let next = (*node).next_ptr().load(Relaxed);
+            free_in(allocator, node);
+            node = next;
+        }
+    }
+
+    /// Passes every node in the list to `visit` and panics if the length, the lock or the readers of the list are wrong.
+    /// `visit` has to panic on a node it has seen before, otherwise a cycle in the list is never left.
+    #[cfg(any(test, feature = "debug-validate"))]
+    pub(crate) fn assert_invariants(&mut self, mut visit: impl FnMut(*mut N)) {
+        assert!(!self.lock.load(Relaxed), "recycle list is still locked");
+        assert_eq!(
+            self.readers.with_mut(|readers| *readers),
+            0,
+            "a thread is still reading"
+        );
+        let mut count = 0;
+        let mut node = self.head.with_mut(|head| *head);
+        //Safe, nodes are never freed while they are in the list.
+        while let Some(node_ref) = unsafe { node.as_ref() } {
+            visit(node);
+            count += 1;
+            node = node_ref.next_ptr().load(Relaxed);
+        }
+
+        assert_eq!(
+            count,
+            self.len.with_mut(|len| *len),
+            "len of the recycle list does not match its nodes"
+        );
+    }
+
+    /// Frees all nodes in the list.
+    ///
+    /// # Safety
+    /// All nodes must have been allocated by the allocator and no thread may read them anymore.
+    pub(crate) unsafe fn free_all<A: Allocator>(&mut self, allocator: &A) {
+        let mut node = self.head.with_mut(|head| *head);
+        while let Some(node_ref) = node.as_ref() {
+            let next = node_ref.next_ptr().load(Relaxed);
+            free_in(allocator, node);
+            node = next;
+        }
+
+        self.head.with_mut(|head| *head = null_mut());
+        self.len.with_mut(|len| *len = 0);
+    }
+}
+
+/// Registration of a thread that reads the values of nodes, returned by `RecycleList::read`.
+/// Dropping it deregisters the thread, also if the thread unwinds.
+#[must_use]
+pub struct Reading<'a> {
+    /// the counter the thread is registered in.
+    readers: &'a AtomicUsize,
+}
+
+impl Drop for Reading<'_> {
+    fn drop(&mut self) {
+        //SeqCst like the increment, releases our reads of the values to the thread that recycles the nodes.
+        self.readers.fetch_sub(1, SeqCst);
+    }
+}
@@ -0,0 +1,57 @@
+//! `Serialize` and `Deserialize` implementations for `AtomicLifo`.
+use crate::allocator::{Allocator, Global};
+use crate::hazard::DEFAULT_HAZARD_LIMIT;
+use crate::{AtomicLifo, Chain, LifoHooks, Reclaim};
+use core::fmt::Formatter;
+use core::marker::PhantomData;
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes the elements as a sequence from top to bottom using a traversal.
+///
+/// Elements pushed or popped by other threads while serializing may or may not be serialized.
+/// A concurrent call to `pop` that removes the element that is currently being serialized
+/// will wait until the element was serialized.
+impl<T: Sync + Send + Serialize, A: Allocator + Clone, R: Reclaim, H: LifoHooks<T>> Serialize
+    for AtomicLifo<T, A, R, H>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        let mut result = Ok(());
+        self.traverse(|element| {
+            result = seq.serialize_element(element);
+            result.is_ok()
+        });
+
+        result?;
+        seq.end()
+    }
+}
+
+/// Deserializes a sequence, the first element of the sequence ends up on top of the lifo and is popped first.
+impl<'de, T: Send + Deserialize<'de>> Deserialize<'de> for AtomicLifo<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(LifoVisitor(PhantomData))
+    }
+}
+
+/// Visitor that builds a lifo from a sequence.
+struct LifoVisitor<T: Send>(PhantomData<T>);
+
+impl<'de, T: Send + Deserialize<'de>> Visitor<'de> for LifoVisitor<T> {
+    type Value = AtomicLifo<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut chain = Chain::new(Global);
+        while let Some(value) = seq.next_element()? {
+            chain.push_bottom(value);
+        }
+
+        Ok(AtomicLifo::from_chain(chain, DEFAULT_HAZARD_LIMIT, ()))
+    }
+}
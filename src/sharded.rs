@@ -0,0 +1,126 @@
+//! Lifo split into several lifos that threads push to and pop from by affinity.
+use crate::AtomicLifo;
+use core::fmt::{Debug, Formatter};
+
+///
+/// Thread Safe stack of `N` `AtomicLifo` shards, for workloads that do not need a single order across all threads.
+///
+/// Every thread has a home shard that it pushes to and that it pops from first.
+/// Only once its home shard is empty a pop looks at the other shards, starting with the shard after the home shard,
+/// so threads with different home shards also scan in a different order.
+/// With the `std` feature threads are assigned their home shard round-robin when they first use a `ShardedLifo`,
+/// without it the home shard is picked by the address of the stack of the thread, which usually but not always
+/// stays the same for all calls of a thread.
+///
+/// Elements pushed by the same thread are popped by that thread in LIFO order,
+/// elements of different threads are popped in no particular order.
+/// Threads that push and pop concurrently mostly contend on different heads.
+///
+pub struct ShardedLifo<T: Send, const N: usize> {
+    /// the shards, indexed by the home shard of a thread.
+    shards: [AtomicLifo<T>; N],
+}
+
+impl<T: Sync + Send + Debug, const N: usize> Debug for ShardedLifo<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ShardedLifo")
+            .field("shards", &self.shards)
+            .finish()
+    }
+}
+
+impl<T: Send, const N: usize> Default for ShardedLifo<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hands out home shards round-robin to threads that use a `ShardedLifo` for the first time.
+#[cfg(feature = "std")]
+static NEXT_HOME: crate::atomic::AtomicUsize = crate::atomic::AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// the home shard of the calling thread, before it is reduced to the amount of shards of a lifo.
+    static HOME: usize = NEXT_HOME.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+}
+
+impl<T: Send, const N: usize> ShardedLifo<T, N> {
+    /// Constructs a new empty `ShardedLifo`
+    ///
+    /// # Panics
+    /// if `N` is 0.
+    #[must_use]
+    pub const fn new() -> Self {
+        assert!(N > 0, "ShardedLifo needs at least one shard");
+        Self {
+            shards: [const { AtomicLifo::new() }; N],
+        }
+    }
+
+    /// Pushes a value on top of the home shard of the calling thread.
+    pub fn push(&self, value: T) {
+        self.shards[Self::home()].push(value);
+    }
+
+    ///
+    /// Pops the top of the home shard of the calling thread, or of the first other shard that is not empty.
+    ///
+    /// Returns None only after every shard was found empty. Shards that were already checked may receive
+    /// an element while the remaining ones are checked, so like `AtomicLifo::pop` the result is only a snapshot.
+    ///
+    /// # Panics
+    /// if more than `isize::MAX` concurrent calls in different threads to this fn are made.
+    ///
+    pub fn pop(&self) -> Option<T> {
+        let home = Self::home();
+        (0..N).find_map(|offset| self.shards[(home + offset) % N].pop())
+    }
+
+    ///
+    /// Returns true if every shard contains no elements.
+    ///
+    /// The shards are checked one after another, so this is an even weaker snapshot than `AtomicLifo::is_empty`.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(AtomicLifo::is_empty)
+    }
+
+    ///
+    /// Returns the amount of elements in all shards.
+    ///
+    /// See `AtomicLifo::len`.
+    ///
+    #[cfg(feature = "count")]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(AtomicLifo::len).sum()
+    }
+
+    ///
+    /// Consumes the lifo and returns all elements, shard after shard in the order `pop` of each shard would have returned them.
+    ///
+    #[must_use]
+    pub fn into_vec(self) -> alloc::vec::Vec<T> {
+        self.shards
+            .into_iter()
+            .flat_map(AtomicLifo::into_vec)
+            .collect()
+    }
+
+    /// Returns the index of the home shard of the calling thread.
+    #[cfg(feature = "std")]
+    fn home() -> usize {
+        HOME.with(|home| *home % N)
+    }
+
+    /// Returns the index of the home shard of the calling thread.
+    #[cfg(not(feature = "std"))]
+    fn home() -> usize {
+        //Stacks of different threads are far apart, calls at different depths of the same thread rarely cross 64 KiB.
+        let local = 0u8;
+        let hash = ((core::ptr::addr_of!(local) as usize) >> 16).wrapping_mul(0x9E37_79B9) >> 8;
+        hash % N
+    }
+}
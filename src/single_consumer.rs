@@ -0,0 +1,123 @@
+//! Lifo that only supports a single consuming thread and therefore needs no hazard list.
+use crate::allocator::free_in;
+use crate::hazard::Linked;
+use crate::AtomicLifo;
+use core::fmt::{Debug, Formatter};
+#[cfg(feature = "count")]
+use core::sync::atomic::Ordering::Relaxed;
+use core::sync::atomic::Ordering::SeqCst;
+
+///
+/// Thread Safe LIFO Stack that may be pushed to by any amount of threads but only popped by a single thread at a time.
+///
+/// Because no other thread can ever look at a node that was popped, `pop` frees the node immediately
+/// and does not need any of the hazard bookkeeping of `AtomicLifo::pop`.
+/// Pushing is identical to `AtomicLifo::push`.
+///
+/// # Soundness
+/// `pop` is unsafe because calling it concurrently from two threads is a use after free.
+/// It is sound as long as all calls to `pop` on the same lifo happen-before each other,
+/// for example because they are all made from the same thread or synchronized by a mutex.
+/// Calls to `push`, `extend` and `is_empty` may be made concurrently from any amount of threads.
+/// For the same reason this type does not print its elements in its `Debug` impl.
+///
+#[derive(Default)]
+pub struct AtomicLifoSc<T: Send> {
+    /// the lifo, its hazard list is never used.
+    inner: AtomicLifo<T>,
+}
+
+impl<T: Send> Debug for AtomicLifoSc<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AtomicLifoSc").finish_non_exhaustive()
+    }
+}
+
+impl<T: Send> AtomicLifoSc<T> {
+    /// Constructs a new empty `AtomicLifoSc`
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: AtomicLifo::new(),
+        }
+    }
+
+    /// Pushes a value on top of the lifo stack
+    pub fn push(&self, value: T) {
+        self.inner.push(value);
+    }
+
+    ///
+    /// Pushes all values of the iterator on top of the lifo stack.
+    ///
+    /// See `AtomicLifo::extend`.
+    ///
+    pub fn extend(&self, iter: impl IntoIterator<Item = T>) {
+        self.inner.extend(iter);
+    }
+
+    ///
+    /// Pops the top of the lifo stack and frees its node immediately.
+    ///
+    /// # Safety
+    /// No other thread may call this fn at the same time, see the soundness section of the type documentation.
+    ///
+    pub unsafe fn pop(&self) -> Option<T> {
+        if size_of::<T>() == 0 {
+            //Zero sized values are only counted and need no hazard bookkeeping.
+            return self.inner.pop();
+        }
+
+        loop {
+            let head = self.inner.head.load(SeqCst);
+            let node = head.ptr.as_ref()?;
+
+            //No other thread can remove this node, so its next pointer cannot change while it is the head.
+            if self
+                .inner
+                .head
+                .remove(head, node.next(), SeqCst, SeqCst)
+                .is_err()
+            {
+                //Some thread pushed in the meantime.
+                continue;
+            }
+
+            #[cfg(feature = "count")]
+            self.inner.len.fetch_sub(1, Relaxed);
+
+            let value = (*head.ptr).value.get_mut().assume_init_read();
+            free_in(&self.inner.allocator, head.ptr);
+            return Some(value);
+        }
+    }
+
+    ///
+    /// Returns true if the lifo contains no elements.
+    ///
+    /// See `AtomicLifo::is_empty`.
+    ///
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    ///
+    /// Returns the amount of elements in the lifo.
+    ///
+    /// See `AtomicLifo::len`.
+    ///
+    #[cfg(feature = "count")]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    ///
+    /// Consumes the lifo and returns all elements in the order `pop` would have returned them.
+    ///
+    #[must_use]
+    pub fn into_vec(self) -> alloc::vec::Vec<T> {
+        self.inner.into_vec()
+    }
+}
@@ -0,0 +1,126 @@
+//! Event counters of the `stats` feature.
+//!
+//! All counters are incremented with Relaxed, they never guard an access to anything.
+//! A snapshot of several counters is therefore not consistent while other threads use the lifo.
+#[cfg(target_has_atomic = "64")]
+use crate::atomic::AtomicU64 as AtomicCounter;
+//Without 64 bit atomics the counters are pointer sized and wrap around after `usize::MAX` events.
+#[cfg(not(target_has_atomic = "64"))]
+use crate::atomic::AtomicUsize as AtomicCounter;
+use core::sync::atomic::Ordering::Relaxed;
+
+///
+/// Snapshot of the counters of an `AtomicLifo`, returned by `AtomicLifo::stats`.
+///
+/// Elements that a fn removes and pushes back, for example `retain` or `pop_bottom`, count as popped and pushed again.
+/// Elements a lifo was constructed with, for example by `from` or `clone`, count as pushed.
+/// Once all concurrent calls have returned, `pushes - pops` is the amount of elements in the lifo.
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifoStats {
+    /// amount of elements that were pushed.
+    pub pushes: u64,
+    /// amount of elements that were popped, including elements removed by `take_all` or `clear`.
+    pub pops: u64,
+    /// amount of calls to `pop` and similar fns that found the lifo empty.
+    pub empty_pops: u64,
+    /// amount of times linking pushed nodes failed because another thread changed the head.
+    pub push_retries: u64,
+    /// amount of times removing nodes failed because another thread changed the head.
+    pub pop_retries: u64,
+    /// amount of popped nodes that were handed to the reclamation strategy.
+    /// With the `dwcas` feature popped nodes that are reused right away are not retired.
+    pub retired: u64,
+    /// amount of retired nodes that were freed or kept for reuse by the strategy.
+    /// The difference to `retired` is the amount of nodes that are waiting to be freed.
+    pub freed: u64,
+    /// amount of times a thread found more than the hazard limit of popped nodes waiting to be freed and helped freeing them.
+    pub hazard_limit_hits: u64,
+    /// amount of pops that took the element of a push from the elimination array instead of the lifo,
+    /// both are counted in `pushes` and `pops` as well. Always 0 without `AtomicLifo::new_with_elimination`.
+    pub eliminations: u64,
+    /// amount of values `AtomicLifo::push_or_exchange` handed directly to `AtomicLifo::pop_waiting`,
+    /// they are counted in `pushes` and `pops` as well.
+    pub exchanges: u64,
+}
+
+/// Counter of events that is only ever incremented.
+pub struct Counter(AtomicCounter);
+
+impl Counter {
+    const_fn! {
+        /// Constructs a counter that has not counted anything yet.
+        pub fn new() -> Self {
+            Self(AtomicCounter::new(0))
+        }
+    }
+
+    /// Counts `count` events.
+    pub fn add(&self, count: usize) {
+        self.0.fetch_add(count as _, Relaxed);
+    }
+
+    /// Returns the amount of counted events.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn get(&self) -> u64 {
+        self.0.load(Relaxed) as u64
+    }
+}
+
+/// Counters of the operations of a lifo.
+pub struct LifoCounters {
+    /// see `LifoStats::pushes`.
+    pub pushes: Counter,
+    /// see `LifoStats::pops`.
+    pub pops: Counter,
+    /// see `LifoStats::empty_pops`.
+    pub empty_pops: Counter,
+    /// see `LifoStats::push_retries`.
+    pub push_retries: Counter,
+    /// see `LifoStats::pop_retries`.
+    pub pop_retries: Counter,
+    /// see `LifoStats::eliminations`.
+    pub eliminations: Counter,
+    /// see `LifoStats::exchanges`.
+    pub exchanges: Counter,
+}
+
+impl LifoCounters {
+    const_fn! {
+        /// Constructs counters that have not counted anything yet.
+        pub fn new() -> Self {
+            Self {
+                pushes: Counter::new(),
+                pops: Counter::new(),
+                empty_pops: Counter::new(),
+                push_retries: Counter::new(),
+                pop_retries: Counter::new(),
+                eliminations: Counter::new(),
+                exchanges: Counter::new(),
+            }
+        }
+    }
+}
+
+/// Counters of a reclamation strategy.
+pub struct ReclaimCounters {
+    /// see `LifoStats::retired`.
+    pub retired: Counter,
+    /// see `LifoStats::freed`.
+    pub freed: Counter,
+    /// see `LifoStats::hazard_limit_hits`.
+    pub limit_hits: Counter,
+}
+
+impl ReclaimCounters {
+    const_fn! {
+        /// Constructs counters that have not counted anything yet.
+        pub fn new() -> Self {
+            Self {
+                retired: Counter::new(),
+                freed: Counter::new(),
+                limit_hits: Counter::new(),
+            }
+        }
+    }
+}
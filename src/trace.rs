@@ -0,0 +1,53 @@
+//! Events of the `tracing` feature.
+//!
+//! Operations only count locally and emit a single event once a count crosses a threshold,
+//! there are no spans and no events for operations that went as expected.
+//! Without the feature all fns do nothing and compile to nothing.
+#![cfg_attr(
+    not(feature = "tracing"),
+    allow(unused_variables, clippy::missing_const_for_fn)
+)]
+
+/// Amount of failed compare and swaps of a single operation from which on it emits an event.
+#[cfg(feature = "tracing")]
+const RETRY_THRESHOLD: u32 = 32;
+
+/// Amount of nodes a single free of the hazard list has to free to emit an event.
+#[cfg(feature = "tracing")]
+const FREED_THRESHOLD: usize = 1024;
+
+/// Emits an event if the compare and swap loop of the operation failed at least `RETRY_THRESHOLD` times.
+pub fn retries(operation: &'static str, retries: u32) {
+    #[cfg(feature = "tracing")]
+    if retries >= RETRY_THRESHOLD {
+        tracing::debug!(
+            target: "atomic_lifo",
+            operation,
+            retries,
+            "compare and swap retried {retries} times"
+        );
+    }
+}
+
+/// Emits an event because more than `hazard_limit` popped nodes wait to be freed and the calling thread helps freeing them.
+pub fn hazard_limit(deferred: usize, hazard_limit: usize) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        target: "atomic_lifo",
+        deferred,
+        hazard_limit,
+        "hazard limit reached with {deferred} nodes waiting to be freed"
+    );
+}
+
+/// Emits an event if a single free of old hazard generations freed at least `FREED_THRESHOLD` nodes.
+pub fn freed(freed: usize) {
+    #[cfg(feature = "tracing")]
+    if freed >= FREED_THRESHOLD {
+        tracing::debug!(
+            target: "atomic_lifo",
+            freed,
+            "freed {freed} deferred nodes"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Waker slots of the futures and streams that wait for elements of an `AtomicLifo`.
+use crate::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+use crate::AtomicLifo;
+use alloc::boxed::Box;
+use core::cell::UnsafeCell;
+use core::ptr::{null_mut, NonNull};
+use core::sync::atomic::Ordering::{AcqRel, Acquire, Release, SeqCst};
+use core::task::Waker;
+
+/// State of a slot whose waker is not accessed by any thread.
+const WAITING: usize = 0;
+/// Bit of the state that is set while the owner of a slot replaces its waker.
+const REGISTERING: usize = 1;
+/// Bit of the state that is set while a thread takes the waker of a slot.
+const WAKING: usize = 2;
+
+///
+/// Slot of a single future or stream that holds the waker it was polled with last, like `AtomicWaker` of `futures`.
+///
+/// Only the owner of the slot registers wakers, any thread may take the registered waker to wake it.
+/// Re-polling replaces the waker, so a future that is polled many times occupies a single slot.
+///
+pub struct WakerSlot {
+    /// `WAITING` or the `REGISTERING` and `WAKING` bits of the threads that currently access `waker`.
+    state: AtomicUsize,
+    /// the registered waker, None once it was taken.
+    waker: UnsafeCell<Option<Waker>>,
+    /// true while the slot is in the queue of `Wakers`, a slot is queued at most once.
+    queued: AtomicBool,
+    /// next slot in the list of all slots, set before the slot is published.
+    next: *mut Self,
+}
+
+//The waker is only accessed by the thread that set REGISTERING or WAKING in the state.
+unsafe impl Send for WakerSlot {}
+unsafe impl Sync for WakerSlot {}
+
+impl WakerSlot {
+    /// Replaces the registered waker.
+    ///
+    /// # Safety
+    /// Only the owner of the slot may call this fn.
+    unsafe fn register(&self, waker: &Waker) {
+        if self
+            .state
+            .compare_exchange(WAITING, REGISTERING, Acquire, Acquire)
+            .is_err()
+        {
+            //A thread is taking the previous waker right now, the task was woken and has to poll again.
+            waker.wake_by_ref();
+            return;
+        }
+
+        let registered = &mut *self.waker.get();
+        if !registered
+            .as_ref()
+            .is_some_and(|registered| registered.will_wake(waker))
+        {
+            *registered = Some(waker.clone());
+        }
+
+        if self
+            .state
+            .compare_exchange(REGISTERING, WAITING, AcqRel, Acquire)
+            .is_err()
+        {
+            //A thread tried to take the waker while we replaced it and left the waking to us.
+            let waker = registered.take();
+            self.state.swap(WAITING, AcqRel);
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Takes the registered waker.
+    /// Returns None if no waker is registered or the owner is replacing it right now, then the owner wakes itself.
+    fn take(&self) -> Option<Waker> {
+        if self.state.fetch_or(WAKING, AcqRel) != WAITING {
+            return None;
+        }
+
+        //Safe, we set WAKING while nobody was registering, the owner leaves the waker to us.
+        let waker = unsafe { (*self.waker.get()).take() };
+        self.state.fetch_and(!WAKING, Release);
+        waker
+    }
+}
+
+/// Reference to a slot that is stored in the lists of `Wakers`.
+struct SlotRef(NonNull<WakerSlot>);
+
+//The slots are Sync and outlive the lists.
+unsafe impl Send for SlotRef {}
+
+impl SlotRef {
+    /// Returns the slot.
+    const fn slot(&self) -> &WakerSlot {
+        //Safe, slots are only freed when `Wakers` is dropped.
+        unsafe { self.0.as_ref() }
+    }
+}
+
+///
+/// Waker slots of the tasks that wait for an element of a lifo.
+///
+/// Every future and stream acquires a slot the first time it has to wait and releases it when it is dropped.
+/// Released slots are reused by the next future, so the lifo holds at most as many slots as futures waited at the same time.
+///
+pub struct Wakers {
+    /// slots whose owners wait for an element, woken from the top.
+    queued: AtomicLifo<SlotRef>,
+    /// slots whose owner was dropped.
+    free: AtomicLifo<SlotRef>,
+    /// every slot that was allocated, linked by their next pointer.
+    all: AtomicPtr<WakerSlot>,
+}
+
+impl Wakers {
+    const_fn! {
+        /// Constructs a new `Wakers` without any slots.
+        pub fn new() -> Self {
+            Self {
+                queued: AtomicLifo::new(),
+                free: AtomicLifo::new(),
+                all: AtomicPtr::new(null_mut()),
+            }
+        }
+    }
+
+    /// Returns a slot for a future that has to wait, reuses a released slot if there is one.
+    pub fn acquire(&self) -> &WakerSlot {
+        if let Some(slot) = self.free.pop() {
+            //Safe, slots are only freed when we are dropped.
+            return unsafe { slot.0.as_ref() };
+        }
+
+        let slot = Box::into_raw(Box::new(WakerSlot {
+            state: AtomicUsize::new(WAITING),
+            waker: UnsafeCell::new(None),
+            queued: AtomicBool::new(false),
+            next: null_mut(),
+        }));
+
+        let mut all = self.all.load(SeqCst);
+        loop {
+            //Safe, the slot is not published yet.
+            unsafe {
+                (*slot).next = all;
+            }
+
+            match self.all.compare_exchange_weak(all, slot, SeqCst, SeqCst) {
+                //Safe, slots are only freed when we are dropped.
+                Ok(_) => return unsafe { &*slot },
+                Err(current) => all = current,
+            }
+        }
+    }
+
+    /// Registers the waker in the slot and queues the slot unless it is queued already.
+    ///
+    /// # Safety
+    /// Only the owner of the slot may call this fn.
+    pub unsafe fn register(&self, slot: &WakerSlot, waker: &Waker) {
+        slot.register(waker);
+        //The waker is registered before queued is checked and taken after queued was cleared,
+        //so either we queue the slot again or the thread that dequeued it takes our waker.
+        if !slot.queued.swap(true, SeqCst) {
+            self.queued.push(SlotRef(NonNull::from(slot)));
+        }
+    }
+
+    /// Wakes up to `count` tasks, skips slots whose owner was dropped or was woken already.
+    pub fn wake(&self, count: usize) {
+        for _ in 0..count {
+            loop {
+                let Some(slot) = self.queued.pop() else {
+                    return;
+                };
+
+                let slot = slot.slot();
+                slot.queued.store(false, SeqCst);
+                if let Some(waker) = slot.take() {
+                    waker.wake();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Drops the waker of the slot and makes the slot available to the next future that has to wait.
+    /// Returns true if the waker was taken before, the owner was woken then.
+    ///
+    /// # Safety
+    /// Only the owner of the slot may call this fn and it must not use the slot afterward.
+    pub unsafe fn release(&self, slot: &WakerSlot) -> bool {
+        let woken = slot.take().is_none();
+        //The slot may still be queued, the next owner then does not have to queue it again.
+        self.free.push(SlotRef(NonNull::from(slot)));
+        woken
+    }
+}
+
+impl Drop for Wakers {
+    fn drop(&mut self) {
+        let mut current = self.all.load(SeqCst);
+        while !current.is_null() {
+            //Safe, every slot is in the list once and no future outlives the lifo.
+            let slot = unsafe { Box::from_raw(current) };
+            current = slot.next;
+        }
+    }
+}
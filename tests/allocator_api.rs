@@ -0,0 +1,89 @@
+#![cfg(feature = "allocator-api")]
+use allocator_api2::alloc::{AllocError, Allocator, Global};
+use atomic_lifo::{AtomicFifo, AtomicLifo};
+use std::alloc::Layout;
+use std::ptr::NonNull;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u64 = if cfg!(miri) { 100 } else { 10_000 };
+
+#[derive(Default)]
+struct TrackingAllocator {
+    allocations: AtomicUsize,
+    live: AtomicUsize,
+}
+
+unsafe impl Allocator for TrackingAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.allocations.fetch_add(1, SeqCst);
+        self.live.fetch_add(1, SeqCst);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.fetch_sub(1, SeqCst);
+        Global.deallocate(ptr, layout);
+    }
+}
+
+#[test]
+pub fn test_new_in() {
+    let allocator = TrackingAllocator::default();
+    let lifo = AtomicLifo::new_in(&allocator);
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    assert_eq!(allocator.live.load(SeqCst), 2);
+    assert_eq!(lifo.pop(), Some(String::from("test2")));
+    lifo.extend(["test3", "test4"].map(String::from));
+    lifo.reserve(8);
+
+    let clone = lifo.clone();
+    assert_eq!(format!("{clone:?}"), format!("{lifo:?}"));
+    assert_eq!(clone.into_iter().collect::<Vec<_>>(), ["test4", "test3", "test1"]);
+    assert_eq!(lifo.drain_fifo().next(), Some(String::from("test1")));
+    lifo.push(String::from("test5"));
+    assert_eq!(lifo.take_all().count(), 1);
+    lifo.push(String::from("test6"));
+    drop(lifo);
+
+    assert!(allocator.allocations.load(SeqCst) > 2);
+    assert_eq!(allocator.live.load(SeqCst), 0);
+}
+
+#[test]
+pub fn test_new_in_mt() {
+    let allocator = TrackingAllocator::default();
+    let lifo = AtomicLifo::new_in(&allocator);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for i in 0..ITERATIONS {
+                    lifo.push(i);
+                    lifo.pop();
+                }
+            });
+        }
+    });
+
+    lifo.push(1);
+    drop(lifo);
+    assert_eq!(allocator.live.load(SeqCst), 0);
+}
+
+#[test]
+pub fn test_fifo_new_in() {
+    let allocator = TrackingAllocator::default();
+    let fifo = AtomicFifo::new_in(&allocator);
+    fifo.push(String::from("test1"));
+    fifo.push(String::from("test2"));
+    //The dummy node and one node per push, the values are stored in the nodes.
+    assert_eq!(allocator.allocations.load(SeqCst), 3);
+    assert_eq!(fifo.pop(), Some(String::from("test1")));
+    fifo.push(String::from("test3"));
+    drop(fifo);
+
+    assert_eq!(allocator.live.load(SeqCst), 0);
+}
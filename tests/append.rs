@@ -0,0 +1,59 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 1000 };
+
+#[test]
+pub fn test_append() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.append(AtomicLifo::new());
+    assert!(lifo.is_empty());
+    lifo.extend(["test1", "test2"].map(String::from));
+    let other = AtomicLifo::<String>::new();
+    other.extend(["test3", "test4"].map(String::from));
+    assert_eq!(other.pop().unwrap(), String::from("test4"));
+    lifo.append(other);
+    assert_eq!(lifo.into_vec(), ["test3", "test2", "test1"].map(String::from).to_vec());
+}
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+struct DropCounter;
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        DROP_COUNT.fetch_add(1, SeqCst);
+    }
+}
+
+static MT_LIFO: AtomicLifo<DropCounter> = AtomicLifo::new();
+
+#[test]
+pub fn test_append_mt() {
+    let mut jh = Vec::new();
+    for _ in 0..4 {
+        jh.push(thread::spawn(|| {
+            for _ in 0..ITERATIONS {
+                let other = AtomicLifo::new();
+                for _ in 0..10 {
+                    other.push(DropCounter);
+                }
+
+                _ = other.pop();
+                MT_LIFO.append(other);
+                _ = MT_LIFO.pop();
+            }
+        }));
+    }
+
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    assert_eq!(DROP_COUNT.load(SeqCst), 8 * ITERATIONS);
+    assert_eq!(MT_LIFO.drain().count(), 32 * ITERATIONS);
+    assert_eq!(DROP_COUNT.load(SeqCst), 40 * ITERATIONS);
+}
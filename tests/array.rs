@@ -0,0 +1,77 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::ArrayLifo;
+
+#[test]
+pub fn test_array() {
+    let lifo = ArrayLifo::<String, 2>::new();
+    assert_eq!(lifo.capacity(), 2);
+    assert_eq!(lifo.pop(), None);
+    assert!(lifo.is_empty());
+    assert!(lifo.try_push(String::from("test1")).is_ok());
+    assert!(lifo.try_push(String::from("test2")).is_ok());
+    assert_eq!(lifo.try_push(String::from("test3")), Err(String::from("test3")));
+    assert_eq!(lifo.pop().unwrap(), String::from("test2"));
+    assert!(lifo.try_push(String::from("test4")).is_ok());
+    assert_eq!(lifo.pop().unwrap(), String::from("test4"));
+    assert!(lifo.try_push(String::from("test5")).is_ok());
+    assert!(!lifo.is_empty());
+    drop(lifo);
+    assert!(ArrayLifo::<u32, 0>::new().try_push(1).is_err());
+}
+
+static MT_LIFO: ArrayLifo<u32, 16> = ArrayLifo::new();
+
+#[test]
+pub fn test_array_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for t in 0..4u32 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || {
+            let mut pushed = 0u64;
+            let mut popped = 0u64;
+            while !stop_clone.load(SeqCst) {
+                if MT_LIFO.try_push(t).is_ok() {
+                    pushed += 1;
+                }
+
+                if let Some(value) = MT_LIFO.pop() {
+                    assert!(value < 4);
+                    popped += 1;
+                }
+            }
+
+            (pushed, popped)
+        }));
+    }
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    let mut pushed = 0;
+    let mut popped = 0;
+    for jh in jh {
+        let (p, q) = jh.join().unwrap();
+        pushed += p;
+        popped += q;
+    }
+
+    while MT_LIFO.pop().is_some() {
+        popped += 1;
+    }
+
+    assert_eq!(pushed, popped);
+    for i in 0..16 {
+        assert!(MT_LIFO.try_push(i).is_ok());
+    }
+
+    assert!(MT_LIFO.try_push(16).is_err());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "std")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use std::time::{Duration, Instant};
+use atomic_lifo::BlockingLifo;
+
+const CONSUMERS: usize = 8;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITEMS: usize = if cfg!(miri) { 200 } else { 1_000_000 };
+const BURST: usize = if cfg!(miri) { 20 } else { 1000 };
+
+#[test]
+pub fn test_pop_wait() {
+    let lifo = BlockingLifo::new();
+    assert_eq!(lifo.pop(), None);
+    lifo.push(1);
+    lifo.extend([2, 3]);
+    assert_eq!(lifo.pop_wait(), 3);
+    assert_eq!(lifo.pop_wait_timeout(Duration::ZERO), Some(2));
+    assert_eq!(lifo.into_vec(), [1]);
+}
+
+#[test]
+pub fn test_pop_wait_timeout() {
+    let lifo = BlockingLifo::<u32>::new();
+    assert_eq!(lifo.pop_wait_timeout(Duration::ZERO), None);
+    let start = Instant::now();
+    assert_eq!(lifo.pop_wait_timeout(Duration::from_millis(100)), None);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert_eq!(lifo.waiting_count(), 0);
+
+    thread::scope(|scope| {
+        let consumer = scope.spawn(|| lifo.pop_wait_timeout(Duration::from_secs(60)));
+        while lifo.waiting_count() == 0 {
+            thread::yield_now();
+        }
+
+        lifo.push(1);
+        assert_eq!(consumer.join().unwrap(), Some(1));
+    });
+    assert_eq!(lifo.waiting_count(), 0);
+}
+
+#[test]
+pub fn test_pop_wait_mt() {
+    let lifo = BlockingLifo::<usize>::new();
+    let received: Vec<AtomicBool> = (0..ITEMS).map(|_| AtomicBool::new(false)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..CONSUMERS {
+            scope.spawn(|| loop {
+                let item = lifo.pop_wait();
+                if item == usize::MAX {
+                    return;
+                }
+
+                assert!(!received[item].swap(true, SeqCst), "{item} was received twice");
+            });
+        }
+
+        //Bursts of pushes with pauses in between, so the consumers keep going back to waiting.
+        for burst in (0..ITEMS).step_by(BURST) {
+            for item in burst..ITEMS.min(burst + BURST) {
+                lifo.push(item);
+            }
+
+            if (burst / BURST).is_multiple_of(10) {
+                thread::sleep(Duration::from_millis(1));
+            } else {
+                thread::yield_now();
+            }
+        }
+
+        //The sentinels would be popped before the remaining items.
+        while !lifo.is_empty() {
+            thread::yield_now();
+        }
+
+        lifo.extend([usize::MAX; CONSUMERS]);
+    });
+
+    assert!(received.iter().all(|received| received.load(SeqCst)));
+    assert!(lifo.is_empty());
+    assert_eq!(lifo.waiting_count(), 0);
+}
@@ -0,0 +1,111 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::BoundedAtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 500 } else { 50_000 };
+const EVICT_ITERATIONS: u32 = if cfg!(miri) { 100 } else { 10_000 };
+
+#[test]
+pub fn test_bounded() {
+    let lifo = BoundedAtomicLifo::<String>::new(2);
+    assert_eq!(lifo.capacity(), 2);
+    assert!(lifo.is_empty());
+    assert!(lifo.try_push(String::from("test1")).is_ok());
+    assert!(!lifo.is_full());
+    assert!(lifo.try_push(String::from("test2")).is_ok());
+    assert!(lifo.is_full());
+    assert_eq!(lifo.len(), 2);
+    assert_eq!(lifo.try_push(String::from("test3")), Err(String::from("test3")));
+    assert_eq!(lifo.pop().unwrap(), String::from("test2"));
+    assert!(lifo.try_push(String::from("test4")).is_ok());
+    assert_eq!(lifo.into_vec(), ["test4", "test1"].map(String::from).to_vec());
+    assert!(BoundedAtomicLifo::new(0).try_push(1).is_err());
+}
+
+static MT_LIFO: BoundedAtomicLifo<u32> = BoundedAtomicLifo::new(64);
+static ACCEPTED: AtomicUsize = AtomicUsize::new(0);
+static POPPED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+pub fn test_bounded_mt() {
+    let mut jh = Vec::new();
+    for _ in 0..4 {
+        jh.push(thread::spawn(|| {
+            for i in 0..ITERATIONS {
+                if MT_LIFO.try_push(i).is_ok() {
+                    ACCEPTED.fetch_add(1, SeqCst);
+                }
+
+                assert!(MT_LIFO.len() <= 64);
+            }
+        }));
+    }
+
+    for _ in 0..2 {
+        jh.push(thread::spawn(|| {
+            for _ in 0..ITERATIONS {
+                if MT_LIFO.pop().is_some() {
+                    POPPED.fetch_add(1, SeqCst);
+                }
+            }
+        }));
+    }
+
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    let mut remaining = 0;
+    while MT_LIFO.pop().is_some() {
+        remaining += 1;
+    }
+
+    assert!(remaining <= 64);
+    assert_eq!(ACCEPTED.load(SeqCst), POPPED.load(SeqCst) + remaining);
+    assert_eq!(MT_LIFO.len(), 0);
+}
+
+#[test]
+pub fn test_push_evict() {
+    let lifo = BoundedAtomicLifo::<String>::new(2);
+    assert_eq!(lifo.push_evict(String::from("test1")), None);
+    assert_eq!(lifo.push_evict(String::from("test2")), None);
+    assert_eq!(lifo.push_evict(String::from("test3")), Some(String::from("test1")));
+    assert_eq!(lifo.len(), 2);
+    assert_eq!(lifo.try_push(String::from("test4")), Err(String::from("test4")));
+    assert_eq!(lifo.into_vec(), ["test3", "test2"].map(String::from).to_vec());
+    assert_eq!(BoundedAtomicLifo::new(0).push_evict(1), Some(1));
+}
+
+static EVICT_LIFO: BoundedAtomicLifo<u32> = BoundedAtomicLifo::new(4);
+static EVICTED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+pub fn test_push_evict_mt() {
+    let mut jh = Vec::new();
+    for _ in 0..4 {
+        jh.push(thread::spawn(|| {
+            for i in 0..EVICT_ITERATIONS {
+                if EVICT_LIFO.push_evict(i).is_some() {
+                    EVICTED.fetch_add(1, SeqCst);
+                }
+
+                assert!(EVICT_LIFO.len() <= 4);
+            }
+        }));
+    }
+
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    let mut remaining = 0;
+    while EVICT_LIFO.pop().is_some() {
+        remaining += 1;
+    }
+
+    assert_eq!(remaining, 4);
+    assert_eq!(EVICTED.load(SeqCst) + remaining, 4 * EVICT_ITERATIONS as usize);
+}
@@ -0,0 +1,41 @@
+use atomic_lifo::AtomicLifo;
+
+#[test]
+#[allow(deprecated)]
+pub fn test_push_boxed() {
+    let lifo = AtomicLifo::<[u8; 4096]>::new();
+    lifo.push_boxed(Box::new([7u8; 4096]));
+    lifo.push([1u8; 4096]);
+    assert_eq!(lifo.pop().unwrap(), [1u8; 4096]);
+    assert_eq!(lifo.peek_with(|value| value[0]), Some(7));
+    assert_eq!(lifo.pop().unwrap(), [7u8; 4096]);
+    assert_eq!(lifo.pop(), None);
+}
+
+#[test]
+#[allow(deprecated)]
+pub fn test_pop_boxed() {
+    let lifo = AtomicLifo::<[u8; 4096]>::new();
+    assert_eq!(lifo.pop_boxed(), None);
+    lifo.push_boxed(Box::new([7u8; 4096]));
+    lifo.push([1u8; 4096]);
+    assert_eq!(*lifo.pop_boxed().unwrap(), [1u8; 4096]);
+    assert_eq!(*lifo.pop_boxed().unwrap(), [7u8; 4096]);
+    assert_eq!(lifo.pop_boxed(), None);
+}
+
+//The replacement for push_boxed and pop_boxed, only the pointer is moved and the allocation is kept.
+#[test]
+pub fn test_boxed_elements() {
+    let lifo = AtomicLifo::<Box<[u8; 4096]>>::new();
+    let boxed = Box::new([7u8; 4096]);
+    let ptr: *const [u8; 4096] = &*boxed;
+    lifo.push(boxed);
+    lifo.push(Box::new([1u8; 4096]));
+    assert_eq!(*lifo.pop().unwrap(), [1u8; 4096]);
+    assert_eq!(lifo.peek_with(|value| core::ptr::eq(&**value, ptr)), Some(true));
+    let popped = lifo.pop().unwrap();
+    assert!(core::ptr::eq(&*popped, ptr));
+    assert_eq!(*popped, [7u8; 4096]);
+    assert_eq!(lifo.pop(), None);
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "capi")]
+use std::ffi::c_void;
+use std::ptr::null_mut;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::{atomic_lifo_free, atomic_lifo_new, atomic_lifo_pop, atomic_lifo_push};
+
+const HEADER: &str = include_str!("../include/atomic_lifo.h");
+const SOURCE: &str = include_str!("../src/capi.rs");
+
+static DESTROYED: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn destroy(value: *mut c_void) {
+    //Safe, every payload of the test is a leaked box.
+    drop(unsafe { Box::from_raw(value.cast::<u64>()) });
+    DESTROYED.fetch_add(1, SeqCst);
+}
+
+fn payload(value: u64) -> *mut c_void {
+    Box::into_raw(Box::new(value)).cast()
+}
+
+#[test]
+pub fn test_capi() {
+    unsafe {
+        let handle = atomic_lifo_new();
+        assert!(atomic_lifo_pop(handle).is_null());
+
+        for i in 0..5 {
+            atomic_lifo_push(handle, payload(i));
+        }
+        let top = atomic_lifo_pop(handle);
+        assert_eq!(*top.cast::<u64>(), 4);
+        destroy(top);
+
+        //The 4 remaining payloads are passed to the destructor.
+        let before = DESTROYED.load(SeqCst);
+        atomic_lifo_free(handle, Some(destroy));
+        assert_eq!(DESTROYED.load(SeqCst) - before, 4);
+
+        let handle = atomic_lifo_new();
+        atomic_lifo_push(handle, null_mut());
+        atomic_lifo_free(handle, None);
+        atomic_lifo_free(null_mut(), None);
+    }
+}
+
+#[test]
+pub fn test_capi_mt() {
+    const ITERATIONS: u64 = if cfg!(miri) { 100 } else { 10_000 };
+
+    struct Handle(*mut atomic_lifo::AtomicLifoHandle);
+    unsafe impl Sync for Handle {}
+
+    let handle = Handle(atomic_lifo_new());
+    let popped = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| unsafe {
+                //Borrows the entire wrapper, a closure would capture only the pointer, which is not Sync.
+                let handle = &handle;
+                for i in 0..ITERATIONS {
+                    atomic_lifo_push(handle.0, payload(i));
+                    let value = atomic_lifo_pop(handle.0);
+                    if !value.is_null() {
+                        drop(Box::from_raw(value.cast::<u64>()));
+                        popped.fetch_add(1, SeqCst);
+                    }
+                }
+            });
+        }
+    });
+
+    let before = DESTROYED.load(SeqCst);
+    unsafe { atomic_lifo_free(handle.0, Some(destroy)) };
+    let left = DESTROYED.load(SeqCst) - before;
+    assert_eq!(popped.load(SeqCst) + left, 2 * ITERATIONS as usize);
+}
+
+//Every exported fn must be declared in the header with the same name, and the header must not declare fns that do not exist.
+#[test]
+pub fn test_capi_header() {
+    let exported: Vec<&str> = SOURCE
+        .lines()
+        .filter(|line| line.starts_with("pub extern \"C\" fn ") || line.starts_with("pub unsafe extern \"C\" fn "))
+        .map(|line| line.split("fn ").nth(1).unwrap().split('(').next().unwrap())
+        .collect();
+    assert_eq!(exported, ["atomic_lifo_new", "atomic_lifo_push", "atomic_lifo_pop", "atomic_lifo_free"]);
+
+    let declared: Vec<&str> = HEADER
+        .lines()
+        .filter(|line| !line.starts_with("/*") && line.ends_with(");"))
+        .map(|line| line.split('(').next().unwrap().rsplit([' ', '*']).next().unwrap())
+        .collect();
+    assert_eq!(declared, exported);
+
+    assert!(HEADER.contains("typedef struct AtomicLifoHandle AtomicLifoHandle;"));
+    assert!(HEADER.contains("AtomicLifoHandle *atomic_lifo_new(void);"));
+    assert!(HEADER.contains("void atomic_lifo_push(const AtomicLifoHandle *handle, void *value);"));
+    assert!(HEADER.contains("void *atomic_lifo_pop(const AtomicLifoHandle *handle);"));
+    assert!(HEADER.contains("void atomic_lifo_free(AtomicLifoHandle *handle, void (*dtor)(void *));"));
+}
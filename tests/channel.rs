@@ -0,0 +1,71 @@
+use std::collections::HashSet;
+use std::thread;
+use atomic_lifo::{channel, TryRecvError};
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 100 } else { 25_000 };
+
+#[test]
+pub fn test_channel() {
+    let (sender, receiver) = channel::<String>();
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    sender.push(String::from("test1"));
+    sender.extend(["test2", "test3"].map(String::from));
+    assert_eq!(receiver.pop().unwrap(), String::from("test3"));
+    let sender2 = sender.clone();
+    drop(sender);
+    assert!(!receiver.is_disconnected());
+    sender2.push(String::from("test4"));
+    drop(sender2);
+    assert!(receiver.is_disconnected());
+    assert_eq!(receiver.try_recv(), Ok(String::from("test4")));
+    let receiver2 = receiver.clone();
+    assert_eq!(receiver2.drain().collect::<Vec<_>>(), ["test2", "test1"].map(String::from).to_vec());
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(receiver.pop(), None);
+}
+
+#[test]
+pub fn test_channel_mt() {
+    let (sender, receiver) = channel::<u32>();
+    let mut senders = Vec::new();
+    for t in 0..4u32 {
+        let sender = sender.clone();
+        senders.push(thread::spawn(move || {
+            for i in 0..ITERATIONS {
+                sender.push(t * ITERATIONS + i);
+            }
+        }));
+    }
+
+    drop(sender);
+
+    let mut receivers = Vec::new();
+    for _ in 0..2 {
+        let receiver = receiver.clone();
+        receivers.push(thread::spawn(move || {
+            let mut received = Vec::new();
+            loop {
+                match receiver.try_recv() {
+                    Ok(value) => received.push(value),
+                    Err(TryRecvError::Empty) => thread::yield_now(),
+                    Err(TryRecvError::Disconnected) => return received,
+                }
+            }
+        }));
+    }
+
+    for jh in senders {
+        jh.join().unwrap();
+    }
+
+    let mut all = HashSet::new();
+    for jh in receivers {
+        for value in jh.join().unwrap() {
+            assert!(all.insert(value));
+        }
+    }
+
+    assert_eq!(all.len(), 4 * ITERATIONS as usize);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+}
@@ -0,0 +1,47 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 5000 };
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct DropCounter;
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        DROP_COUNT.fetch_add(1, SeqCst);
+    }
+}
+
+static MT_LIFO: AtomicLifo<DropCounter> = AtomicLifo::new();
+
+#[test]
+pub fn test_clear() {
+    for _ in 0..ITERATIONS {
+        MT_LIFO.push(DropCounter);
+    }
+
+    let pusher = thread::spawn(|| {
+        for _ in 0..ITERATIONS {
+            MT_LIFO.push(DropCounter);
+        }
+    });
+
+    let clearer = thread::spawn(|| {
+        for _ in 0..100 {
+            MT_LIFO.clear();
+            thread::yield_now();
+        }
+    });
+
+    pusher.join().unwrap();
+    clearer.join().unwrap();
+    MT_LIFO.clear();
+
+    assert!(MT_LIFO.is_empty());
+    assert_eq!(DROP_COUNT.load(SeqCst), 2 * ITERATIONS);
+}
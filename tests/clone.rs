@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 500 } else { 50_000 };
+
+#[test]
+pub fn test_clone() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.extend(["test1", "test2", "test3"].map(String::from));
+    let clone = lifo.clone();
+    assert_eq!(lifo.pop().unwrap(), String::from("test3"));
+    assert_eq!(clone.into_vec(), ["test3", "test2", "test1"].map(String::from).to_vec());
+    assert_eq!(lifo.into_vec(), ["test2", "test1"].map(String::from).to_vec());
+    assert!(AtomicLifo::<String>::new().clone().is_empty());
+}
+
+static MT_LIFO: AtomicLifo<String> = AtomicLifo::new();
+
+#[test]
+pub fn test_clone_mt() {
+    MT_LIFO.extend((0..ITERATIONS / 10).map(|i| i.to_string()));
+    let pusher = thread::spawn(|| {
+        for i in ITERATIONS / 10..ITERATIONS {
+            MT_LIFO.push(i.to_string());
+        }
+    });
+
+    let popper = thread::spawn(|| {
+        for _ in 0..ITERATIONS / 5 {
+            _ = MT_LIFO.pop();
+        }
+    });
+
+    for _ in 0..20 {
+        let clone = MT_LIFO.clone();
+        let mut seen = HashSet::new();
+        let mut last = u32::MAX;
+        for value in clone.into_vec() {
+            let value: u32 = value.parse().unwrap();
+            assert!(seen.insert(value));
+            assert!(value < last);
+            last = value;
+        }
+    }
+
+    pusher.join().unwrap();
+    popper.join().unwrap();
+    MT_LIFO.clear();
+}
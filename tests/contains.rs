@@ -0,0 +1,74 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_contains() {
+    let lifo = AtomicLifo::<String>::new();
+    assert!(!lifo.contains(&String::from("test1")));
+    lifo.extend(["test1", "test2", "test3"].map(String::from));
+    assert!(lifo.contains(&String::from("test1")));
+    assert!(lifo.contains(&String::from("test2")));
+    assert!(lifo.contains(&String::from("test3")));
+    assert!(!lifo.contains(&String::from("test4")));
+    assert_eq!(lifo.pop().unwrap(), String::from("test3"));
+    assert!(!lifo.contains(&String::from("test3")));
+}
+
+static MT_LIFO: AtomicLifo<String> = AtomicLifo::new();
+
+#[test]
+pub fn test_contains_mt() {
+    MT_LIFO.push(String::from("bottom"));
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || loop {
+            if stop_clone.load(SeqCst) {
+                return;
+            }
+            MT_LIFO.push(String::from("123456"));
+            thread::yield_now();
+        }));
+    }
+
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || loop {
+            if stop_clone.load(SeqCst) {
+                return;
+            }
+
+            if let Some(data) = MT_LIFO.pop_if(|v| v.as_str() == "123456") {
+                assert_eq!(data.as_str(), "123456");
+            }
+        }));
+    }
+
+    let stop_clone = Arc::clone(&stop);
+    jh.push(thread::spawn(move || loop {
+        if stop_clone.load(SeqCst) {
+            return;
+        }
+
+        assert!(MT_LIFO.contains(&String::from("bottom")));
+        assert!(!MT_LIFO.contains(&String::from("other")));
+    }));
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    assert!(MT_LIFO.contains(&String::from("bottom")));
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "count")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_len() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.len(), 0);
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    assert_eq!(lifo.len(), 2);
+    assert_eq!(lifo.pop().unwrap(), String::from("test2"));
+    assert_eq!(lifo.len(), 1);
+    assert_eq!(lifo.pop().unwrap(), String::from("test1"));
+    assert_eq!(lifo.len(), 0);
+    assert_eq!(lifo.pop(), None);
+    assert_eq!(lifo.len(), 0);
+}
+
+#[test]
+pub fn test_len_take_all() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.extend(0..10);
+    let mut chain = lifo.take_all();
+    //The removed elements are no longer counted, whether the chain yielded them or not.
+    assert_eq!(lifo.len(), 0);
+    assert_eq!(chain.next(), Some(9));
+    lifo.push(10);
+    assert_eq!(lifo.len(), 1);
+    drop(chain);
+    assert_eq!(lifo.len(), 1);
+    lifo.clear();
+    assert_eq!(lifo.len(), 0);
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_len_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..4 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || loop {
+            if stop_clone.load(SeqCst) {
+                return;
+            }
+
+            _ = MT_LIFO.pop();
+            //Would be a gigantic number after an underflow.
+            assert!(MT_LIFO.len() < usize::MAX / 2);
+        }));
+    }
+
+    let stop_clone = Arc::clone(&stop);
+    let pusher = thread::spawn(move || {
+        let mut pushed = 0usize;
+        loop {
+            if stop_clone.load(SeqCst) {
+                return pushed;
+            }
+            MT_LIFO.push(123456);
+            pushed += 1;
+        }
+    });
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    let pushed = pusher.join().unwrap();
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    let mut remaining = 0usize;
+    while MT_LIFO.pop().is_some() {
+        remaining += 1;
+    }
+
+    assert!(remaining <= pushed);
+    assert_eq!(MT_LIFO.len(), 0);
+}
+
+#[test]
+pub fn test_approximate_heap_bytes() {
+    let lifo = AtomicLifo::<u64>::new();
+    assert_eq!(lifo.approximate_heap_bytes(), 0);
+
+    lifo.extend(0..10);
+    let bytes = lifo.approximate_heap_bytes();
+    assert_eq!(bytes % 10, 0);
+    let node = bytes / 10;
+    assert!(node > size_of::<u64>());
+
+    //Popped nodes are kept for reuse.
+    while lifo.pop().is_some() {}
+    assert_eq!(lifo.approximate_heap_bytes(), bytes);
+
+    lifo.quiesce();
+    assert_eq!(lifo.approximate_heap_bytes(), 0);
+
+    //Also reserves the bookkeeping of popping the nodes.
+    lifo.reserve(4);
+    assert!(lifo.approximate_heap_bytes() > 4 * node);
+
+    //Only the inline size of a value is counted.
+    let strings = AtomicLifo::new();
+    strings.push(String::from_utf8(vec![b'a'; 4096]).unwrap());
+    assert!(strings.approximate_heap_bytes() < 4096);
+
+    //Retired nodes are counted until they are freed.
+    let leaky = AtomicLifo::new_leaky();
+    leaky.extend(0..10u64);
+    let leaky_bytes = leaky.approximate_heap_bytes();
+    while leaky.pop().is_some() {}
+    assert_eq!(leaky.approximate_heap_bytes(), leaky_bytes);
+
+    let zst = AtomicLifo::new();
+    zst.extend([(); 10]);
+    assert_eq!(zst.approximate_heap_bytes(), 0);
+}
+
+#[test]
+pub fn test_max_len_seen() {
+    let lifo = AtomicLifo::new();
+    assert_eq!(lifo.max_len_seen(), 0);
+    for i in 0..1000u32 {
+        lifo.push(i);
+    }
+    for _ in 0..900 {
+        lifo.pop();
+    }
+    lifo.extend(0..50);
+    assert_eq!(lifo.max_len_seen(), 1000);
+
+    lifo.reset_max_len();
+    assert_eq!(lifo.max_len_seen(), 150);
+    lifo.push(0);
+    assert_eq!(lifo.max_len_seen(), 151);
+    lifo.clear();
+    assert_eq!(lifo.max_len_seen(), 151);
+
+    assert_eq!(AtomicLifo::from(vec![1, 2, 3]).max_len_seen(), 3);
+
+    let zst = AtomicLifo::new();
+    zst.extend([(); 10]);
+    zst.pop();
+    assert_eq!(zst.max_len_seen(), 10);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "critical-section")]
+use atomic_lifo::CriticalSectionLifo;
+use std::collections::HashSet;
+use std::thread;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 100 } else { 10_000 };
+
+//The std implementation of critical-section is a global reentrant mutex, the threads stand in for interrupt handlers.
+static CS_LIFO: CriticalSectionLifo<u32> = CriticalSectionLifo::new();
+
+#[test]
+pub fn test_critical_section() {
+    let lifo = CriticalSectionLifo::new();
+    assert_eq!(lifo.pop(), None);
+    assert!(lifo.is_empty());
+    lifo.push(String::from("test1"));
+    lifo.try_push(String::from("test2")).unwrap();
+    assert_eq!(lifo.len(), 2);
+    assert_eq!(lifo.peek_with(String::clone), Some(String::from("test2")));
+    assert_eq!(lifo.pop(), Some(String::from("test2")));
+    assert_eq!(lifo.len(), 1);
+    lifo.push(String::from("test3"));
+    assert!(format!("{lifo:?}").contains("len: 2"));
+}
+
+#[test]
+pub fn test_critical_section_mt() {
+    let producers: Vec<_> = (0..4u32)
+        .map(|t| {
+            thread::spawn(move || {
+                for i in 0..ITERATIONS {
+                    CS_LIFO.push(t * ITERATIONS + i);
+                }
+            })
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    while seen.len() < 4 * ITERATIONS as usize {
+        if let Some(value) = CS_LIFO.pop() {
+            assert!(seen.insert(value));
+        }
+    }
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+
+    assert!(CS_LIFO.is_empty());
+    assert_eq!(CS_LIFO.len(), 0);
+}
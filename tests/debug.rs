@@ -0,0 +1,70 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 20 } else { 10_000 };
+
+#[test]
+pub fn test_debug() {
+    let lifo = AtomicLifo::<u32>::new();
+    assert_eq!(format!("{lifo:?}"), "AtomicLifo { len: ~0, items: [] }");
+    lifo.extend([1, 2, 3]);
+    assert_eq!(format!("{lifo:?}"), "AtomicLifo { len: ~3, items: [3, 2, 1] }");
+}
+
+#[test]
+pub fn test_debug_truncated() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.extend(0..100);
+    let expected = (68..100u32).rev().map(|i| i.to_string()).collect::<Vec<_>>().join(", ");
+    assert_eq!(format!("{lifo:?}"), format!("AtomicLifo {{ len: ~100, items: [{expected}, ..] }}"));
+}
+
+static MT_LIFO: AtomicLifo<String> = AtomicLifo::new();
+
+#[test]
+pub fn test_debug_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || {
+            while !stop_clone.load(SeqCst) {
+                MT_LIFO.push(String::from("123456"));
+                _ = MT_LIFO.pop();
+                _ = MT_LIFO.pop();
+            }
+        }));
+    }
+
+    for _ in 0..ITERATIONS {
+        let debug = format!("{MT_LIFO:?}");
+        assert!(debug.starts_with("AtomicLifo { len: ~"));
+        let items = debug.split_once("items: [").unwrap().1.strip_suffix("] }").unwrap();
+        for item in items.split(", ").filter(|item| !item.is_empty()) {
+            assert_eq!(item, "\"123456\"");
+        }
+    }
+
+    stop.store(true, SeqCst);
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    MT_LIFO.clear();
+}
+
+#[test]
+pub fn test_debug_len() {
+    struct NotDebug(#[allow(dead_code)] u32);
+
+    let lifo = AtomicLifo::new();
+    assert_eq!(format!("{:?}", lifo.debug_len()), "AtomicLifo { len: ~0, .. }");
+    lifo.extend([1, 2, 3].map(NotDebug));
+    assert_eq!(format!("{:?}", lifo.debug_len()), "AtomicLifo { len: ~3, .. }");
+    _ = lifo.pop();
+    assert_eq!(format!("{:?}", lifo.debug_len()), "AtomicLifo { len: ~2, .. }");
+}
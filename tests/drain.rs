@@ -0,0 +1,100 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_drain() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.drain().next(), None);
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    lifo.push(String::from("test3"));
+    let all: Vec<String> = lifo.drain().collect();
+    assert_eq!(all, vec![String::from("test3"), String::from("test2"), String::from("test1")]);
+    assert_eq!(lifo.pop(), None);
+}
+
+#[test]
+pub fn test_drain_fused() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.push(1);
+    {
+        let mut drain = lifo.drain();
+        assert_eq!(drain.next(), Some(1));
+        assert_eq!(drain.next(), None);
+        lifo.push(2);
+        assert_eq!(drain.next(), None);
+    }
+    assert_eq!(lifo.pop(), Some(2));
+}
+
+#[test]
+pub fn test_drain_drop_early() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    lifo.push(String::from("test3"));
+    {
+        let mut drain = lifo.drain();
+        assert_eq!(drain.next().unwrap(), String::from("test3"));
+    }
+    assert_eq!(lifo.pop().unwrap(), String::from("test2"));
+    assert_eq!(lifo.pop().unwrap(), String::from("test1"));
+    assert_eq!(lifo.pop(), None);
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_drain_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || {
+            let mut pushed = 0u64;
+            loop {
+                if stop_clone.load(SeqCst) {
+                    return pushed;
+                }
+                MT_LIFO.push(123456);
+                pushed += 1;
+                thread::yield_now();
+            }
+        }));
+    }
+
+    let stop_clone = Arc::clone(&stop);
+    let drainer = thread::spawn(move || {
+        let mut drained = 0u64;
+        loop {
+            if stop_clone.load(SeqCst) {
+                return drained;
+            }
+
+            for data in MT_LIFO.drain() {
+                assert_eq!(data, 123456);
+                drained += 1;
+            }
+        }
+    });
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    let mut pushed = 0;
+    for jh in jh {
+        pushed += jh.join().unwrap();
+    }
+
+    let drained = drainer.join().unwrap();
+    let remaining = MT_LIFO.drain().count() as u64;
+    assert_eq!(pushed, drained + remaining);
+}
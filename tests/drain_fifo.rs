@@ -0,0 +1,44 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_drain_fifo() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.drain_fifo().next(), None);
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    lifo.push(String::from("test3"));
+    let all: Vec<String> = lifo.drain_fifo().collect();
+    assert_eq!(all, ["test1", "test2", "test3"].map(String::from).to_vec());
+    assert!(lifo.is_empty());
+}
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct DropCounter(u32);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        DROP_COUNT.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_drain_fifo_partial() {
+    let lifo = AtomicLifo::<DropCounter>::new();
+    for i in 0..10 {
+        lifo.push(DropCounter(i));
+    }
+
+    let mut drain = lifo.drain_fifo();
+    assert_eq!(drain.next().unwrap().0, 0);
+    assert_eq!(drain.next().unwrap().0, 1);
+    assert_eq!(DROP_COUNT.load(SeqCst), 2);
+    lifo.push(DropCounter(10));
+    drop(drain);
+    assert_eq!(DROP_COUNT.load(SeqCst), 10);
+    assert_eq!(lifo.pop().unwrap().0, 10);
+    assert_eq!(DROP_COUNT.load(SeqCst), 11);
+}
@@ -0,0 +1,106 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::mpsc::{channel, Sender};
+use atomic_lifo::AtomicLifo;
+
+struct Request {
+    id: u32,
+    reply: Sender<Result<u32, u32>>,
+}
+
+fn fail(request: Request) {
+    request.reply.send(Err(request.id)).unwrap();
+}
+
+#[test]
+pub fn test_drop_handler() {
+    let (sender, receiver) = channel();
+    let mut lifo = AtomicLifo::new();
+    lifo.set_drop_handler(fail);
+    for id in 0..10 {
+        lifo.push(Request { id, reply: sender.clone() });
+    }
+    drop(sender);
+
+    for _ in 0..3 {
+        let request = lifo.pop().unwrap();
+        request.reply.send(Ok(request.id)).unwrap();
+    }
+    drop(lifo);
+
+    //Every unpopped request is failed exactly once, in the order pop would have returned them.
+    let replies: Vec<_> = receiver.iter().collect();
+    let mut expected: Vec<_> = (7..10).rev().map(Ok).collect();
+    expected.extend((0..7).rev().map(Err));
+    assert_eq!(replies, expected);
+}
+
+#[test]
+pub fn test_drop_handler_moved_out() {
+    let (sender, receiver) = channel();
+    let mut lifo = AtomicLifo::new();
+    lifo.set_drop_handler(fail);
+    lifo.push(Request { id: 1, reply: sender.clone() });
+    lifo.push(Request { id: 2, reply: sender.clone() });
+
+    assert_eq!(lifo.into_iter().count(), 2);
+    drop(sender);
+    assert!(receiver.iter().next().is_none());
+}
+
+#[test]
+pub fn test_drop_handler_clone() {
+    static FAILED: AtomicUsize = AtomicUsize::new(0);
+
+    let mut lifo = AtomicLifo::new();
+    lifo.set_drop_handler(|_: u32| {
+        FAILED.fetch_add(1, SeqCst);
+    });
+    lifo.extend([1, 2, 3]);
+    let clone = lifo.clone();
+    drop(lifo);
+    assert_eq!(FAILED.load(SeqCst), 3);
+    drop(clone);
+    assert_eq!(FAILED.load(SeqCst), 6);
+}
+
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct Element(u32);
+
+impl Drop for Element {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_drop_handler_panic() {
+    let mut lifo = AtomicLifo::new();
+    lifo.set_drop_handler(|element: Element| {
+        HANDLED.fetch_add(1, SeqCst);
+        assert_ne!(element.0, 5, "element 5 cannot be handled");
+    });
+    lifo.extend((0..10).map(Element));
+
+    //9 to 5 reach the handler, 4 to 0 are only dropped, none is leaked.
+    assert!(catch_unwind(AssertUnwindSafe(|| drop(lifo))).is_err());
+    assert_eq!(HANDLED.load(SeqCst), 5);
+    assert_eq!(DROPPED.load(SeqCst), 10);
+}
+
+#[test]
+pub fn test_drop_handler_zst() {
+    static UNITS: AtomicUsize = AtomicUsize::new(0);
+
+    let mut lifo = AtomicLifo::new();
+    lifo.set_drop_handler(|()| {
+        UNITS.fetch_add(1, SeqCst);
+    });
+    lifo.extend([(); 4]);
+    lifo.pop();
+    drop(lifo);
+    assert_eq!(UNITS.load(SeqCst), 3);
+}
@@ -0,0 +1,68 @@
+use atomic_lifo::AtomicLifo;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+
+const THREADS: u64 = 4;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u64 = if cfg!(miri) { 200 } else { 20_000 };
+
+//Runs with and without the dwcas feature, popped nodes are only recycled with it.
+#[test]
+pub fn test_mixed_stress() {
+    let lifo = AtomicLifo::new();
+    let popped = AtomicU64::new(0);
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let lifo = &lifo;
+            let popped = &popped;
+            scope.spawn(move || {
+                for i in 0..ITERATIONS {
+                    let value = t * ITERATIONS + i;
+                    match i % 4 {
+                        0 => lifo.extend([value]),
+                        _ => lifo.push(value),
+                    }
+
+                    let removed = match i % 8 {
+                        0 => lifo.pop_n(2).into_iter().sum(),
+                        1 => lifo.pop_if(|_| true).unwrap_or(0),
+                        _ => lifo.pop().unwrap_or(0),
+                    };
+                    popped.fetch_add(removed, SeqCst);
+                }
+            });
+        }
+
+        //Reads values while the other threads pop, nodes popped in the meantime must not be recycled.
+        let lifo = &lifo;
+        scope.spawn(move || {
+            for _ in 0..ITERATIONS / 10 {
+                lifo.peek_with(|top| assert!(*top < THREADS * ITERATIONS));
+                let clone = lifo.clone();
+                assert!(clone.into_iter().all(|value| value < THREADS * ITERATIONS));
+                assert!(!lifo.contains(&u64::MAX));
+            }
+        });
+    });
+
+    let drained: u64 = lifo.drain().sum();
+    let pushed: u64 = (0..THREADS * ITERATIONS).sum();
+    assert_eq!(popped.load(SeqCst) + drained, pushed);
+
+    lifo.quiesce();
+    assert_eq!(lifo.free_node_count(), 0);
+}
+
+#[test]
+pub fn test_reuse_after_pop() {
+    let lifo = AtomicLifo::new();
+    for i in 0..1000u64 {
+        lifo.push(i);
+        lifo.push(i + 1);
+        assert_eq!(lifo.pop(), Some(i + 1));
+        assert_eq!(lifo.pop(), Some(i));
+        //Both nodes can be reused by the next pushes, recycled with the dwcas feature or freed without it.
+        assert_eq!(lifo.free_node_count(), 2);
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+const THREADS: usize = 8;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 20_000 };
+
+#[test]
+pub fn test_elimination_order() {
+    let lifo = AtomicLifo::new_with_elimination::<4>();
+    assert_eq!(lifo.pop(), None);
+    lifo.push(1);
+    lifo.push(2);
+    lifo.extend([3, 4]);
+    assert_eq!(lifo.pop(), Some(4));
+    assert_eq!(lifo.pop(), Some(3));
+    assert_eq!(lifo.clone().into_vec(), [2, 1]);
+    assert!(lifo.drain().eq([2, 1]));
+
+    let lifo = AtomicLifo::new_with_elimination::<0>();
+    lifo.push(String::from("a"));
+    assert_eq!(lifo.pop().unwrap(), "a");
+}
+
+#[test]
+pub fn test_elimination_mt() {
+    let lifo = AtomicLifo::new_with_elimination::<2>();
+    let popped: Vec<AtomicBool> = (0..THREADS * ITERATIONS).map(|_| AtomicBool::new(false)).collect();
+
+    //Every thread pushes and pops in turns, so pushes and pops keep colliding on the head.
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let lifo = &lifo;
+            let popped = &popped;
+            scope.spawn(move || {
+                for i in 0..ITERATIONS {
+                    lifo.push(Box::new(t * ITERATIONS + i));
+                    let value = *lifo.pop().unwrap();
+                    assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+                }
+            });
+        }
+    });
+
+    assert!(popped.iter().all(|popped| popped.load(SeqCst)));
+    assert!(lifo.is_empty());
+    #[cfg(feature = "count")]
+    assert_eq!(lifo.len(), 0);
+    #[cfg(feature = "stats")]
+    {
+        let stats = lifo.stats();
+        assert_eq!(stats.pushes, (THREADS * ITERATIONS) as u64);
+        assert_eq!(stats.pops, stats.pushes);
+        assert!(stats.eliminations <= stats.pops, "{stats:?}");
+    }
+    #[cfg(feature = "debug-validate")]
+    {
+        let mut lifo = lifo;
+        lifo.assert_invariants();
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+const THREADS: usize = 4;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 100_000 };
+
+#[test]
+pub fn test_exchange() {
+    let lifo = AtomicLifo::new();
+    assert_eq!(lifo.pop_waiting(), None);
+    //Nobody is waiting, the values are pushed.
+    assert!(!lifo.push_or_exchange(String::from("a")));
+    assert!(!lifo.push_or_exchange(String::from("b")));
+    assert_eq!(lifo.pop_waiting().unwrap(), "b");
+    assert_eq!(lifo.into_vec(), ["a"]);
+
+    let lifo = AtomicLifo::new();
+    assert!(!lifo.push_or_exchange(()));
+    assert_eq!(lifo.pop_waiting(), Some(()));
+    assert_eq!(lifo.pop_waiting(), None);
+}
+
+#[test]
+pub fn test_exchange_ping_pong() {
+    let lifo = AtomicLifo::new();
+    let done = AtomicBool::new(false);
+    let exchanged = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for i in 0..ITERATIONS {
+                if lifo.push_or_exchange(i) {
+                    exchanged.fetch_add(1, SeqCst);
+                }
+
+                //Waits for the consumer, so the lifo keeps oscillating around empty.
+                while !lifo.is_empty() {
+                    thread::yield_now();
+                }
+            }
+
+            done.store(true, SeqCst);
+        });
+
+        let mut expected = 0;
+        while expected < ITERATIONS {
+            match lifo.pop_waiting() {
+                Some(value) => {
+                    assert_eq!(value, expected);
+                    expected += 1;
+                }
+                None => {
+                    assert!(!done.load(SeqCst) || !lifo.is_empty());
+                    //Lets the producer run if there are fewer cores than threads.
+                    thread::yield_now();
+                }
+            }
+        }
+    });
+
+    assert!(lifo.is_empty());
+    #[cfg(feature = "stats")]
+    {
+        let stats = lifo.stats();
+        assert_eq!(stats.pushes, ITERATIONS as u64);
+        assert_eq!(stats.pops, stats.pushes);
+        assert_eq!(stats.exchanges, exchanged.load(SeqCst) as u64);
+    }
+}
+
+#[test]
+pub fn test_exchange_mt() {
+    let lifo = AtomicLifo::new();
+    let popped: Vec<AtomicBool> = (0..THREADS * ITERATIONS).map(|_| AtomicBool::new(false)).collect();
+    let exchanged = AtomicUsize::new(0);
+    let remaining = AtomicUsize::new(THREADS * ITERATIONS);
+
+    //Several waiters compete for the slot and give up while the pushers hand over their values.
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let lifo = &lifo;
+            let exchanged = &exchanged;
+            scope.spawn(move || {
+                for i in 0..ITERATIONS {
+                    if lifo.push_or_exchange(t * ITERATIONS + i) {
+                        exchanged.fetch_add(1, SeqCst);
+                    }
+
+                    if i % 64 == 0 {
+                        thread::yield_now();
+                    }
+                }
+            });
+
+            scope.spawn(|| {
+                while remaining.load(SeqCst) != 0 {
+                    if let Some(value) = lifo.pop_waiting() {
+                        assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+                        remaining.fetch_sub(1, SeqCst);
+                    } else {
+                        thread::yield_now();
+                    }
+                }
+            });
+        }
+    });
+
+    assert!(popped.iter().all(|popped| popped.load(SeqCst)), "a value was lost");
+    assert!(lifo.is_empty());
+    #[cfg(feature = "count")]
+    assert_eq!(lifo.len(), 0);
+    #[cfg(feature = "stats")]
+    assert_eq!(lifo.stats().exchanges, exchanged.load(SeqCst) as u64);
+    #[cfg(feature = "debug-validate")]
+    {
+        let mut lifo = lifo;
+        lifo.assert_invariants();
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_extend() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.push(1);
+    lifo.extend(vec![2, 3, 4]);
+    assert_eq!(lifo.pop(), Some(4));
+    assert_eq!(lifo.pop(), Some(3));
+    assert_eq!(lifo.pop(), Some(2));
+    assert_eq!(lifo.pop(), Some(1));
+    assert_eq!(lifo.pop(), None);
+}
+
+#[test]
+pub fn test_extend_empty() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.extend(Vec::new());
+    assert!(lifo.is_empty());
+    lifo.push(1);
+    lifo.extend(Vec::new());
+    assert_eq!(lifo.pop(), Some(1));
+    assert_eq!(lifo.pop(), None);
+}
+
+#[test]
+pub fn test_extend_trait() {
+    let lifo = AtomicLifo::<String>::new();
+    let mut lifo_ref = &lifo;
+    Extend::extend(&mut lifo_ref, ["test1", "test2"].map(String::from));
+    assert_eq!(lifo.pop().unwrap(), String::from("test2"));
+    assert_eq!(lifo.pop().unwrap(), String::from("test1"));
+    assert_eq!(lifo.pop(), None);
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_extend_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || {
+            let mut count = 0u64;
+            loop {
+                if stop_clone.load(SeqCst) {
+                    return count;
+                }
+                MT_LIFO.extend([123456; 16]);
+                count += 16;
+                thread::yield_now();
+            }
+        }));
+    }
+
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || {
+            let mut count = 0u64;
+            loop {
+                if stop_clone.load(SeqCst) {
+                    return count;
+                }
+
+                if let Some(data) = MT_LIFO.pop() {
+                    assert_eq!(data, 123456);
+                    count += 1;
+                }
+            }
+        }));
+    }
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    let mut results = Vec::new();
+    for jh in jh {
+        results.push(jh.join().unwrap());
+    }
+
+    let remaining = MT_LIFO.take_all().count() as u64;
+    assert_eq!(results[0] + results[1], results[2] + results[3] + remaining);
+}
+
+#[test]
+pub fn test_from_iter() {
+    let lifo: AtomicLifo<u32> = vec![1, 2, 3].into_iter().collect();
+    assert_eq!(lifo.pop(), Some(3));
+    assert_eq!(lifo.pop(), Some(2));
+    assert_eq!(lifo.pop(), Some(1));
+    assert_eq!(lifo.pop(), None);
+
+    let lifo: AtomicLifo<String> = std::iter::empty().collect();
+    assert!(lifo.is_empty());
+    lifo.push(String::from("test1"));
+    assert_eq!(lifo.pop().unwrap(), String::from("test1"));
+}
+
+#[test]
+pub fn test_extend_from_slice() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.push(String::from("below"));
+    let items = ["a", "b", "c"].map(String::from);
+    lifo.extend_from_slice(&items);
+    assert_eq!(items, ["a", "b", "c"]);
+
+    //The last element of the slice is popped first, the previous top ends up below the slice.
+    assert_eq!(lifo.pop().unwrap(), "c");
+    assert_eq!(lifo.pop().unwrap(), "b");
+    assert_eq!(lifo.pop().unwrap(), "a");
+    assert_eq!(lifo.pop().unwrap(), "below");
+    assert_eq!(lifo.pop(), None);
+
+    let copied = AtomicLifo::<u64>::new();
+    copied.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(copied.pop_n(3), [3, 2, 1]);
+
+    let zst = AtomicLifo::new();
+    zst.extend_from_slice(&[(); 4]);
+    assert_eq!(zst.drain().count(), 4);
+}
+
+#[test]
+pub fn test_extend_from_slice_empty() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.extend_from_slice(&[]);
+    assert!(lifo.is_empty());
+    assert_eq!(lifo.free_node_count(), 0);
+    #[cfg(feature = "stats")]
+    assert_eq!(lifo.stats(), atomic_lifo::LifoStats::default());
+
+    lifo.push(1);
+    lifo.extend_from_slice(&[]);
+    assert_eq!(lifo.pop(), Some(1));
+    assert_eq!(lifo.pop(), None);
+}
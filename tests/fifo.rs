@@ -0,0 +1,185 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use std::sync::atomic::AtomicUsize;
+use atomic_lifo::{AtomicFifo, Global, LeakReclaim, LifoHooks};
+
+#[test]
+pub fn test_fifo() {
+    let fifo = AtomicFifo::<String>::new();
+    assert_eq!(fifo.pop(), None);
+    fifo.push(String::from("test1"));
+    fifo.push(String::from("test2"));
+    fifo.push(String::from("test3"));
+    assert_eq!(fifo.pop().unwrap(), String::from("test1"));
+    assert_eq!(fifo.pop().unwrap(), String::from("test2"));
+    fifo.push(String::from("test4"));
+    assert_eq!(fifo.pop().unwrap(), String::from("test3"));
+    assert_eq!(fifo.pop().unwrap(), String::from("test4"));
+    assert_eq!(fifo.pop(), None);
+    assert_eq!(fifo.pop(), None);
+}
+
+#[test]
+pub fn test_fifo_drop_with_elements() {
+    let fifo = AtomicFifo::<String>::new();
+    drop(AtomicFifo::<String>::new());
+    fifo.push(String::from("test1"));
+    fifo.push(String::from("test2"));
+    fifo.push(String::from("test3"));
+    assert_eq!(fifo.pop().unwrap(), String::from("test1"));
+    drop(fifo);
+}
+
+#[test]
+pub fn test_fifo_is_empty() {
+    let fifo = AtomicFifo::<String>::new();
+    assert!(fifo.is_empty());
+    fifo.push(String::from("test1"));
+    assert!(!fifo.is_empty());
+    fifo.push(String::from("test2"));
+    assert_eq!(fifo.pop().unwrap(), String::from("test1"));
+    assert!(!fifo.is_empty());
+    assert_eq!(fifo.pop().unwrap(), String::from("test2"));
+    assert!(fifo.is_empty());
+}
+
+#[derive(Default)]
+struct Counting {
+    pushed: AtomicUsize,
+    popped: AtomicUsize,
+    empty_pops: AtomicUsize,
+}
+
+impl LifoHooks<usize> for Counting {
+    fn on_push(&self, value: &usize) {
+        self.pushed.fetch_add(*value, SeqCst);
+    }
+
+    fn on_pop(&self, value: &usize) {
+        self.popped.fetch_add(*value, SeqCst);
+    }
+
+    fn on_empty_pop(&self) {
+        self.empty_pops.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_fifo_hooks_leaky() {
+    let fifo = AtomicFifo::with_hooks_in(Counting::default(), LeakReclaim, Global);
+    assert_eq!(fifo.pop(), None);
+    for i in 1..=10 {
+        fifo.push(i);
+    }
+    assert_eq!(fifo.pop(), Some(1));
+    assert_eq!(fifo.pop(), Some(2));
+
+    let hooks = fifo.hooks();
+    assert_eq!(hooks.pushed.load(SeqCst), 55);
+    assert_eq!(hooks.popped.load(SeqCst), 3);
+    assert_eq!(hooks.empty_pops.load(SeqCst), 1);
+}
+
+static MT_FIFO: AtomicFifo<u64> = AtomicFifo::new();
+
+#[test]
+pub fn test_fifo_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..4 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || {
+            let mut last = None;
+            loop {
+                if stop_clone.load(SeqCst) {
+                    return;
+                }
+
+                if let Some(data) = MT_FIFO.pop() {
+                    //Every consumer must observe the elements of the single producer in order.
+                    assert!(last < Some(data));
+                    last = Some(data);
+                }
+            }
+        }));
+    }
+
+    let stop_clone = Arc::clone(&stop);
+    jh.push(thread::spawn(move || {
+        let mut next = 0u64;
+        loop {
+            if stop_clone.load(SeqCst) {
+                return;
+            }
+
+            MT_FIFO.push(next);
+            next += 1;
+            thread::yield_now();
+        }
+    }));
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(15)
+    });
+    stop.store(true, SeqCst);
+    for jh in jh {
+        jh.join().unwrap();
+    }
+}
+
+static MT_FIFO_SINGLE: AtomicFifo<u64> = AtomicFifo::new();
+
+#[test]
+pub fn test_fifo_mt_single_consumer() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let producer = {
+        let stop_clone = Arc::clone(&stop);
+        thread::spawn(move || {
+            let mut next = 0u64;
+            while !stop_clone.load(SeqCst) {
+                MT_FIFO_SINGLE.push(next);
+                next += 1;
+                thread::yield_now();
+            }
+
+            next
+        })
+    };
+
+    let consumer = {
+        let stop_clone = Arc::clone(&stop);
+        thread::spawn(move || {
+            let mut expected = 0u64;
+            while !stop_clone.load(SeqCst) {
+                if let Some(data) = MT_FIFO_SINGLE.pop() {
+                    assert_eq!(data, expected);
+                    expected += 1;
+                }
+            }
+
+            expected
+        })
+    };
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(15)
+    });
+    stop.store(true, SeqCst);
+    let pushed = producer.join().unwrap();
+    let mut expected = consumer.join().unwrap();
+    while let Some(data) = MT_FIFO_SINGLE.pop() {
+        assert_eq!(data, expected);
+        expected += 1;
+    }
+
+    assert_eq!(expected, pushed);
+}
@@ -0,0 +1,79 @@
+use atomic_lifo::AtomicLifo;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+
+struct CountingAllocator;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(1, SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(1, SeqCst);
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 1000 } else { 10_000 };
+
+#[test]
+pub fn test_hazard_limit() {
+    let lifo = AtomicLifo::with_hazard_limit(8);
+    lifo.push(usize::MAX);
+    let live_before = LIVE.load(SeqCst);
+
+    //Nodes popped while we peek cannot be freed, after 8 of them every pop helps freeing the hazard list.
+    lifo.peek_with(|top| {
+        assert_eq!(usize::MAX, *top);
+        for i in 0..ITERATIONS {
+            lifo.push(i);
+            assert_eq!(Some(i), lifo.pop());
+        }
+    });
+    let live_stalled = LIVE.load(SeqCst);
+    assert!(live_stalled - live_before >= ITERATIONS);
+
+    for i in 0..4 {
+        lifo.push(i);
+        assert_eq!(Some(i), lifo.pop());
+    }
+
+    //Only the nodes kept for reuse remain.
+    let live_after = LIVE.load(SeqCst);
+    assert!(
+        live_after - live_before <= 2 * 1024 + 8,
+        "{} allocations were not freed",
+        live_after - live_before
+    );
+    assert_eq!(Some(usize::MAX), lifo.pop());
+    assert_eq!(None, lifo.pop());
+}
+
+//With the std feature threads that wait yield to the scheduler, so the thread they wait for gets to run.
+#[test]
+pub fn test_forward_progress_oversubscribed() {
+    let lifo = AtomicLifo::with_hazard_limit(1);
+    let threads = std::thread::available_parallelism().map_or(1, usize::from) * 4;
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for i in 0..ITERATIONS / 10 {
+                    lifo.push(i);
+                    lifo.peek_with(|top| assert!(*top < ITERATIONS));
+                    assert!(lifo.pop().is_some());
+                }
+            });
+        }
+    });
+
+    assert_eq!(None, lifo.pop());
+}
@@ -0,0 +1,204 @@
+use atomic_lifo::{AtomicLifo, LifoHooks};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::Arc;
+use std::thread;
+
+#[derive(Default)]
+struct Counts {
+    pushes: AtomicUsize,
+    pops: AtomicUsize,
+    empty_pops: AtomicUsize,
+    pushed_sum: AtomicUsize,
+    popped_sum: AtomicUsize,
+}
+
+//Shared by clones, so a lifo and its clones count into the same counters.
+#[derive(Clone, Default)]
+struct Counting(Arc<Counts>);
+
+impl LifoHooks<usize> for Counting {
+    fn on_push(&self, value: &usize) {
+        self.0.pushes.fetch_add(1, Relaxed);
+        self.0.pushed_sum.fetch_add(*value, Relaxed);
+    }
+
+    fn on_pop(&self, value: &usize) {
+        self.0.pops.fetch_add(1, Relaxed);
+        self.0.popped_sum.fetch_add(*value, Relaxed);
+    }
+
+    fn on_empty_pop(&self) {
+        self.0.empty_pops.fetch_add(1, Relaxed);
+    }
+}
+
+impl LifoHooks<()> for Counting {
+    fn on_push(&self, _: &()) {
+        self.0.pushes.fetch_add(1, Relaxed);
+    }
+
+    fn on_pop(&self, _: &()) {
+        self.0.pops.fetch_add(1, Relaxed);
+    }
+
+    fn on_empty_pop(&self) {
+        self.0.empty_pops.fetch_add(1, Relaxed);
+    }
+}
+
+impl Counting {
+    fn get(&self) -> (usize, usize, usize) {
+        (
+            self.0.pushes.load(Relaxed),
+            self.0.pops.load(Relaxed),
+            self.0.empty_pops.load(Relaxed),
+        )
+    }
+}
+
+#[test]
+pub fn test_hooks() {
+    let lifo = AtomicLifo::with_hooks(Counting::default());
+    let hooks = lifo.hooks().clone();
+    assert_eq!(hooks.get(), (0, 0, 0));
+
+    for i in 0..100 {
+        lifo.push(i);
+    }
+    lifo.try_push(100).unwrap();
+    lifo.extend(101..150);
+    assert_eq!(hooks.get(), (150, 0, 0));
+
+    for _ in 0..30 {
+        assert!(lifo.pop().is_some());
+    }
+    assert_eq!(lifo.pop_n(20).len(), 20);
+    assert_eq!(lifo.pop_if(|_| true), Some(99));
+    assert_eq!(lifo.pop_if(|_| false), None);
+    assert_eq!(hooks.get(), (150, 51, 0));
+
+    //Only the 49 odd elements are removed, the 50 even ones are pushed back without being passed to the hooks.
+    lifo.retain(|value| value % 2 == 0);
+    assert_eq!(hooks.get(), (150, 100, 0));
+    assert_eq!(lifo.pop_bottom(), Some(0));
+    assert_eq!(hooks.get(), (150, 101, 0));
+    assert_eq!(lifo.remove_first(|value| *value == 2), Some(2));
+    assert_eq!(hooks.get(), (150, 102, 0));
+
+    let mut guard = lifo.pop_guarded().unwrap();
+    *guard += 1;
+    drop(guard);
+    assert_eq!(hooks.get(), (151, 103, 0));
+
+    lifo.clear();
+    assert_eq!(lifo.pop(), None);
+    assert_eq!(lifo.pop_if(|_| true), None);
+    assert!(lifo.pop_n(4).is_empty());
+    assert_eq!(lifo.take_all().count(), 0);
+    assert_eq!(hooks.get(), (151, 151, 3));
+
+    //Every value was passed to on_pop as it was passed to on_push, including the one the guard changed.
+    assert_eq!(hooks.0.pushed_sum.load(Relaxed), hooks.0.popped_sum.load(Relaxed));
+}
+
+#[test]
+pub fn test_hooks_move() {
+    let hooks = Counting::default();
+    let lifo = AtomicLifo::with_hooks(hooks.clone());
+    let other_hooks = Counting::default();
+    let other = AtomicLifo::with_hooks(other_hooks.clone());
+
+    lifo.extend(0..10);
+    assert_eq!(lifo.steal_batch_into(&other, 4), 4);
+    assert_eq!(hooks.get(), (10, 4, 0));
+    assert_eq!(other_hooks.get(), (4, 0, 0));
+
+    lifo.swap_contents(&other);
+    assert_eq!(hooks.get(), (14, 10, 0));
+    assert_eq!(other_hooks.get(), (10, 4, 0));
+
+    //Clones and taken lifos count into clones of the hooks, which share the counters here.
+    let clone = lifo.clone();
+    assert_eq!(hooks.get(), (18, 10, 0));
+    let taken = clone.take();
+    assert_eq!(hooks.get(), (22, 14, 0));
+    assert_eq!(taken.into_vec().len(), 4);
+
+    //Elements still in a consumed lifo are not passed.
+    assert_eq!(other.into_iter().count(), 6);
+    assert_eq!(other_hooks.get(), (10, 4, 0));
+}
+
+#[test]
+pub fn test_hooks_remove_first() {
+    let hooks = Counting::default();
+    let lifo = AtomicLifo::with_hooks(hooks.clone());
+    lifo.extend(0..10);
+    assert_eq!(lifo.remove_first(|value| *value == 3), Some(3));
+    assert_eq!(lifo.remove_first(|_| false), None);
+    assert_eq!(hooks.get(), (10, 1, 0));
+    assert_eq!(hooks.0.popped_sum.load(Relaxed), 3);
+
+    //A panicking predicate pushes everything back without passing anything to the hooks.
+    assert!(catch_unwind(AssertUnwindSafe(|| lifo.remove_first(|_| panic!()))).is_err());
+    assert_eq!(hooks.get(), (10, 1, 0));
+    assert_eq!(lifo.into_vec(), [9, 8, 7, 6, 5, 4, 2, 1, 0]);
+}
+
+#[test]
+pub fn test_hooks_swap_top() {
+    let hooks = Counting::default();
+    let lifo = AtomicLifo::with_hooks(hooks.clone());
+    //Nothing was pushed or popped, the hooks see nothing.
+    assert_eq!(lifo.swap_top(1), Err(1));
+    assert_eq!(hooks.get(), (0, 0, 0));
+
+    lifo.push(2);
+    assert_eq!(lifo.swap_top(3), Ok(2));
+    assert_eq!(hooks.get(), (2, 1, 0));
+    assert_eq!(hooks.0.pushed_sum.load(Relaxed), 5);
+    assert_eq!(hooks.0.popped_sum.load(Relaxed), 2);
+    assert_eq!(lifo.into_vec(), [3]);
+}
+
+#[test]
+pub fn test_hooks_zst() {
+    let hooks = Counting::default();
+    let lifo = AtomicLifo::with_hooks(hooks.clone());
+    lifo.extend([(); 10]);
+    lifo.push(());
+    assert_eq!(lifo.pop_n(4).len(), 4);
+    while lifo.pop().is_some() {}
+    assert_eq!(hooks.get(), (11, 11, 1));
+
+    lifo.extend([(); 3]);
+    lifo.clear();
+    assert_eq!(hooks.get(), (14, 14, 1));
+}
+
+#[test]
+pub fn test_hooks_mt() {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = if cfg!(miri) { 100 } else { 10_000 };
+
+    let hooks = Counting::default();
+    let lifo = AtomicLifo::with_hooks(hooks.clone());
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for i in 0..ITERATIONS {
+                    lifo.push(i);
+                    lifo.pop();
+                }
+            });
+        }
+    });
+
+    let (pushes, pops, empty_pops) = hooks.get();
+    assert_eq!(pushes, THREADS * ITERATIONS);
+    assert_eq!(pops + empty_pops, THREADS * ITERATIONS);
+    assert_eq!(pushes - pops, lifo.take_all().count());
+    assert_eq!(hooks.0.pushed_sum.load(Relaxed), hooks.0.popped_sum.load(Relaxed));
+}
@@ -0,0 +1,73 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 10_000 };
+
+struct DropCounter<'a>(&'a AtomicUsize);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, SeqCst);
+    }
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+static DROPPED_UNPOPPED: AtomicUsize = AtomicUsize::new(0);
+static DROPPED_MT: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+pub fn test_popped_values_drop_once() {
+    let lifo = AtomicLifo::new();
+    for _ in 0..10 {
+        lifo.push(DropCounter(&DROPPED));
+    }
+
+    for i in 0..10 {
+        drop(lifo.pop().unwrap());
+        assert_eq!(DROPPED.load(SeqCst), i + 1);
+    }
+
+    //Retired and reused nodes must not drop the values again.
+    for _ in 0..10 {
+        lifo.push(DropCounter(&DROPPED));
+        drop(lifo.pop().unwrap());
+    }
+
+    drop(lifo);
+    assert_eq!(DROPPED.load(SeqCst), 20);
+}
+
+#[test]
+pub fn test_unpopped_values_drop_with_lifo() {
+    let lifo = AtomicLifo::new();
+    for _ in 0..10 {
+        lifo.push(DropCounter(&DROPPED_UNPOPPED));
+    }
+
+    drop(lifo.pop().unwrap());
+    drop(lifo.pop_n(3));
+    assert_eq!(DROPPED_UNPOPPED.load(SeqCst), 4);
+    drop(lifo);
+    assert_eq!(DROPPED_UNPOPPED.load(SeqCst), 10);
+}
+
+#[test]
+pub fn test_values_drop_once_mt() {
+    let lifo = AtomicLifo::new();
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    lifo.push(DropCounter(&DROPPED_MT));
+                    drop(lifo.pop());
+                }
+            });
+        }
+    });
+
+    drop(lifo);
+    assert_eq!(DROPPED_MT.load(SeqCst), 4 * ITERATIONS);
+}
@@ -0,0 +1,95 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use atomic_lifo::AtomicLifo;
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct DropCounter(u32);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        DROP_COUNT.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_into_iter() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.extend(["test1", "test2", "test3"].map(String::from));
+    let mut all = Vec::new();
+    for value in lifo {
+        all.push(value);
+    }
+    assert_eq!(all, vec![String::from("test3"), String::from("test2"), String::from("test1")]);
+
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.into_iter().next(), None);
+}
+
+#[test]
+pub fn test_into_iter_partial() {
+    let lifo = AtomicLifo::<DropCounter>::new();
+    lifo.extend((0..10).map(DropCounter));
+    assert_eq!(lifo.pop().unwrap().0, 9);
+    assert_eq!(lifo.pop().unwrap().0, 8);
+    assert_eq!(DROP_COUNT.load(SeqCst), 2);
+
+    let mut iter = lifo.into_iter();
+    assert_eq!(iter.next().unwrap().0, 7);
+    assert_eq!(iter.next().unwrap().0, 6);
+    assert_eq!(DROP_COUNT.load(SeqCst), 4);
+    drop(iter);
+    assert_eq!(DROP_COUNT.load(SeqCst), 10);
+}
+
+#[test]
+pub fn test_vec_round_trip() {
+    let vec = vec![1u32, 2, 3, 4];
+    let lifo = AtomicLifo::from(vec.clone());
+    assert_eq!(lifo.pop(), Some(4));
+    lifo.push(4);
+    let mut reversed = vec.clone();
+    reversed.reverse();
+    assert_eq!(lifo.into_vec(), reversed);
+    assert!(AtomicLifo::<u32>::from(Vec::new()).into_vec().is_empty());
+}
+
+#[test]
+pub fn test_into_vec_drop_count() {
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counted(u32);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, SeqCst);
+        }
+    }
+
+    let lifo = AtomicLifo::new();
+    lifo.extend((0..100).map(Counted));
+    //Keeps the nodes of the popped elements waiting to be freed until the lifo is consumed.
+    let pinned = lifo.pin();
+    for _ in 0..10 {
+        lifo.pop().unwrap();
+    }
+    drop(pinned);
+    assert_eq!(DROPPED.load(SeqCst), 10);
+    assert_eq!(lifo.deferred_node_count(), 10);
+
+    let all = lifo.into_vec();
+    assert_eq!(DROPPED.load(SeqCst), 10);
+    assert!(all.iter().map(|value| value.0).eq((0..90).rev()));
+    drop(all);
+    assert_eq!(DROPPED.load(SeqCst), 100);
+}
+
+#[test]
+pub fn test_into_inner() {
+    let lifo = AtomicLifo::new();
+    lifo.extend(["a", "b", "c"].map(String::from));
+    assert_eq!(lifo.pop().unwrap(), "c");
+    assert_eq!(lifo.into_inner(), ["b", "a"]);
+    assert!(AtomicLifo::<()>::new().into_inner().is_empty());
+}
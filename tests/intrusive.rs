@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use std::thread;
+use atomic_lifo::{Intrusive, IntrusiveAtomicLifo, Link};
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 100_000 };
+
+#[derive(Debug)]
+struct Item {
+    value: u32,
+    link: Link,
+}
+
+unsafe impl Intrusive for Item {
+    fn link(&self) -> &Link {
+        &self.link
+    }
+
+    unsafe fn from_link(link: *mut Link) -> *mut Self {
+        link.byte_sub(core::mem::offset_of!(Self, link)).cast()
+    }
+}
+
+//The items live until free_arena is called, which the test may only do once it no longer uses them.
+fn arena(size: u32) -> *mut [Item] {
+    Box::into_raw((0..size).map(|value| Item { value, link: Link::new() }).collect())
+}
+
+unsafe fn free_arena(arena: *mut [Item]) {
+    drop(Box::from_raw(arena));
+}
+
+#[test]
+pub fn test_intrusive() {
+    let lifo = IntrusiveAtomicLifo::<Item>::new();
+    assert!(lifo.pop().is_none());
+    let items = arena(3);
+    for item in unsafe { &mut *items } {
+        lifo.push(item);
+    }
+
+    assert_eq!(lifo.pop().unwrap().value, 2);
+    let item = lifo.pop().unwrap();
+    assert_eq!(item.value, 1);
+    item.value = 10;
+    lifo.push(item);
+    assert_eq!(lifo.pop().unwrap().value, 10);
+    assert_eq!(lifo.pop().unwrap().value, 0);
+    assert!(lifo.is_empty());
+    unsafe {
+        free_arena(items);
+    }
+}
+
+#[test]
+pub fn test_intrusive_raw() {
+    let lifo = IntrusiveAtomicLifo::<Item>::new();
+    let mut item = Item { value: 5, link: Link::new() };
+    unsafe {
+        lifo.push_raw(&raw mut item);
+        let popped = lifo.pop_raw();
+        assert_eq!(popped, &raw mut item);
+        assert!(lifo.pop_raw().is_null());
+    }
+
+    assert_eq!(item.value, 5);
+}
+
+static MT_LIFO: IntrusiveAtomicLifo<Item> = IntrusiveAtomicLifo::new();
+
+#[test]
+pub fn test_intrusive_mt() {
+    let items = arena(64);
+    for item in unsafe { &mut *items } {
+        MT_LIFO.push(item);
+    }
+
+    let mut jh = Vec::new();
+    for _ in 0..4 {
+        jh.push(thread::spawn(|| {
+            for _ in 0..ITERATIONS {
+                let mut held = Vec::new();
+                while held.len() < 4 {
+                    if let Some(item) = MT_LIFO.pop() {
+                        held.push(item);
+                    }
+                }
+
+                for item in held {
+                    MT_LIFO.push(item);
+                }
+            }
+        }));
+    }
+
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    let mut all = HashSet::new();
+    while let Some(item) = MT_LIFO.pop() {
+        assert!(all.insert(item.value));
+    }
+
+    assert_eq!(all.len(), 64);
+    unsafe {
+        free_arena(items);
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_iter() {
+    let lifo = AtomicLifo::new();
+    assert!(lifo.iter(&lifo.pin()).next().is_none());
+
+    lifo.extend((0..10).map(|i| i.to_string()));
+    let guard = lifo.pin();
+    let all = lifo.iter(&guard).map(|element| (*element).clone()).collect::<Vec<_>>();
+    drop(guard);
+    assert_eq!(all, (0..10).rev().map(|i| i.to_string()).collect::<Vec<_>>());
+
+    //Elements pushed after the start are not yielded, elements popped before the iterator reaches them are skipped.
+    let guard = lifo.pin();
+    let mut iter = lifo.iter(&guard);
+    lifo.push(String::from("new"));
+    assert_eq!(lifo.pop().unwrap(), "new");
+    assert_eq!(lifo.pop().unwrap(), "9");
+    assert_eq!(lifo.pop().unwrap(), "8");
+    assert_eq!(*iter.next().unwrap(), "7");
+
+    //The reference to "7" was dropped, popping it does not wait for the guard.
+    assert_eq!(lifo.pop().unwrap(), "7");
+    assert_eq!(*iter.next().unwrap(), "6");
+    drop(guard);
+}
+
+#[test]
+pub fn test_iter_zst() {
+    let lifo = AtomicLifo::new();
+    lifo.extend([(); 4]);
+    let guard = lifo.pin();
+    assert_eq!(lifo.iter(&guard).map(|element| *element).collect::<Vec<_>>(), [(); 4]);
+}
+
+#[test]
+pub fn test_iter_all_popped() {
+    let lifo = AtomicLifo::new();
+    lifo.extend((0..100).map(Box::new));
+    let guard = lifo.pin();
+    let mut iter = lifo.iter(&guard);
+
+    //Every node the iterator could still visit is popped, it must neither yield nor touch freed memory.
+    while lifo.pop().is_some() {}
+    lifo.extend((0..100).map(Box::new));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+#[should_panic(expected = "the guard does not pin this lifo")]
+pub fn test_iter_other_guard() {
+    let lifo = AtomicLifo::from(vec![1]);
+    let other = AtomicLifo::from(vec![2]);
+    let guard = other.pin();
+    _ = lifo.iter(&guard);
+}
+
+//Run under Miri or with -Zsanitizer=address to check that no freed node is read.
+#[test]
+pub fn test_iter_mt() {
+    const ITERATIONS: usize = if cfg!(miri) { 20 } else { 10_000 };
+
+    let lifo = AtomicLifo::new();
+    let stop = AtomicBool::new(false);
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                while !stop.load(SeqCst) {
+                    lifo.extend((0..4).map(|i| Box::new(i.to_string())));
+                    for _ in 0..4 {
+                        if let Some(value) = lifo.pop() {
+                            assert_eq!(value.len(), 1);
+                        }
+                    }
+                }
+            });
+        }
+
+        for _ in 0..ITERATIONS {
+            let guard = lifo.pin();
+            for element in lifo.iter(&guard) {
+                assert_eq!(element.len(), 1);
+            }
+        }
+        stop.store(true, SeqCst);
+    });
+}
+
+#[test]
+pub fn test_pop_yielded_element() {
+    let lifo = Arc::new(AtomicLifo::from(vec![String::from("a"), String::from("b")]));
+    let guard = lifo.pin();
+    let element = lifo.iter(&guard).next().unwrap();
+    let original = element.as_ptr();
+
+    //The pop waits for the reference and then moves the original out.
+    let popper = {
+        let lifo = Arc::clone(&lifo);
+        thread::spawn(move || lifo.pop().unwrap())
+    };
+    assert_eq!(*element, "b");
+    drop(element);
+    let popped = popper.join().unwrap();
+    assert_eq!(popped, "b");
+    assert_eq!(popped.as_ptr(), original);
+    assert_eq!(lifo.pop().unwrap(), "a");
+}
@@ -0,0 +1,80 @@
+use atomic_lifo::AtomicLifo;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+
+struct CountingAllocator;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    //Frees made by the thread, other threads such as the test harness may free at the same time.
+    static FREED: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(1, SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(1, SeqCst);
+        FREED.with(|freed| freed.set(freed.get() + 1));
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+struct Dropped(Arc<AtomicUsize>);
+
+impl Drop for Dropped {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, SeqCst);
+    }
+}
+
+const THREADS: usize = 4;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 1000 };
+
+#[test]
+pub fn test_leaky_frees_on_drop() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let lifo = AtomicLifo::new_leaky();
+    let live_before = LIVE.load(SeqCst);
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    lifo.push(Dropped(Arc::clone(&dropped)));
+                    lifo.push(Dropped(Arc::clone(&dropped)));
+                    drop(lifo.pop());
+                }
+            });
+        }
+    });
+
+    //Every pushed element has its own node, popped nodes are retired and neither freed nor reused.
+    assert_eq!(dropped.load(SeqCst), THREADS * ITERATIONS);
+    let live_before_drop = LIVE.load(SeqCst);
+    assert!(live_before_drop - live_before >= 2 * THREADS * ITERATIONS);
+    assert_eq!(lifo.free_node_count(), 0);
+    assert_eq!(lifo.deferred_node_count(), THREADS * ITERATIONS);
+    assert_eq!(lifo.in_flight_pop_count(), 0);
+
+    //Dropping frees the nodes of the elements in the lifo and all retired nodes.
+    let freed_before_drop = FREED.with(Cell::get);
+    drop(lifo);
+    assert_eq!(dropped.load(SeqCst), 2 * THREADS * ITERATIONS);
+    assert_eq!(
+        FREED.with(Cell::get) - freed_before_drop,
+        2 * THREADS * ITERATIONS
+    );
+}
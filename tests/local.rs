@@ -0,0 +1,134 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+const THREADS: usize = 8;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 100_000 };
+
+#[test]
+pub fn test_local() {
+    let lifo = AtomicLifo::new();
+    let mut local = lifo.local_with_capacity(3);
+    assert_eq!(local.capacity(), 3);
+    local.push(1);
+    local.push(2);
+    assert_eq!(local.buffered(), 2);
+    assert!(lifo.is_empty());
+
+    //Filling the buffer pushes all of it at once.
+    local.push(3);
+    assert_eq!(local.buffered(), 0);
+    assert_eq!(lifo.pop(), Some(3));
+
+    local.push(4);
+    lifo.push(5);
+    assert_eq!(local.pop(), Some(4));
+    assert_eq!(local.pop(), Some(5));
+    assert_eq!(local.pop(), Some(2));
+    local.push(6);
+    drop(local);
+    assert_eq!(lifo.into_vec(), [6, 1]);
+}
+
+#[test]
+pub fn test_local_interleaved() {
+    let lifo = AtomicLifo::new();
+    let mut local = lifo.local_with_capacity(4);
+    //What the lifo and the buffer should contain, the top is the last element.
+    let mut shared = Vec::new();
+    let mut buffered = Vec::new();
+
+    for i in 0..1000u32 {
+        match i % 7 {
+            0 | 2 | 5 => {
+                local.push(i);
+                buffered.push(i);
+                if buffered.len() == 4 {
+                    shared.append(&mut buffered);
+                }
+            }
+            1 => {
+                local.lifo().push(i);
+                shared.push(i);
+            }
+            3 => {
+                let expected = buffered.pop().or_else(|| shared.pop());
+                assert_eq!(local.pop(), expected);
+            }
+            4 => assert_eq!(local.lifo().pop(), shared.pop()),
+            _ => {
+                if i % 3 == 0 {
+                    local.flush();
+                    shared.append(&mut buffered);
+                }
+            }
+        }
+
+        assert_eq!(local.buffered(), buffered.len());
+    }
+
+    drop(local);
+    shared.append(&mut buffered);
+    shared.reverse();
+    assert_eq!(lifo.into_vec(), shared);
+}
+
+#[test]
+pub fn test_local_zst() {
+    let lifo = AtomicLifo::new();
+    let mut local = lifo.local();
+    local.push(());
+    local.push(());
+    assert_eq!(local.pop(), Some(()));
+    local.flush();
+    assert_eq!(lifo.pop(), Some(()));
+    assert_eq!(lifo.pop(), None);
+}
+
+#[test]
+pub fn test_local_panic() {
+    let lifo = AtomicLifo::new();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut local = lifo.local();
+        local.push(String::from("a"));
+        local.push(String::from("b"));
+        panic!("the buffer is still full");
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(lifo.into_vec(), ["b", "a"]);
+}
+
+#[test]
+pub fn test_local_mt() {
+    let lifo = AtomicLifo::new();
+    let popped: Vec<AtomicBool> = (0..THREADS * ITERATIONS).map(|_| AtomicBool::new(false)).collect();
+
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let lifo = &lifo;
+            let popped = &popped;
+            scope.spawn(move || {
+                let mut local = lifo.local();
+                for i in 0..ITERATIONS {
+                    local.push(t * ITERATIONS + i);
+                    //Pops the own buffer or the elements of the other threads.
+                    if i % 3 == 0 {
+                        if let Some(value) = local.pop() {
+                            assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    for value in lifo.into_vec() {
+        assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+    }
+
+    assert!(popped.iter().all(|popped| popped.load(SeqCst)), "an element was lost");
+}
@@ -0,0 +1,530 @@
+//Run with RUSTFLAGS="--cfg loom" cargo test --test loom --features allocator-api --release
+#![cfg(all(loom, feature = "allocator-api"))]
+use allocator_api2::alloc::{AllocError, Allocator, Global};
+use atomic_lifo::{AtomicFifo, AtomicLifo};
+use loom::sync::Arc;
+use loom::thread;
+use std::alloc::Layout;
+use std::ptr::NonNull;
+use std::sync::Mutex;
+
+//Every popped node has to wait for the next but one generation or for the limit, a small limit explores the helping in pop as well.
+const HAZARD_LIMIT: usize = 1;
+
+//Byte that freed memory is overwritten with.
+const POISON: u8 = 0xA5;
+
+#[derive(Default)]
+struct State {
+    live: usize,
+    freed: Vec<(NonNull<u8>, Layout)>,
+}
+
+//The pointers are only used by the thread that holds the lock.
+unsafe impl Send for State {}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        for (ptr, layout) in self.freed.drain(..) {
+            unsafe {
+                Global.deallocate(ptr, layout);
+            }
+        }
+    }
+}
+
+//Poisons freed memory instead of returning it to the system until the model iteration is over.
+//A thread that accesses a freed node reads the poison, which either fails the checks of the values or crashes loom.
+#[derive(Clone, Default)]
+struct PoisonAllocator(std::sync::Arc<Mutex<State>>);
+
+impl PoisonAllocator {
+    fn live(&self) -> usize {
+        self.0.lock().unwrap().live
+    }
+}
+
+unsafe impl Allocator for PoisonAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = Global.allocate(layout)?;
+        self.0.lock().unwrap().live += 1;
+        Ok(ptr)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        ptr.as_ptr().write_bytes(POISON, layout.size());
+        let mut state = self.0.lock().unwrap();
+        state.live -= 1;
+        state.freed.push((ptr, layout));
+    }
+}
+
+fn lifo(allocator: &PoisonAllocator) -> Arc<AtomicLifo<u32, PoisonAllocator>> {
+    Arc::new(AtomicLifo::with_hazard_limit_in(HAZARD_LIMIT, allocator.clone()))
+}
+
+//Drops the lifo after all threads were joined and checks that it freed every allocation.
+fn drop_lifo(lifo: Arc<AtomicLifo<u32, PoisonAllocator>>, allocator: &PoisonAllocator) {
+    drop(Arc::try_unwrap(lifo).ok().unwrap());
+    assert_eq!(allocator.live(), 0);
+}
+
+//Exploring every interleaving of a pop takes minutes, LOOM_MAX_PREEMPTIONS overrides the bound.
+fn model(f: impl Fn() + Sync + Send + 'static) {
+    let mut builder = loom::model::Builder::new();
+    builder.preemption_bound.get_or_insert(3);
+    builder.check(f);
+}
+
+//Asserts that every value in 1..=count was popped exactly once.
+fn assert_each_once(mut popped: Vec<u32>, count: u32) {
+    popped.sort_unstable();
+    assert_eq!(popped, (1..=count).collect::<Vec<_>>());
+}
+
+#[test]
+pub fn test_push_pop() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+
+        let threads: Vec<_> = (1..=2)
+            .map(|value| {
+                let lifo = lifo.clone();
+                thread::spawn(move || {
+                    lifo.push(value);
+                    lifo.pop()
+                })
+            })
+            .collect();
+
+        //Every pop comes after a push of the same thread, so no thread finds the lifo empty.
+        let popped: Vec<u32> = threads.into_iter().map(|th| th.join().unwrap().unwrap()).collect();
+        assert_eq!(lifo.pop(), None);
+        assert_each_once(popped, 2);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_concurrent_pop() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.push(1);
+        lifo.push(2);
+
+        let threads: Vec<_> = (0..2)
+            .map(|_| {
+                let lifo = lifo.clone();
+                thread::spawn(move || lifo.pop())
+            })
+            .collect();
+
+        let popped: Vec<u32> = threads.into_iter().map(|th| th.join().unwrap().unwrap()).collect();
+        assert_each_once(popped, 2);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_pop_reuse() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.push(1);
+        lifo.push(2);
+
+        //Pushes into a node that was freed by its own pop while the other thread may still hold the popped nodes.
+        let reuse = {
+            let lifo = lifo.clone();
+            thread::spawn(move || {
+                let value = lifo.pop().unwrap();
+                lifo.push(3);
+                value
+            })
+        };
+
+        let pop = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.pop().unwrap())
+        };
+
+        let mut popped = vec![reuse.join().unwrap(), pop.join().unwrap()];
+        popped.extend(lifo.pop());
+        assert_eq!(lifo.pop(), None);
+        assert_each_once(popped, 3);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_peek_while_pop() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.push(1);
+        lifo.push(2);
+
+        //A freed node would be read as the poison.
+        let peek = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.peek_with(|value| *value))
+        };
+
+        let pop = {
+            let lifo = lifo.clone();
+            thread::spawn(move || [lifo.pop().unwrap(), lifo.pop().unwrap()])
+        };
+
+        let peeked = peek.join().unwrap();
+        assert!(matches!(peeked, None | Some(1 | 2)), "{peeked:?}");
+        assert_each_once(pop.join().unwrap().to_vec(), 2);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_three_threads() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.push(1);
+
+        let threads: Vec<_> = (2..=3)
+            .map(|value| {
+                let lifo = lifo.clone();
+                thread::spawn(move || {
+                    lifo.push(value);
+                })
+            })
+            .collect();
+
+        let popped = lifo.pop();
+        for th in threads {
+            th.join().unwrap();
+        }
+
+        let mut popped: Vec<u32> = popped.into_iter().collect();
+        while let Some(value) = lifo.pop() {
+            popped.push(value);
+        }
+
+        assert_each_once(popped, 3);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+//A push and a pop that both fail their compare and swap meet in the single slot of the elimination array.
+fn elimination_lifo(allocator: &PoisonAllocator) -> Arc<AtomicLifo<u32, PoisonAllocator>> {
+    Arc::new(AtomicLifo::with_elimination_in::<1>(allocator.clone()))
+}
+
+#[test]
+pub fn test_elimination() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = elimination_lifo(&allocator);
+        lifo.push(1);
+
+        let push = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.push(2))
+        };
+
+        let pop = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.pop())
+        };
+
+        //Either pop may come before the push and find the lifo empty.
+        let mut popped: Vec<u32> = lifo.pop().into_iter().collect();
+        push.join().unwrap();
+        popped.extend(pop.join().unwrap());
+        while let Some(value) = lifo.pop() {
+            popped.push(value);
+        }
+        assert_each_once(popped, 2);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_elimination_pushes() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = elimination_lifo(&allocator);
+
+        //Two pushes compete for the slot a pop waits in, only one of them may hand over its node.
+        let threads: Vec<_> = (1..=2)
+            .map(|value| {
+                let lifo = lifo.clone();
+                thread::spawn(move || lifo.push(value))
+            })
+            .collect();
+
+        let popped = lifo.pop();
+        for th in threads {
+            th.join().unwrap();
+        }
+
+        let mut popped: Vec<u32> = popped.into_iter().collect();
+        while let Some(value) = lifo.pop() {
+            popped.push(value);
+        }
+
+        assert_each_once(popped, 2);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_exchange() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+
+        //The waiter may give up right as the pusher hands over the value, it must end up in exactly one place.
+        let waiter = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.pop_waiting())
+        };
+
+        let exchanged = lifo.push_or_exchange(1);
+        let mut popped: Vec<u32> = waiter.join().unwrap().into_iter().collect();
+        if exchanged {
+            assert_eq!(popped, [1]);
+        }
+
+        while let Some(value) = lifo.pop() {
+            popped.push(value);
+        }
+
+        assert_each_once(popped, 1);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_exchange_pushes() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+
+        //Two pushes compete for the waiting thread, only one of them may hand over its value.
+        let threads: Vec<_> = (1..=2)
+            .map(|value| {
+                let lifo = lifo.clone();
+                thread::spawn(move || lifo.push_or_exchange(value))
+            })
+            .collect();
+
+        let popped = lifo.pop_waiting();
+        let exchanged = threads.into_iter().filter_map(|th| th.join().unwrap().then_some(())).count();
+        assert!(exchanged <= 1);
+
+        let mut popped: Vec<u32> = popped.into_iter().collect();
+        while let Some(value) = lifo.pop() {
+            popped.push(value);
+        }
+
+        assert_each_once(popped, 2);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_pop_exact() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.extend([1, 2, 3]);
+
+        //Whether the single pop comes first or not, the pair is two consecutive elements.
+        let pair = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.pop_exact::<2>())
+        };
+
+        let single = lifo.pop().unwrap();
+        let pair = pair.join().unwrap().unwrap();
+        assert!(pair == [3, 2] || pair == [2, 1], "{pair:?}");
+
+        let mut popped = vec![single];
+        popped.extend(pair);
+        assert_each_once(popped, 3);
+        assert_eq!(lifo.pop(), None);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_pop_exact_too_few() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.push(1);
+
+        //The walk may find one element while the push adds the second one, the lifo must stay untouched then.
+        let push = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.push(2))
+        };
+
+        let pair = lifo.pop_exact::<2>();
+        push.join().unwrap();
+        match pair {
+            Some(pair) => assert_eq!(pair, [2, 1]),
+            None => assert_eq!(lifo.pop_n(3), [2, 1]),
+        }
+
+        assert_eq!(lifo.pop(), None);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_swap_top() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.push(1);
+
+        //The pop sees either the former top or the new value, never neither.
+        let swap = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.swap_top(2))
+        };
+
+        let mut popped: Vec<u32> = lifo.pop().into_iter().collect();
+        match swap.join().unwrap() {
+            Ok(replaced) => popped.push(replaced),
+            Err(value) => popped.push(value),
+        }
+
+        while let Some(value) = lifo.pop() {
+            popped.push(value);
+        }
+
+        assert_each_once(popped, 2);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_pop_while() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.extend([1, 2]);
+
+        //The pop of the other thread removes an element the predicate may be looking at.
+        let pop = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.pop())
+        };
+
+        let mut popped = Vec::new();
+        lifo.pop_while(|_| true, |value| popped.push(value));
+        popped.extend(pop.join().unwrap());
+        assert_each_once(popped, 2);
+        assert_eq!(lifo.pop(), None);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_retain() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let lifo = lifo(&allocator);
+        lifo.extend([1, 2, 3]);
+
+        //The pop may have loaded the nodes before they are removed and compare and swap the head with them once they are pushed back.
+        let pop = {
+            let lifo = lifo.clone();
+            thread::spawn(move || lifo.pop())
+        };
+
+        lifo.retain(|value| *value != 2);
+        let mut popped: Vec<u32> = pop.join().unwrap().into_iter().collect();
+        while let Some(value) = lifo.pop() {
+            popped.push(value);
+        }
+
+        popped.sort_unstable();
+        assert_eq!(popped, vec![1, 3]);
+        drop_lifo(lifo, &allocator);
+    });
+}
+
+fn fifo(allocator: &PoisonAllocator) -> Arc<AtomicFifo<u32, PoisonAllocator>> {
+    Arc::new(AtomicFifo::new_in(allocator.clone()))
+}
+
+//Drops the fifo after all threads were joined and checks that it freed every allocation.
+fn drop_fifo(fifo: Arc<AtomicFifo<u32, PoisonAllocator>>, allocator: &PoisonAllocator) {
+    drop(Arc::try_unwrap(fifo).ok().unwrap());
+    assert_eq!(allocator.live(), 0);
+}
+
+#[test]
+pub fn test_fifo_concurrent_push() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let fifo = fifo(&allocator);
+        //Allocates the dummy node, so both pushes race on linking their node behind the tail.
+        fifo.push(1);
+        assert_eq!(fifo.pop(), Some(1));
+
+        //A push that finds the tail lagging behind moves it for the other push before it links its own node.
+        let threads: Vec<_> = (2..=3)
+            .map(|value| {
+                let fifo = fifo.clone();
+                thread::spawn(move || fifo.push(value))
+            })
+            .collect();
+
+        for th in threads {
+            th.join().unwrap();
+        }
+
+        let first = fifo.pop().unwrap();
+        let second = fifo.pop().unwrap();
+        assert_eq!(fifo.pop(), None);
+        assert_each_once(vec![1, first, second], 3);
+        drop_fifo(fifo, &allocator);
+    });
+}
+
+#[test]
+pub fn test_fifo_push_pop() {
+    model(|| {
+        let allocator = PoisonAllocator::default();
+        let fifo = fifo(&allocator);
+        fifo.push(1);
+
+        //A pop that finds the head equal to a lagging tail moves the tail before it moves the head past it.
+        let push = {
+            let fifo = fifo.clone();
+            thread::spawn(move || fifo.push(2))
+        };
+        let pop = {
+            let fifo = fifo.clone();
+            thread::spawn(move || (fifo.pop(), fifo.pop()))
+        };
+
+        push.join().unwrap();
+        let (first, second) = pop.join().unwrap();
+        //Elements leave in the order they were pushed.
+        assert_eq!(first, Some(1));
+        let mut popped = vec![1];
+        popped.extend(second.into_iter().chain(fifo.pop()));
+        assert_eq!(fifo.pop(), None);
+        assert_each_once(popped, 2);
+
+        fifo.push(3);
+        assert_eq!(fifo.pop(), Some(3));
+        drop_fifo(fifo, &allocator);
+    });
+}
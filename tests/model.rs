@@ -0,0 +1,212 @@
+//Proptest seeds from the system entropy and persists failing cases to files, which Miri isolates.
+#![cfg(not(miri))]
+use atomic_lifo::AtomicLifo;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+
+//Values are drawn from a small range so contains and remove_first find them.
+const VALUES: std::ops::Range<u32> = 0..16;
+
+//One operation of the single threaded model test, the Debug output of the shrunk sequence reproduces a failure.
+#[derive(Debug, Clone)]
+enum Op {
+    Push(u32),
+    TryPush(u32),
+    Extend(Vec<u32>),
+    Append(Vec<u32>),
+    Pop,
+    TryPop,
+    PopBottom,
+    PopIfEven,
+    PopN(usize),
+    Drain(usize),
+    DrainFifo,
+    TakeAll,
+    Take,
+    Clear,
+    RetainEven,
+    RemoveFirst(u32),
+    SwapContents(Vec<u32>),
+    StealBatch(usize),
+    Contains(u32),
+    Peek,
+    IsEmpty,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => VALUES.prop_map(Op::Push),
+        1 => VALUES.prop_map(Op::TryPush),
+        1 => vec(VALUES, 0..8).prop_map(Op::Extend),
+        1 => vec(VALUES, 0..8).prop_map(Op::Append),
+        4 => Just(Op::Pop),
+        1 => Just(Op::TryPop),
+        1 => Just(Op::PopBottom),
+        1 => Just(Op::PopIfEven),
+        1 => (0..8usize).prop_map(Op::PopN),
+        1 => (0..8usize).prop_map(Op::Drain),
+        1 => Just(Op::DrainFifo),
+        1 => Just(Op::TakeAll),
+        1 => Just(Op::Take),
+        1 => Just(Op::Clear),
+        1 => Just(Op::RetainEven),
+        1 => VALUES.prop_map(Op::RemoveFirst),
+        1 => vec(VALUES, 0..8).prop_map(Op::SwapContents),
+        1 => (0..8usize).prop_map(Op::StealBatch),
+        1 => VALUES.prop_map(Op::Contains),
+        1 => Just(Op::Peek),
+        1 => Just(Op::IsEmpty),
+    ]
+}
+
+//Pops the elements of the model in the order the lifo pops them, the top of the model is its last element.
+fn pop_order(model: &[u32]) -> Vec<u32> {
+    model.iter().rev().copied().collect()
+}
+
+//Applies the operation to the lifo and to the model and asserts that both return the same.
+fn apply(lifo: &AtomicLifo<u32>, model: &mut Vec<u32>, op: Op) {
+    match op {
+        Op::Push(value) => {
+            lifo.push(value);
+            model.push(value);
+        }
+        Op::TryPush(value) => {
+            assert!(lifo.try_push(value).is_ok());
+            model.push(value);
+        }
+        Op::Extend(values) => {
+            lifo.extend(values.iter().copied());
+            model.extend(values);
+        }
+        Op::Append(values) => {
+            lifo.append(AtomicLifo::from(values.clone()));
+            model.extend(values);
+        }
+        Op::Pop => assert_eq!(lifo.pop(), model.pop()),
+        Op::TryPop => assert_eq!(lifo.try_pop(), Ok(model.pop())),
+        Op::PopBottom => {
+            let expected = (!model.is_empty()).then(|| model.remove(0));
+            assert_eq!(lifo.pop_bottom(), expected);
+        }
+        Op::PopIfEven => {
+            let expected = model.pop_if(|value| *value % 2 == 0);
+            assert_eq!(lifo.pop_if(|value| value % 2 == 0), expected);
+        }
+        Op::PopN(n) => {
+            let expected = pop_order(&model.split_off(model.len().saturating_sub(n)));
+            assert_eq!(lifo.pop_n(n), expected);
+        }
+        Op::Drain(n) => {
+            let expected = pop_order(&model.split_off(model.len().saturating_sub(n)));
+            assert_eq!(lifo.drain().take(n).collect::<Vec<_>>(), expected);
+        }
+        Op::DrainFifo => assert_eq!(lifo.drain_fifo().collect::<Vec<_>>(), core::mem::take(model)),
+        Op::TakeAll => assert_eq!(lifo.take_all().collect::<Vec<_>>(), pop_order(&core::mem::take(model))),
+        Op::Take => assert_eq!(lifo.take().into_vec(), pop_order(&core::mem::take(model))),
+        Op::Clear => {
+            lifo.clear();
+            model.clear();
+        }
+        Op::RetainEven => {
+            lifo.retain(|value| value % 2 == 0);
+            model.retain(|value| value % 2 == 0);
+        }
+        Op::RemoveFirst(value) => {
+            let expected = model.iter().rposition(|v| *v == value).map(|index| model.remove(index));
+            assert_eq!(lifo.remove_first(|v| *v == value), expected);
+        }
+        Op::SwapContents(values) => {
+            let other = AtomicLifo::from(values.clone());
+            lifo.swap_contents(&other);
+            let previous = core::mem::replace(model, values);
+            assert_eq!(other.into_vec(), pop_order(&previous));
+        }
+        Op::StealBatch(max) => {
+            let dest = AtomicLifo::new();
+            let expected = pop_order(&model.split_off(model.len().saturating_sub(max)));
+            assert_eq!(lifo.steal_batch_into(&dest, max), expected.len());
+            assert_eq!(dest.into_vec(), expected);
+        }
+        Op::Contains(value) => assert_eq!(lifo.contains(&value), model.contains(&value)),
+        Op::Peek => assert_eq!(lifo.peek_with(|value| *value), model.last().copied()),
+        Op::IsEmpty => assert_eq!(lifo.is_empty(), model.is_empty()),
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_model(ops in vec(op(), 0..64)) {
+        let lifo = AtomicLifo::new();
+        let mut model = Vec::new();
+        for op in ops {
+            apply(&lifo, &mut model, op);
+        }
+
+        prop_assert_eq!(lifo.into_vec(), pop_order(&model));
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    //Every producer pushes its batches one after another, batches of one value with push and larger ones with extend.
+    //Consumers pop one or two elements at a time until all producers are done and the lifo is empty.
+    #[test]
+    fn test_model_mt(producers in vec(vec(vec(VALUES, 1..4), 0..32), 1..4), consumers in 1..4usize) {
+        let lifo = AtomicLifo::new();
+        let running = AtomicUsize::new(producers.len());
+        let mut pushed: Vec<u32> = producers.iter().flatten().flatten().copied().collect();
+
+        let mut popped: Vec<u32> = thread::scope(|scope| {
+            for batches in &producers {
+                let lifo = &lifo;
+                let running = &running;
+                scope.spawn(move || {
+                    for batch in batches {
+                        if let [value] = batch.as_slice() {
+                            lifo.push(*value);
+                        } else {
+                            lifo.extend(batch.iter().copied());
+                        }
+                    }
+
+                    running.fetch_sub(1, SeqCst);
+                });
+            }
+
+            let handles: Vec<_> = (0..consumers)
+                .map(|consumer| {
+                    let lifo = &lifo;
+                    let running = &running;
+                    scope.spawn(move || {
+                        let mut popped = Vec::new();
+                        loop {
+                            let done = running.load(SeqCst) == 0;
+                            let count = popped.len();
+                            if consumer % 2 == 0 {
+                                popped.extend(lifo.pop());
+                            } else {
+                                popped.extend(lifo.pop_n(2));
+                            }
+
+                            if done && popped.len() == count {
+                                return popped;
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+
+        popped.extend(lifo.into_vec());
+        popped.sort_unstable();
+        pushed.sort_unstable();
+        prop_assert_eq!(popped, pushed);
+    }
+}
@@ -0,0 +1,123 @@
+use atomic_lifo::AtomicLifo;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+pub fn test_node_reuse() {
+    let lifo = AtomicLifo::new();
+    for i in 0..16u64 {
+        lifo.push(i);
+        assert_eq!(lifo.pop(), Some(i));
+    }
+
+    let before = allocations();
+    for i in 0..1000u64 {
+        lifo.push(i);
+        assert_eq!(lifo.pop(), Some(i));
+    }
+
+    //Both the node and the hazard node of the pop are reused.
+    assert_eq!(allocations(), before);
+
+    let boxed = Box::new(5u64);
+    let before = allocations();
+    #[allow(deprecated)]
+    lifo.push_boxed(boxed);
+    assert_eq!(allocations(), before);
+    assert_eq!(lifo.pop(), Some(5));
+}
+
+#[test]
+pub fn test_pop_frees_immediately() {
+    let lifo = AtomicLifo::new();
+    let before = allocations();
+    lifo.push(String::from("first"));
+    assert_eq!(allocations(), before + 2);
+    assert_eq!(lifo.pop().as_deref(), Some("first"));
+
+    //No other thread popped at the same time, so the node was freed right away without a hazard node.
+    assert_eq!(lifo.free_node_count(), 1);
+    assert_eq!(allocations(), before + 2);
+
+    for i in 0..1000u64 {
+        lifo.push(i.to_string());
+        assert_eq!(lifo.pop(), Some(i.to_string()));
+        assert_eq!(lifo.free_node_count(), 1);
+    }
+}
+
+#[test]
+pub fn test_retain_reuses_nodes() {
+    let lifo = AtomicLifo::new();
+    lifo.extend(0..100u64);
+    let before = allocations();
+    lifo.retain(|value| value % 2 == 0);
+    assert_eq!(allocations(), before);
+    assert_eq!(lifo.into_vec(), (0..100).rev().filter(|value| value % 2 == 0).collect::<Vec<u64>>());
+}
+
+#[test]
+pub fn test_remove_first_reuses_nodes() {
+    let lifo = AtomicLifo::new();
+    lifo.extend(0..100u64);
+    let before = allocations();
+    assert_eq!(lifo.remove_first(|value| *value == 50), Some(50));
+    assert_eq!(allocations(), before);
+    assert_eq!(lifo.into_vec(), (0..100).rev().filter(|value| *value != 50).collect::<Vec<u64>>());
+}
+
+#[test]
+pub fn test_pop_bottom_reuses_nodes() {
+    let lifo = AtomicLifo::new();
+    lifo.extend(0..100u64);
+    let before = allocations();
+    assert_eq!(lifo.pop_bottom(), Some(0));
+    assert_eq!(allocations(), before);
+    assert_eq!(lifo.into_vec(), (1..100).rev().collect::<Vec<u64>>());
+}
+
+#[test]
+pub fn test_take_reuses_nodes() {
+    let lifo = AtomicLifo::new();
+    lifo.extend(0..100u64);
+    let before = allocations();
+    let taken = lifo.take();
+    assert_eq!(allocations(), before);
+    assert!(lifo.is_empty());
+    assert_eq!(taken.into_vec(), (0..100).rev().collect::<Vec<u64>>());
+}
+
+#[test]
+pub fn test_drain_fifo_reuses_nodes() {
+    let lifo = AtomicLifo::new();
+    lifo.extend(0..100u64);
+    let before = allocations();
+    let mut drain = lifo.drain_fifo();
+    assert_eq!(allocations(), before);
+    assert_eq!(drain.next(), Some(0));
+    assert_eq!(drain.collect::<Vec<u64>>(), (1..100).collect::<Vec<u64>>());
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "rayon")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use atomic_lifo::AtomicLifo;
+use rayon::iter::ParallelIterator;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITEMS: u64 = if cfg!(miri) { 1000 } else { 1_000_000 };
+
+#[test]
+pub fn test_par_drain_sum() {
+    let lifo = AtomicLifo::new();
+    lifo.extend(0..ITEMS);
+    let sequential: u64 = (0..ITEMS).sum();
+    assert_eq!(lifo.par_drain().sum::<u64>(), sequential);
+    assert!(lifo.is_empty());
+    assert_eq!(lifo.par_drain().count(), 0);
+}
+
+#[test]
+pub fn test_par_drain_once() {
+    let lifo: AtomicLifo<usize> = (0..ITEMS as usize).collect();
+    let yielded: Vec<AtomicBool> = (0..ITEMS).map(|_| AtomicBool::new(false)).collect();
+    //More threads than cores, so tasks are split off regardless of the machine.
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+    pool.install(|| {
+        lifo.par_drain().for_each(|item| {
+            assert!(!yielded[item].swap(true, SeqCst), "{item} was yielded twice");
+        });
+    });
+    assert!(yielded.iter().all(|yielded| yielded.load(SeqCst)));
+}
+
+#[test]
+pub fn test_par_drain_strings() {
+    let lifo = AtomicLifo::new();
+    lifo.extend((0..1000).map(|i| i.to_string()));
+    let mut all: Vec<String> = lifo.par_drain().collect();
+    all.sort_by_key(|item| item.parse::<u32>().unwrap());
+    assert!(all.into_iter().eq((0..1000).map(|i| i.to_string())));
+}
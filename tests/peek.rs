@@ -0,0 +1,73 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_peek() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.peek_with(String::clone), None);
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    assert_eq!(lifo.peek_with(String::clone).unwrap(), String::from("test2"));
+    assert_eq!(lifo.peek_with(String::len).unwrap(), 5);
+    assert_eq!(lifo.pop().unwrap(), String::from("test2"));
+    assert_eq!(lifo.peek_with(String::clone).unwrap(), String::from("test1"));
+    assert_eq!(lifo.pop().unwrap(), String::from("test1"));
+    assert_eq!(lifo.peek_with(String::clone), None);
+}
+
+static MT_LIFO: AtomicLifo<String> = AtomicLifo::new();
+
+#[test]
+pub fn test_peek_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || loop {
+            if stop_clone.load(SeqCst) {
+                return;
+            }
+
+            if let Some(data) = MT_LIFO.pop() {
+                assert_eq!(data.as_str(), "123456");
+            }
+        }));
+    }
+
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || loop {
+            if stop_clone.load(SeqCst) {
+                return;
+            }
+
+            if let Some(data) = MT_LIFO.peek_with(String::clone) {
+                assert_eq!(data.as_str(), "123456");
+            }
+        }));
+    }
+
+    let stop_clone = Arc::clone(&stop);
+    jh.push(thread::spawn(move || loop {
+        if stop_clone.load(SeqCst) {
+            return;
+        }
+        MT_LIFO.push(String::from("123456"));
+        thread::yield_now();
+    }));
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    for jh in jh {
+        jh.join().unwrap();
+    }
+}
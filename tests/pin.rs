@@ -0,0 +1,144 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_pin() {
+    let lifo = AtomicLifo::<String>::new();
+    assert!(lifo.pin().head().is_none());
+    assert_eq!(lifo.pin().iter().count(), 0);
+
+    lifo.extend(["a", "b", "c"].map(String::from));
+    let guard = lifo.pin();
+    assert_eq!(guard.head().unwrap().as_str(), "c");
+    let all: Vec<String> = guard.iter().map(|element| element.clone()).collect();
+    assert_eq!(all, ["c", "b", "a"]);
+    assert_eq!((&guard).into_iter().count(), 3);
+    assert_eq!(format!("{:?}", guard.head().unwrap()), "\"c\"");
+    drop(guard);
+
+    //Elements pushed after the iterator was created are not yielded, popped ones are skipped.
+    let guard = lifo.pin();
+    let mut iter = guard.iter();
+    lifo.push(String::from("d"));
+    assert_eq!(lifo.pop().unwrap(), "d");
+    assert_eq!(lifo.pop().unwrap(), "c");
+    assert_eq!(iter.next().unwrap().as_str(), "b");
+    assert_eq!(guard.head().unwrap().as_str(), "b");
+    drop(guard);
+
+    assert_eq!(lifo.pop().unwrap(), "b");
+    assert_eq!(lifo.pop().unwrap(), "a");
+    assert!(lifo.pop().is_none());
+}
+
+#[test]
+pub fn test_pin_zst() {
+    let lifo = AtomicLifo::new();
+    assert!(lifo.pin().head().is_none());
+    lifo.extend([(); 3]);
+    let guard = lifo.pin();
+    assert_eq!(*guard.head().unwrap(), ());
+    assert_eq!(guard.iter().count(), 3);
+}
+
+#[test]
+pub fn test_pin_stalls_reclamation() {
+    let lifo = AtomicLifo::new();
+    lifo.push(0u64);
+    let guard = lifo.pin();
+    assert_eq!(lifo.in_flight_pop_count(), 1);
+
+    for i in 1..=100 {
+        lifo.push(i);
+        assert_eq!(lifo.pop(), Some(i));
+    }
+    assert_eq!(lifo.deferred_node_count(), 100);
+    assert_eq!(*guard.head().unwrap(), 0);
+
+    drop(guard);
+    assert_eq!(lifo.in_flight_pop_count(), 0);
+    lifo.quiesce();
+    assert_eq!(lifo.deferred_node_count(), 0);
+}
+
+#[test]
+pub fn test_pin_pop_waits() {
+    let lifo = AtomicLifo::new();
+    lifo.push(String::from("pinned"));
+    let popped = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        let guard = lifo.pin();
+        let head = guard.head().unwrap();
+        let popper = scope.spawn(|| {
+            let value = lifo.pop();
+            popped.store(true, SeqCst);
+            value
+        });
+
+        //The pop removed the node but cannot move the value out while we reference it.
+        thread::sleep(Duration::from_millis(100));
+        assert!(!popped.load(SeqCst));
+        assert_eq!(head.as_str(), "pinned");
+        drop(head);
+
+        assert_eq!(popper.join().unwrap().unwrap(), "pinned");
+        assert!(guard.head().is_none());
+    });
+}
+
+#[test]
+pub fn test_pin_pop_moves_original() {
+    let lifo = AtomicLifo::from(vec![String::from("bottom"), String::from("top")]);
+    let guard = lifo.pin();
+    let bottom = guard.iter().nth(1).unwrap();
+    let original = bottom.as_ptr();
+
+    //Elements that are not referenced are popped right away, also while the guard is alive.
+    assert_eq!(lifo.pop().unwrap(), "top");
+
+    thread::scope(|scope| {
+        let popper = scope.spawn(|| lifo.pop().unwrap());
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(bottom.as_str(), "bottom");
+        drop(bottom);
+
+        //The pop moved the original out of the node instead of a copy.
+        let popped = popper.join().unwrap();
+        assert_eq!(popped, "bottom");
+        assert_eq!(popped.as_ptr(), original);
+    });
+
+    assert!(guard.head().is_none());
+}
+
+#[test]
+pub fn test_pin_mt() {
+    const ITERATIONS: usize = if cfg!(miri) { 100 } else { 10_000 };
+
+    let lifo = AtomicLifo::new();
+    let stop = AtomicBool::new(false);
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                while !stop.load(SeqCst) {
+                    lifo.push(String::from("123456"));
+                    if let Some(value) = lifo.pop() {
+                        assert_eq!(value, "123456");
+                    }
+                }
+            });
+        }
+
+        for _ in 0..ITERATIONS {
+            let guard = lifo.pin();
+            for element in &guard {
+                assert_eq!(element.as_str(), "123456");
+            }
+        }
+        stop.store(true, SeqCst);
+    });
+}
@@ -0,0 +1,83 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::Pool;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 100 } else { 100_000 };
+
+#[test]
+pub fn test_pool() {
+    let pool = Pool::new(|| Vec::<u8>::with_capacity(16));
+    assert!(pool.is_empty());
+    let mut first = pool.acquire();
+    first.push(1);
+    let second = pool.acquire();
+    assert!(second.is_empty());
+    drop(first);
+    drop(second);
+    assert!(!pool.is_empty());
+
+    //The most recently returned object is handed out first.
+    let second = pool.acquire();
+    assert!(second.is_empty());
+    let first = pool.acquire();
+    assert_eq!(first.take(), vec![1]);
+    drop(second);
+    assert!(pool.acquire().is_empty());
+    pool.put(vec![2]);
+    assert_eq!(*pool.acquire(), vec![2]);
+}
+
+#[test]
+pub fn test_pool_panic() {
+    let pool = Pool::new(String::new);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut guard = pool.acquire();
+        guard.push_str("test1");
+        panic!("test");
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(pool.acquire().as_str(), "test1");
+}
+
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+fn create() -> u32 {
+    CREATED.fetch_add(1, SeqCst);
+    0
+}
+
+static MT_POOL: Pool<u32> = Pool::new(create);
+
+#[test]
+pub fn test_pool_mt() {
+    let mut jh = Vec::new();
+    for _ in 0..4 {
+        jh.push(thread::spawn(|| {
+            for _ in 0..ITERATIONS {
+                let mut guard = MT_POOL.acquire();
+                *guard += 1;
+            }
+        }));
+    }
+
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    let created = CREATED.load(SeqCst);
+    assert!(created <= 4);
+    let mut total = 0;
+    let mut guards = Vec::new();
+    for _ in 0..created {
+        let guard = MT_POOL.acquire();
+        total += *guard;
+        guards.push(guard);
+    }
+
+    assert_eq!(total, 4 * ITERATIONS);
+    assert_eq!(CREATED.load(SeqCst), created);
+}
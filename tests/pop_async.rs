@@ -0,0 +1,150 @@
+#![cfg(feature = "async")]
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::thread::Thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+
+        thread::park();
+    }
+}
+
+#[test]
+pub fn test_pop_async() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.push(String::from("test1"));
+    assert_eq!(block_on(lifo.pop_async()), String::from("test1"));
+
+    let waker = Waker::noop();
+    let mut cx = Context::from_waker(waker);
+    let mut future = pin!(lifo.pop_async());
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    lifo.push(String::from("test2"));
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(String::from("test2")));
+}
+
+#[derive(Default)]
+struct FlagWaker(AtomicBool);
+
+impl Wake for FlagWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_pop_async_repoll() {
+    let lifo = AtomicLifo::<u32>::new();
+    let flag = Arc::new(FlagWaker::default());
+    let waker = Waker::from(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(lifo.pop_async());
+
+    //Every poll replaces the waker of the previous one instead of registering another clone.
+    for _ in 0..1000 {
+        assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    }
+    //Our Arc, our waker and the single registered clone.
+    assert_eq!(Arc::strong_count(&flag), 3);
+
+    let other = Arc::new(FlagWaker::default());
+    let other_waker = Waker::from(other.clone());
+    assert_eq!(future.as_mut().poll(&mut Context::from_waker(&other_waker)), Poll::Pending);
+    assert_eq!(Arc::strong_count(&flag), 2);
+
+    lifo.push(1);
+    assert!(other.0.load(SeqCst));
+    assert!(!flag.0.load(SeqCst));
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Ready(1));
+
+    //Dropping the future deregisters its waker.
+    let mut future = Box::pin(lifo.pop_async());
+    assert_eq!(future.as_mut().poll(&mut cx), Poll::Pending);
+    drop(future);
+    assert_eq!(Arc::strong_count(&flag), 2);
+}
+
+#[test]
+pub fn test_pop_async_cancelled() {
+    let lifo = AtomicLifo::<u32>::new();
+    let live = Arc::new(FlagWaker::default());
+    let live_waker = Waker::from(live.clone());
+    let mut live_future = Box::pin(lifo.pop_async());
+    assert_eq!(live_future.as_mut().poll(&mut Context::from_waker(&live_waker)), Poll::Pending);
+
+    //The cancelled future registered last, so its slot is woken first.
+    let cancelled = Arc::new(FlagWaker::default());
+    let cancelled_waker = Waker::from(cancelled.clone());
+    let mut cancelled_future = Box::pin(lifo.pop_async());
+    assert_eq!(cancelled_future.as_mut().poll(&mut Context::from_waker(&cancelled_waker)), Poll::Pending);
+    drop(cancelled_future);
+
+    lifo.push(1);
+    assert!(!cancelled.0.load(SeqCst));
+    assert!(live.0.load(SeqCst), "the wake was lost on the cancelled future");
+    assert_eq!(live_future.as_mut().poll(&mut Context::from_waker(&live_waker)), Poll::Ready(1));
+
+    //A future that is woken and dropped before it popped passes the wake on.
+    let live = Arc::new(FlagWaker::default());
+    let live_waker = Waker::from(live.clone());
+    let mut live_future = Box::pin(lifo.pop_async());
+    assert_eq!(live_future.as_mut().poll(&mut Context::from_waker(&live_waker)), Poll::Pending);
+    let woken = Arc::new(FlagWaker::default());
+    let woken_waker = Waker::from(woken.clone());
+    let mut woken_future = Box::pin(lifo.pop_async());
+    assert_eq!(woken_future.as_mut().poll(&mut Context::from_waker(&woken_waker)), Poll::Pending);
+
+    lifo.push(2);
+    assert!(woken.0.load(SeqCst));
+    assert!(!live.0.load(SeqCst));
+    drop(woken_future);
+    assert!(live.0.load(SeqCst), "the wake was lost on the dropped future");
+    assert_eq!(live_future.as_mut().poll(&mut Context::from_waker(&live_waker)), Poll::Ready(2));
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_pop_async_mt() {
+    let consumer = thread::spawn(|| {
+        let mut sum = 0;
+        for _ in 0..100 {
+            sum += block_on(MT_LIFO.pop_async());
+        }
+
+        sum
+    });
+
+    for i in 0..100 {
+        if i % 10 == 0 {
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        MT_LIFO.push(i);
+    }
+
+    assert_eq!(consumer.join().unwrap(), 4950);
+    assert!(MT_LIFO.is_empty());
+}
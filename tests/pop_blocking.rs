@@ -0,0 +1,113 @@
+#![cfg(feature = "std")]
+use std::collections::HashSet;
+use std::thread;
+use std::time::{Duration, Instant};
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 50 } else { 1000 };
+
+#[test]
+pub fn test_pop_blocking() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.push(String::from("test1"));
+    assert_eq!(lifo.pop_blocking(), String::from("test1"));
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_pop_blocking_mt() {
+    let mut jh = Vec::new();
+    for _ in 0..4 {
+        jh.push(thread::spawn(|| {
+            let mut received = Vec::new();
+            loop {
+                let value = MT_LIFO.pop_blocking();
+                if value == u32::MAX {
+                    return received;
+                }
+
+                received.push(value);
+            }
+        }));
+    }
+
+    for i in 0..200 {
+        if i % 20 == 0 {
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        MT_LIFO.push(i);
+    }
+
+    while !MT_LIFO.is_empty() {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    MT_LIFO.extend([u32::MAX; 4]);
+
+    let mut all = HashSet::new();
+    for jh in jh {
+        for value in jh.join().unwrap() {
+            assert!(all.insert(value));
+        }
+    }
+
+    assert_eq!(all.len(), 200);
+    assert!(MT_LIFO.is_empty());
+}
+
+#[test]
+pub fn test_pop_timeout() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.pop_timeout(Duration::ZERO), None);
+    let start = Instant::now();
+    assert_eq!(lifo.pop_timeout(Duration::from_millis(100)), None);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    lifo.push(String::from("test1"));
+    assert_eq!(lifo.pop_timeout(Duration::ZERO), Some(String::from("test1")));
+    lifo.push(String::from("test2"));
+    assert_eq!(lifo.pop_timeout(Duration::MAX), Some(String::from("test2")));
+}
+
+static TIMEOUT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_pop_timeout_wakeup() {
+    let consumer = thread::spawn(|| {
+        let start = Instant::now();
+        let value = TIMEOUT_LIFO.pop_timeout(Duration::from_secs(60));
+        (value, start.elapsed())
+    });
+
+    thread::sleep(Duration::from_millis(100));
+    TIMEOUT_LIFO.push(5);
+    let (value, elapsed) = consumer.join().unwrap();
+    assert_eq!(value, Some(5));
+    assert!(elapsed < Duration::from_secs(30));
+}
+
+static RACE_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_pop_timeout_race() {
+    let consumer = thread::spawn(|| {
+        let mut received = 0;
+        for _ in 0..2 * ITERATIONS {
+            if RACE_LIFO.pop_timeout(Duration::from_micros(50)).is_some() {
+                received += 1;
+            }
+        }
+
+        received
+    });
+
+    for _ in 0..ITERATIONS {
+        RACE_LIFO.push(1);
+        thread::sleep(Duration::from_micros(50));
+    }
+
+    let received = consumer.join().unwrap();
+    assert_eq!(received + RACE_LIFO.drain().count(), ITERATIONS);
+}
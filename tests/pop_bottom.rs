@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 200 } else { 20_000 };
+
+#[test]
+pub fn test_pop_bottom() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.pop_bottom(), None);
+    lifo.extend(["test1", "test2", "test3"].map(String::from));
+    assert_eq!(lifo.pop_bottom().unwrap(), String::from("test1"));
+    assert_eq!(lifo.pop().unwrap(), String::from("test3"));
+    assert_eq!(lifo.pop_bottom().unwrap(), String::from("test2"));
+    assert_eq!(lifo.pop_bottom(), None);
+    assert!(lifo.is_empty());
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_pop_bottom_mt() {
+    let done = Arc::new(AtomicBool::new(false));
+    let pusher = {
+        let done = Arc::clone(&done);
+        thread::spawn(move || {
+            for i in 0..ITERATIONS {
+                MT_LIFO.push(i);
+            }
+
+            done.store(true, SeqCst);
+        })
+    };
+
+    let mut jh = Vec::new();
+    for bottom in [false, false, true, true] {
+        let done = Arc::clone(&done);
+        jh.push(thread::spawn(move || {
+            let mut popped = Vec::new();
+            loop {
+                let finished = done.load(SeqCst);
+                let value = if bottom { MT_LIFO.pop_bottom() } else { MT_LIFO.pop() };
+                match value {
+                    Some(value) => popped.push(value),
+                    None if finished => return popped,
+                    None => thread::yield_now(),
+                }
+            }
+        }));
+    }
+
+    pusher.join().unwrap();
+    let mut all = HashSet::new();
+    for jh in jh {
+        for value in jh.join().unwrap() {
+            assert!(all.insert(value));
+        }
+    }
+
+    for value in MT_LIFO.drain() {
+        assert!(all.insert(value));
+    }
+
+    assert_eq!(all.len(), ITERATIONS as usize);
+}
@@ -0,0 +1,108 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+const K: usize = 4;
+const THREADS: usize = 4;
+//Miri interprets every operation and is orders of magnitude slower.
+const MESSAGES: usize = if cfg!(miri) { 50 } else { 50_000 };
+
+#[test]
+pub fn test_pop_exact() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.pop_exact::<2>(), None);
+    lifo.extend(["test1", "test2", "test3"].map(String::from));
+    //Too few elements leave the lifo untouched.
+    assert_eq!(lifo.pop_exact::<4>(), None);
+    assert_eq!(lifo.pop_exact::<0>(), Some([]));
+    assert_eq!(lifo.pop_exact::<2>().unwrap(), ["test3", "test2"]);
+    assert_eq!(lifo.pop_exact::<2>(), None);
+    assert_eq!(lifo.pop_exact::<1>().unwrap(), ["test1"]);
+    assert!(lifo.is_empty());
+}
+
+#[test]
+pub fn test_pop_exact_zst() {
+    let lifo = AtomicLifo::new();
+    lifo.extend([(); 3]);
+    assert_eq!(lifo.pop_exact::<4>(), None);
+    assert_eq!(lifo.pop_exact::<3>(), Some([(); 3]));
+    assert_eq!(lifo.pop(), None);
+}
+
+#[test]
+pub fn test_pop_exact_mt() {
+    let lifo = AtomicLifo::new();
+    let popped: Vec<AtomicBool> = (0..THREADS * MESSAGES).map(|_| AtomicBool::new(false)).collect();
+
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let lifo = &lifo;
+            scope.spawn(move || {
+                for message in t * MESSAGES..(t + 1) * MESSAGES {
+                    //The fragments of a message are pushed at once, so they are consecutive in the lifo.
+                    lifo.extend((0..K).map(|fragment| (message, fragment)));
+                }
+            });
+        }
+
+        for _ in 0..THREADS {
+            let lifo = &lifo;
+            let popped = &popped;
+            scope.spawn(move || {
+                let mut received = 0;
+                while received < MESSAGES {
+                    let Some(group) = lifo.pop_exact::<K>() else {
+                        thread::yield_now();
+                        continue;
+                    };
+
+                    let message = group[0].0;
+                    assert_eq!(group, [3, 2, 1, 0].map(|fragment| (message, fragment)), "partial group");
+                    assert!(!popped[message].swap(true, SeqCst), "{message} was popped twice");
+                    received += 1;
+                }
+            });
+        }
+    });
+
+    assert!(popped.iter().all(|popped| popped.load(SeqCst)), "a message was lost");
+    assert!(lifo.is_empty());
+}
+
+#[test]
+pub fn test_pop_exact_partial_groups() {
+    let lifo = AtomicLifo::new();
+    let popped: Vec<AtomicBool> = (0..MESSAGES * K).map(|_| AtomicBool::new(false)).collect();
+    let mark = |value: usize| assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+
+    //Single pops compete with pop_exact, the values are pushed in ascending order so every group has to descend.
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for i in 0..MESSAGES * K {
+                lifo.push(i);
+            }
+        });
+
+        scope.spawn(|| {
+            for _ in 0..MESSAGES {
+                if let Some(value) = lifo.pop() {
+                    mark(value);
+                }
+            }
+        });
+
+        scope.spawn(|| {
+            for _ in 0..MESSAGES {
+                if let Some(group) = lifo.pop_exact::<3>() {
+                    assert!(group[0] > group[1] && group[1] > group[2], "{group:?}");
+                    group.into_iter().for_each(mark);
+                }
+            }
+        });
+    });
+
+    lifo.into_vec().into_iter().for_each(mark);
+    assert!(popped.iter().all(|popped| popped.load(SeqCst)), "a value was lost");
+}
@@ -0,0 +1,54 @@
+use atomic_lifo::AtomicLifo;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[test]
+pub fn test_pop_guarded() {
+    let lifo = AtomicLifo::<String>::new();
+    assert!(lifo.pop_guarded().is_none());
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    {
+        let mut guard = lifo.pop_guarded().unwrap();
+        assert_eq!(guard.as_str(), "test2");
+        guard.push_str("-modified");
+    }
+
+    let guard = lifo.pop_guarded().unwrap();
+    assert_eq!(guard.commit(), String::from("test2-modified"));
+    assert_eq!(lifo.pop_guarded().unwrap().commit(), String::from("test1"));
+    assert!(lifo.is_empty());
+}
+
+fn process(lifo: &AtomicLifo<u32>) -> Result<u32, ()> {
+    let guard = lifo.pop_guarded().ok_or(())?;
+    if *guard % 2 == 1 {
+        return Err(());
+    }
+
+    Ok(guard.commit())
+}
+
+#[test]
+pub fn test_pop_guarded_early_return() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.push(1);
+    assert_eq!(process(&lifo), Err(()));
+    assert_eq!(lifo.pop(), Some(1));
+    lifo.push(2);
+    assert_eq!(process(&lifo), Ok(2));
+    assert!(lifo.is_empty());
+}
+
+#[test]
+pub fn test_pop_guarded_panic() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.push(String::from("test1"));
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let _guard = lifo.pop_guarded().unwrap();
+        assert!(lifo.is_empty());
+        panic!("test");
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(lifo.pop().unwrap(), String::from("test1"));
+}
@@ -0,0 +1,114 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::{AtomicLifo, PopIfEqError};
+
+#[test]
+pub fn test_pop_if() {
+    let lifo = AtomicLifo::<u32>::new();
+    assert_eq!(lifo.pop_if(|_| true), None);
+    lifo.extend([1, 2, 3]);
+    assert_eq!(lifo.pop_if(|v| *v == 2), None);
+    assert_eq!(lifo.pop_if(|v| *v == 3), Some(3));
+    assert_eq!(lifo.pop_if(|v| *v == 2), Some(2));
+    assert_eq!(lifo.pop_if(|_| false), None);
+    assert_eq!(lifo.pop(), Some(1));
+    assert_eq!(lifo.pop_if(|_| true), None);
+}
+
+#[test]
+pub fn test_pop_if_eq() {
+    let lifo = AtomicLifo::new();
+    assert_eq!(lifo.pop_if_eq(&String::from("a")), Err(PopIfEqError::Empty));
+    lifo.extend(["a", "b"].map(String::from));
+    assert_eq!(lifo.pop_if_eq(&String::from("a")), Err(PopIfEqError::Mismatch));
+    assert_eq!(lifo.pop_if_eq(&String::from("b")).unwrap(), "b");
+    assert_eq!(lifo.pop_if_eq(&String::from("a")).unwrap(), "a");
+    assert_eq!(lifo.pop_if_eq(&String::from("a")), Err(PopIfEqError::Empty));
+
+    let lifo = AtomicLifo::new();
+    lifo.push(());
+    assert_eq!(lifo.pop_if_eq(&()), Ok(()));
+    assert_eq!(lifo.pop_if_eq(&()), Err(PopIfEqError::Empty));
+}
+
+#[test]
+pub fn test_pop_if_eq_mt() {
+    const THREADS: usize = 4;
+    //Miri interprets every operation and is orders of magnitude slower.
+    const VALUES: usize = if cfg!(miri) { 200 } else { 100_000 };
+
+    let lifo = AtomicLifo::new();
+    lifo.extend(0..VALUES);
+    let popped: Vec<AtomicBool> = (0..VALUES).map(|_| AtomicBool::new(false)).collect();
+
+    //Every thread peeks the top and only pops it if no other thread popped it in the meantime.
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                while let Some(top) = lifo.peek_with(|top| *top) {
+                    match lifo.pop_if_eq(&top) {
+                        Ok(value) => {
+                            assert_eq!(value, top);
+                            assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+                        }
+                        Err(PopIfEqError::Mismatch) => {}
+                        Err(PopIfEqError::Empty) => break,
+                    }
+                }
+            });
+        }
+    });
+
+    assert!(popped.iter().all(|popped| popped.load(SeqCst)), "a value was lost");
+    assert!(lifo.is_empty());
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_pop_if_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || {
+            let mut value = 0u32;
+            loop {
+                if stop_clone.load(SeqCst) {
+                    return;
+                }
+                MT_LIFO.push(value);
+                value = value.wrapping_add(1);
+            }
+        }));
+    }
+
+    for remainder in [0, 1] {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || loop {
+            if stop_clone.load(SeqCst) {
+                return;
+            }
+
+            if let Some(data) = MT_LIFO.pop_if(|v| v % 2 == remainder) {
+                assert_eq!(data % 2, remainder);
+            }
+        }));
+    }
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    MT_LIFO.clear();
+}
@@ -0,0 +1,89 @@
+use std::mem::MaybeUninit;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_pop_n() {
+    let lifo = AtomicLifo::<u32>::new();
+    assert!(lifo.pop_n(3).is_empty());
+    lifo.extend([1, 2, 3, 4, 5]);
+    assert!(lifo.pop_n(0).is_empty());
+    assert_eq!(lifo.pop_n(2), vec![5, 4]);
+    assert_eq!(lifo.pop_n(10), vec![3, 2, 1]);
+    assert!(lifo.pop_n(10).is_empty());
+    lifo.push(6);
+    assert_eq!(lifo.pop_n(1), vec![6]);
+    assert_eq!(lifo.pop(), None);
+}
+
+#[test]
+pub fn test_pop_into() {
+    let lifo = AtomicLifo::<String>::new();
+    let mut buffer: [MaybeUninit<String>; 4] = [const { MaybeUninit::uninit() }; 4];
+    assert_eq!(lifo.pop_into(&mut buffer), 0);
+    lifo.extend(["test1", "test2", "test3"].map(String::from));
+    assert_eq!(lifo.pop_into(&mut buffer[..2]), 2);
+    let first: Vec<String> = buffer[..2].iter().map(|s| unsafe { s.assume_init_read() }).collect();
+    assert_eq!(first, vec![String::from("test3"), String::from("test2")]);
+    assert_eq!(lifo.pop_into(&mut buffer), 1);
+    assert_eq!(unsafe { buffer[0].assume_init_read() }, String::from("test1"));
+    assert_eq!(lifo.pop_into(&mut buffer), 0);
+    assert_eq!(lifo.pop_into(&mut []), 0);
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_pop_n_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut pushers = Vec::new();
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        pushers.push(thread::spawn(move || {
+            let mut count = 0u64;
+            loop {
+                if stop_clone.load(SeqCst) {
+                    return count;
+                }
+                MT_LIFO.push(123456);
+                count += 1;
+            }
+        }));
+    }
+
+    let mut poppers = Vec::new();
+    for n in [1, 7, 64] {
+        let stop_clone = Arc::clone(&stop);
+        poppers.push(thread::spawn(move || {
+            let mut count = 0u64;
+            loop {
+                if stop_clone.load(SeqCst) {
+                    return count;
+                }
+
+                let popped = MT_LIFO.pop_n(n);
+                assert!(popped.len() <= n);
+                for data in popped {
+                    assert_eq!(data, 123456);
+                    count += 1;
+                }
+            }
+        }));
+    }
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    let pushed: u64 = pushers.into_iter().map(|jh| jh.join().unwrap()).sum();
+    let popped: u64 = poppers.into_iter().map(|jh| jh.join().unwrap()).sum();
+    let remaining = MT_LIFO.take_all().count() as u64;
+    assert_eq!(pushed, popped + remaining);
+}
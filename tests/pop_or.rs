@@ -0,0 +1,59 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 50_000 };
+
+#[test]
+pub fn test_pop_or() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.pop_or_default(), String::new());
+    assert_eq!(lifo.pop_or_else(|| String::from("fresh")), String::from("fresh"));
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    assert_eq!(lifo.pop_or_default(), String::from("test2"));
+    assert_eq!(lifo.pop_or_else(|| String::from("fresh")), String::from("test1"));
+    assert_eq!(lifo.pop_or_default(), String::new());
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+pub fn test_pop_or_else_mt() {
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        jh.push(thread::spawn(|| {
+            for _ in 0..ITERATIONS {
+                MT_LIFO.push(1);
+            }
+        }));
+    }
+
+    let mut consumers = Vec::new();
+    for _ in 0..2 {
+        consumers.push(thread::spawn(|| {
+            let mut popped = 0usize;
+            for _ in 0..2 * ITERATIONS {
+                let value = MT_LIFO.pop_or_else(|| {
+                    CREATED.fetch_add(1, SeqCst);
+                    0
+                });
+                popped += value as usize;
+            }
+
+            popped
+        }));
+    }
+
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    let popped: usize = consumers.into_iter().map(|jh| jh.join().unwrap()).sum();
+    let remaining = MT_LIFO.drain().count();
+    assert_eq!(popped + remaining, 2 * ITERATIONS);
+    assert_eq!(popped + CREATED.load(SeqCst), 4 * ITERATIONS);
+}
@@ -0,0 +1,82 @@
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const VALUES: usize = if cfg!(miri) { 200 } else { 100_000 };
+
+#[test]
+pub fn test_pop_while() {
+    let lifo = AtomicLifo::new();
+    let mut popped = Vec::new();
+    lifo.pop_while(|_| true, |value: String| popped.push(value));
+    assert!(popped.is_empty());
+
+    lifo.extend(["keep", "a", "b"].map(String::from));
+    lifo.pop_while(|value| value.len() == 1, |value| popped.push(value));
+    assert_eq!(popped, ["b", "a"]);
+    //The first element the predicate rejected stays on top.
+    assert_eq!(lifo.pop().unwrap(), "keep");
+}
+
+#[test]
+pub fn test_pop_while_drain() {
+    let lifo = AtomicLifo::new();
+    lifo.extend((0..1000).map(Box::new));
+    let mut popped = Vec::new();
+    lifo.pop_while(|_| true, |value| popped.push(*value));
+    assert!(lifo.is_empty());
+    assert!(popped.into_iter().eq((0..1000).rev()));
+
+    let zst = AtomicLifo::new();
+    zst.extend([(); 5]);
+    let mut count = 0;
+    zst.pop_while(|_| true, |()| count += 1);
+    assert_eq!(count, 5);
+    assert!(zst.is_empty());
+
+    zst.extend([(); 5]);
+    let mut calls = 0;
+    zst.pop_while(|()| { calls += 1; calls <= 3 }, drop);
+    assert_eq!(zst.pop_n(10).len(), 2);
+}
+
+#[test]
+pub fn test_pop_while_racing_pusher() {
+    let lifo = AtomicLifo::new();
+    let popped: Vec<AtomicBool> = (0..VALUES).map(|_| AtomicBool::new(false)).collect();
+    let received = AtomicUsize::new(0);
+    let mark = |value: usize| {
+        assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+        received.fetch_add(1, SeqCst);
+    };
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for i in 0..VALUES {
+                lifo.push(i);
+                if i % 64 == 0 {
+                    thread::yield_now();
+                }
+            }
+        });
+
+        //Odd values stop a batch, the next batch pops them.
+        let mut odd = false;
+        while received.load(SeqCst) < VALUES {
+            lifo.pop_while(
+                |value| odd || value % 2 == 0,
+                |value| {
+                    assert!(odd || value % 2 == 0, "{value} did not match the predicate");
+                    mark(value);
+                },
+            );
+            odd = !odd;
+            thread::yield_now();
+        }
+    });
+
+    assert!(popped.iter().all(|popped| popped.load(SeqCst)), "a value was lost");
+    assert!(lifo.is_empty());
+}
@@ -0,0 +1,99 @@
+use atomic_lifo::AtomicLifo;
+use std::thread;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u64 = if cfg!(miri) { 200 } else { 10_000 };
+
+#[test]
+pub fn test_quiesce() {
+    let lifo = AtomicLifo::new();
+    lifo.reserve(16);
+    lifo.extend(0..100u64);
+    for _ in 0..50 {
+        lifo.pop();
+    }
+
+    lifo.quiesce();
+    assert_eq!(lifo.free_node_count(), 0);
+
+    lifo.push(50);
+    unsafe {
+        lifo.quiesce_unchecked();
+    }
+    assert_eq!(lifo.free_node_count(), 0);
+    assert!(lifo.into_iter().eq((0..51).rev()));
+}
+
+#[test]
+pub fn test_quiesce_mt() {
+    let lifo = AtomicLifo::new();
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for i in 0..ITERATIONS {
+                    lifo.push(i);
+                    lifo.pop();
+                    if i % (ITERATIONS / 10) == 0 {
+                        lifo.quiesce();
+                    }
+                }
+            });
+        }
+    });
+
+    lifo.quiesce();
+    assert_eq!(lifo.free_node_count(), 0);
+    assert!(lifo.is_empty());
+}
+
+#[cfg(feature = "allocator-api")]
+#[test]
+pub fn test_quiesce_frees_all_nodes() {
+    use allocator_api2::alloc::{AllocError, Allocator, Global};
+    use std::alloc::Layout;
+    use std::ptr::NonNull;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    #[derive(Default)]
+    struct TrackingAllocator {
+        live: AtomicUsize,
+    }
+
+    unsafe impl Allocator for TrackingAllocator {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.live.fetch_add(1, SeqCst);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live.fetch_sub(1, SeqCst);
+            Global.deallocate(ptr, layout);
+        }
+    }
+
+    let allocator = TrackingAllocator::default();
+    let lifo = AtomicLifo::new_in(&allocator);
+    thread::scope(|scope| {
+        for t in 0..4u64 {
+            let lifo = &lifo;
+            scope.spawn(move || {
+                for i in 0..ITERATIONS {
+                    lifo.push(i);
+                    lifo.push(i);
+                    lifo.pop();
+                    if t == 0 {
+                        lifo.peek_with(|_| thread::yield_now());
+                    }
+                }
+            });
+        }
+    });
+
+    assert!(allocator.live.load(SeqCst) >= 4 * ITERATIONS as usize);
+    lifo.quiesce();
+    //Every thread popped one element less than it pushed.
+    assert_eq!(allocator.live.load(SeqCst), 4 * ITERATIONS as usize);
+    drop(lifo);
+    assert_eq!(allocator.live.load(SeqCst), 0);
+}
@@ -0,0 +1,72 @@
+use atomic_lifo::AtomicLifo;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::thread;
+
+struct CountingAllocator;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(1, SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(1, SeqCst);
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u64 = if cfg!(miri) { 2000 } else { 1_000_000 };
+
+#[test]
+pub fn test_reclaim_while_always_popping() {
+    let lifo = AtomicLifo::new();
+    lifo.push(0u64);
+    let entered = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        //Two threads take turns inspecting the lifo, one only leaves once the other entered,
+        //so there is always a thread in the middle of accessing the nodes.
+        for _ in 0..2 {
+            scope.spawn(|| {
+                while !stop.load(SeqCst) {
+                    lifo.peek_with(|_| {
+                        let me = entered.fetch_add(1, SeqCst) + 1;
+                        while entered.load(SeqCst) == me && !stop.load(SeqCst) {
+                            thread::yield_now();
+                        }
+                    });
+                }
+            });
+        }
+
+        while entered.load(SeqCst) < 2 {
+            thread::yield_now();
+        }
+
+        let live_before = LIVE.load(SeqCst);
+        let mut max_live = 0;
+        for i in 0..ITERATIONS {
+            lifo.push(i);
+            assert!(lifo.pop().is_some());
+            max_live = max_live.max(LIVE.load(SeqCst));
+        }
+        stop.store(true, SeqCst);
+
+        //Without freeing while other threads access the nodes every pop would keep its node and its hazard node.
+        assert!(
+            max_live - live_before < ITERATIONS as usize / 4,
+            "{} allocations were not freed",
+            max_live - live_before
+        );
+    });
+}
@@ -0,0 +1,66 @@
+use atomic_lifo::AtomicLifo;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+pub fn test_reserve() {
+    let lifo = AtomicLifo::<u64>::new();
+    assert_eq!(lifo.free_node_count(), 0);
+    lifo.reserve(64);
+    assert_eq!(lifo.free_node_count(), 64);
+
+    let before = allocations();
+    for round in 0..100 {
+        for i in 0..62 {
+            lifo.push(round * i);
+        }
+        while lifo.pop().is_some() {}
+    }
+    assert_eq!(allocations(), before);
+
+    lifo.push(1);
+    assert!(lifo.free_node_count() < 64);
+}
+
+#[test]
+pub fn test_reserve_beyond_capacity() {
+    let lifo = AtomicLifo::<u64>::new();
+    lifo.reserve(2000);
+    assert_eq!(lifo.free_node_count(), 2000);
+    lifo.extend(0..10);
+    assert_eq!(lifo.free_node_count(), 2000);
+    lifo.push(10);
+    assert_eq!(lifo.free_node_count(), 1999);
+    drop(lifo);
+}
+
+#[test]
+pub fn test_reserve_zst() {
+    let lifo = AtomicLifo::<()>::new();
+    lifo.reserve(10);
+    assert_eq!(lifo.free_node_count(), 0);
+}
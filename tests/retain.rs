@@ -0,0 +1,97 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 500 } else { 100_000 };
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct DropCounter(u32);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        DROP_COUNT.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_retain() {
+    let lifo = AtomicLifo::<DropCounter>::new();
+    lifo.extend((0..10).map(DropCounter));
+    lifo.retain(|v| v.0 % 3 == 0);
+    assert_eq!(DROP_COUNT.load(SeqCst), 6);
+    let remaining: Vec<u32> = lifo.drain().map(|v| v.0).collect();
+    assert_eq!(remaining, vec![9, 6, 3, 0]);
+    assert_eq!(DROP_COUNT.load(SeqCst), 10);
+
+    lifo.retain(|_| true);
+    assert!(lifo.is_empty());
+}
+
+#[test]
+pub fn test_remove_first() {
+    let lifo = AtomicLifo::<u32>::new();
+    assert_eq!(lifo.remove_first(|_| true), None);
+    lifo.extend([1, 2, 3, 4, 2, 5]);
+    assert_eq!(lifo.remove_first(|v| *v == 2), Some(2));
+    assert_eq!(lifo.remove_first(|v| *v == 6), None);
+    assert_eq!(lifo.remove_first(|v| *v == 5), Some(5));
+    assert_eq!(lifo.drain().collect::<Vec<u32>>(), vec![4, 3, 2, 1]);
+}
+
+#[test]
+pub fn test_retain_panicking() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.extend(0..10);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        lifo.retain(|v| {
+            assert_ne!(*v, 5, "pred");
+            v % 2 == 0
+        });
+    }));
+    assert!(result.is_err());
+
+    //9 and 7 were removed, the elements from 5 on were not examined yet.
+    assert_eq!(lifo.drain().collect::<Vec<u32>>(), vec![8, 6, 5, 4, 3, 2, 1, 0]);
+}
+
+#[test]
+pub fn test_remove_first_panicking() {
+    let lifo = AtomicLifo::<u32>::new();
+    lifo.extend(0..10);
+    let result = catch_unwind(AssertUnwindSafe(|| lifo.remove_first(|v| {
+        assert_ne!(*v, 5, "pred");
+        false
+    })));
+    assert!(result.is_err());
+    assert_eq!(lifo.drain().collect::<Vec<u32>>(), (0..10).rev().collect::<Vec<u32>>());
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_retain_mt() {
+    let pusher = thread::spawn(|| {
+        for i in 0..ITERATIONS {
+            MT_LIFO.push(i);
+        }
+    });
+
+    let retainer = thread::spawn(|| {
+        for _ in 0..ITERATIONS / 100 {
+            MT_LIFO.retain(|v| v % 2 == 0);
+        }
+    });
+
+    pusher.join().unwrap();
+    retainer.join().unwrap();
+    MT_LIFO.retain(|v| v % 2 == 0);
+
+    let mut remaining: Vec<u32> = MT_LIFO.drain().collect();
+    remaining.sort_unstable();
+    assert_eq!(remaining, (0..ITERATIONS).filter(|v| v % 2 == 0).collect::<Vec<u32>>());
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::{ArrayLifo, AtomicFifo, AtomicLifo, BoundedAtomicLifo};
+
+struct Task {
+    id: usize,
+    runs: AtomicUsize,
+}
+
+#[test]
+pub fn test_scoped() {
+    const TASKS: usize = if cfg!(miri) { 10 } else { 1000 };
+
+    let tasks: Vec<Task> = (0..TASKS).map(|id| Task { id, runs: AtomicUsize::new(0) }).collect();
+    let lifo = AtomicLifo::new();
+    lifo.extend(&tasks);
+
+    let ids = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                while let Some(task) = lifo.pop() {
+                    task.runs.fetch_add(1, SeqCst);
+                    ids.fetch_add(task.id, SeqCst);
+                }
+            });
+        }
+    });
+
+    assert!(lifo.is_empty());
+    assert!(tasks.iter().all(|task| task.runs.load(SeqCst) == 1));
+    assert_eq!(ids.load(SeqCst), TASKS * (TASKS - 1) / 2);
+}
+
+#[test]
+pub fn test_scoped_drop() {
+    let values = vec![String::from("a"), String::from("b")];
+    {
+        let lifo = AtomicLifo::new();
+        for value in &values {
+            lifo.push(value.as_str());
+        }
+        assert_eq!(lifo.peek_with(|top| top.len()), Some(1));
+        //The lifo is dropped with the borrowed elements still in it.
+    }
+    assert_eq!(values.len(), 2);
+}
+
+#[test]
+pub fn test_scoped_wrappers() {
+    let tasks: Vec<Task> = (0..4).map(|id| Task { id, runs: AtomicUsize::new(0) }).collect();
+    let bounded = BoundedAtomicLifo::new(4);
+    let fifo = AtomicFifo::new();
+    let array = ArrayLifo::<_, 4>::new();
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for task in &tasks {
+                assert!(bounded.try_push(task).is_ok());
+                fifo.push(task);
+                assert!(array.try_push(task).is_ok());
+            }
+        });
+    });
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            while let Some(task) = bounded.pop() {
+                task.runs.fetch_add(1, SeqCst);
+            }
+            while let Some(task) = fifo.pop() {
+                task.runs.fetch_add(1, SeqCst);
+            }
+            while let Some(task) = array.pop() {
+                task.runs.fetch_add(1, SeqCst);
+            }
+        });
+    });
+
+    assert!(tasks.iter().all(|task| task.runs.load(SeqCst) == 3));
+    assert_eq!(fifo.pop().map(|task| task.id), None);
+}
+
+#[test]
+pub fn test_scoped_ui() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/outlive.rs");
+    tests.compile_fail("tests/ui/outlive_wrappers.rs");
+    tests.compile_fail("tests/ui/invariant.rs");
+}
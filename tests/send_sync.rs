@@ -0,0 +1,79 @@
+use std::cell::Cell;
+use std::thread;
+use atomic_lifo::{
+    channel, ArrayLifo, AtomicFifo, AtomicLifo, AtomicLifoSc, BoundedAtomicLifo, LifoReceiver,
+    LifoSender, Pool,
+};
+
+//Send but not Sync, the lifo only moves it between threads.
+struct Counter {
+    count: Cell<u32>,
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+pub fn test_send_not_sync() {
+    assert_send_sync::<AtomicLifo<Counter>>();
+    assert_send_sync::<AtomicLifo<Cell<u32>>>();
+
+    let lifo = AtomicLifo::new();
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    lifo.push(Counter { count: Cell::new(0) });
+                    let counter = lifo.pop().unwrap();
+                    counter.count.set(counter.count.get() + 1);
+                    lifo.push(counter);
+                }
+            });
+        }
+    });
+
+    let mut total = 0;
+    for counter in lifo.drain() {
+        total += counter.count.get();
+    }
+    assert_eq!(total, 400);
+
+    let lifo = AtomicLifo::from(vec![Cell::new(1), Cell::new(2)]);
+    let moved = thread::spawn(move || lifo.into_vec().iter().map(Cell::get).sum::<u32>());
+    assert_eq!(moved.join().unwrap(), 3);
+}
+
+#[test]
+pub fn test_wrappers_send_not_sync() {
+    assert_send_sync::<BoundedAtomicLifo<Cell<u32>>>();
+    assert_send_sync::<AtomicLifoSc<Cell<u32>>>();
+    assert_send_sync::<AtomicFifo<Cell<u32>>>();
+    assert_send_sync::<ArrayLifo<Cell<u32>, 4>>();
+    assert_send_sync::<Pool<Cell<u32>>>();
+    assert_send_sync::<LifoSender<Cell<u32>>>();
+    assert_send_sync::<LifoReceiver<Cell<u32>>>();
+
+    let bounded = BoundedAtomicLifo::new(2);
+    let fifo = AtomicFifo::new();
+    let array = ArrayLifo::<_, 2>::new();
+    let (sender, receiver) = channel();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            bounded.try_push(Cell::new(1)).unwrap();
+            fifo.push(Cell::new(2));
+            array.try_push(Cell::new(3)).unwrap();
+            sender.push(Cell::new(4));
+        });
+    });
+
+    assert_eq!(bounded.pop().map(|cell| cell.get()), Some(1));
+    assert_eq!(fifo.pop().map(|cell| cell.get()), Some(2));
+    assert_eq!(array.pop().map(|cell| cell.get()), Some(3));
+    assert_eq!(receiver.pop().map(|cell| cell.get()), Some(4));
+}
+
+#[test]
+pub fn test_not_send() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/not_send.rs");
+    tests.compile_fail("tests/ui/peek_not_sync.rs");
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "serde")]
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_serde() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.extend(["test1", "test2", "test3"].map(String::from));
+    let json = serde_json::to_string(&lifo).unwrap();
+    assert_eq!(json, r#"["test3","test2","test1"]"#);
+
+    let deserialized: AtomicLifo<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.pop().unwrap(), String::from("test3"));
+    assert_eq!(deserialized.pop().unwrap(), String::from("test2"));
+    assert_eq!(deserialized.pop().unwrap(), String::from("test1"));
+    assert_eq!(deserialized.pop(), None);
+    assert_eq!(lifo.into_vec(), ["test3", "test2", "test1"].map(String::from).to_vec());
+}
+
+#[test]
+pub fn test_serde_empty() {
+    let lifo = AtomicLifo::<u32>::new();
+    let json = serde_json::to_string(&lifo).unwrap();
+    assert_eq!(json, "[]");
+    let deserialized: AtomicLifo<u32> = serde_json::from_str(&json).unwrap();
+    assert!(deserialized.is_empty());
+    assert!(serde_json::from_str::<AtomicLifo<u32>>(r#"[1, "a"]"#).is_err());
+}
@@ -0,0 +1,82 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::ShardedLifo;
+
+const THREADS: usize = 16;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 50 } else { 20_000 };
+
+#[test]
+pub fn test_sharded() {
+    let lifo = ShardedLifo::<String, 4>::new();
+    assert!(lifo.is_empty());
+    assert_eq!(lifo.pop(), None);
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    assert!(!lifo.is_empty());
+    #[cfg(feature = "count")]
+    assert_eq!(lifo.len(), 2);
+    //Both elements were pushed to the home shard of this thread, which is only fixed per thread with std.
+    let popped = lifo.pop().unwrap();
+    #[cfg(feature = "std")]
+    assert_eq!(popped, "test2");
+    lifo.push(String::from("test3"));
+    let mut remaining = lifo.into_vec();
+    #[cfg(feature = "std")]
+    assert_eq!(remaining, ["test3", "test1"]);
+    remaining.push(popped);
+    remaining.sort_unstable();
+    assert_eq!(remaining, ["test1", "test2", "test3"]);
+}
+
+#[test]
+pub fn test_sharded_other_shards() {
+    let lifo = ShardedLifo::<u32, 8>::new();
+    thread::scope(|scope| {
+        for t in 0..8 {
+            let lifo = &lifo;
+            scope.spawn(move || lifo.push(t));
+        }
+    });
+
+    //The elements of the other threads are found in whatever shard they were pushed to.
+    let mut popped: Vec<u32> = (0..8).map(|_| lifo.pop().unwrap()).collect();
+    assert_eq!(lifo.pop(), None);
+    popped.sort_unstable();
+    assert_eq!(popped, (0..8).collect::<Vec<_>>());
+}
+
+#[test]
+pub fn test_sharded_mt() {
+    let lifo = ShardedLifo::<usize, 4>::new();
+    let popped: Vec<AtomicBool> = (0..THREADS * ITERATIONS).map(|_| AtomicBool::new(false)).collect();
+
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let lifo = &lifo;
+            let popped = &popped;
+            scope.spawn(move || {
+                for i in 0..ITERATIONS {
+                    lifo.push(t * ITERATIONS + i);
+                    //Every other thread only pushes, so the others have to find their elements in foreign shards.
+                    if t % 2 == 0 {
+                        continue;
+                    }
+
+                    for _ in 0..2 {
+                        if let Some(value) = lifo.pop() {
+                            assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    for value in lifo.into_vec() {
+        assert!(!popped[value].swap(true, SeqCst), "{value} was popped twice");
+    }
+
+    assert!(popped.iter().all(|popped| popped.load(SeqCst)), "an element was lost");
+}
@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+use std::thread;
+use atomic_lifo::AtomicLifoSc;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 100 } else { 100_000 };
+
+#[test]
+pub fn test_single_consumer() {
+    let lifo = AtomicLifoSc::<String>::new();
+    unsafe {
+        assert_eq!(lifo.pop(), None);
+        lifo.push(String::from("test1"));
+        lifo.extend(["test2", "test3"].map(String::from));
+        assert_eq!(lifo.pop().unwrap(), String::from("test3"));
+        assert!(!lifo.is_empty());
+    }
+
+    assert_eq!(lifo.into_vec(), ["test2", "test1"].map(String::from).to_vec());
+}
+
+static MT_LIFO: AtomicLifoSc<u32> = AtomicLifoSc::new();
+
+#[test]
+pub fn test_single_consumer_mt() {
+    let mut jh = Vec::new();
+    for t in 0..4u32 {
+        jh.push(thread::spawn(move || {
+            for i in 0..ITERATIONS {
+                MT_LIFO.push(t * ITERATIONS + i);
+            }
+        }));
+    }
+
+    let mut seen = HashSet::new();
+    while seen.len() < 4 * ITERATIONS as usize {
+        //Safe, this is the only thread that pops.
+        if let Some(value) = unsafe { MT_LIFO.pop() } {
+            assert!(seen.insert(value));
+        }
+    }
+
+    for jh in jh {
+        jh.join().unwrap();
+    }
+
+    assert!(MT_LIFO.is_empty());
+}
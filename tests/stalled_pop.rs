@@ -0,0 +1,54 @@
+use atomic_lifo::AtomicLifo;
+use std::sync::mpsc;
+use std::thread;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u64 = if cfg!(miri) { 2000 } else { 1_000_000 };
+
+#[test]
+pub fn test_stalled_pop() {
+    let lifo = AtomicLifo::new();
+    lifo.push(u64::MAX);
+    let (entered_tx, entered_rx) = mpsc::channel();
+    let (resume_tx, resume_rx) = mpsc::channel::<()>();
+
+    let lifo = &lifo;
+    thread::scope(|scope| {
+        //Stays in the middle of accessing the nodes until all other threads are done.
+        let stalled = scope.spawn(move || {
+            lifo.peek_with(|value| {
+                entered_tx.send(()).unwrap();
+                resume_rx.recv().unwrap();
+                *value
+            })
+        });
+        entered_rx.recv().unwrap();
+
+        let workers: Vec<_> = (0..2)
+            .map(|_| {
+                scope.spawn(move || {
+                    for i in 0..ITERATIONS {
+                        lifo.push(i);
+                        assert!(lifo.pop().is_some());
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        //Every node popped while the stalled thread is counted waits for it.
+        assert_eq!(lifo.in_flight_pop_count(), 1);
+        assert_eq!(lifo.deferred_node_count(), 2 * ITERATIONS as usize);
+
+        resume_tx.send(()).unwrap();
+        assert_eq!(stalled.join().unwrap(), Some(u64::MAX));
+    });
+
+    assert_eq!(lifo.in_flight_pop_count(), 0);
+    assert_eq!(lifo.pop(), Some(u64::MAX));
+    assert_eq!(lifo.pop(), None);
+    lifo.quiesce();
+    assert_eq!(lifo.deferred_node_count(), 0);
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "stats")]
+use atomic_lifo::{AtomicLifo, LifoStats};
+use std::thread;
+
+//Asserts the relations between the counters that hold once no other thread uses the lifo.
+fn assert_consistent(stats: LifoStats, len: usize) {
+    assert_eq!(stats.pushes - stats.pops, len as u64);
+    assert!(stats.freed <= stats.retired, "{stats:?}");
+    assert!(stats.retired <= stats.pops, "{stats:?}");
+}
+
+#[test]
+pub fn test_stats() {
+    let lifo = AtomicLifo::new();
+    assert_eq!(lifo.stats(), LifoStats::default());
+
+    for i in 0..100u32 {
+        lifo.push(i);
+    }
+    lifo.extend(100..150);
+    assert_consistent(lifo.stats(), 150);
+
+    for _ in 0..30 {
+        assert!(lifo.pop().is_some());
+    }
+    assert_eq!(lifo.pop_n(20).len(), 20);
+    assert_eq!(lifo.pop_if(|_| true), Some(99));
+    assert_eq!(lifo.pop_if(|_| false), None);
+    assert_consistent(lifo.stats(), 99);
+
+    //Takes all 99 elements and pushes the 50 even ones back.
+    lifo.retain(|value| value % 2 == 0);
+    assert_eq!(lifo.pop_bottom(), Some(0));
+    assert_consistent(lifo.stats(), 49);
+
+    lifo.clear();
+    assert_eq!(lifo.pop(), None);
+    assert_eq!(lifo.pop_if(|_| true), None);
+    assert!(lifo.pop_n(4).is_empty());
+    assert_eq!(lifo.take_all().count(), 0);
+
+    lifo.quiesce();
+    let stats = lifo.stats();
+    assert_eq!(stats.pushes, 150 + 50 + 49);
+    assert_eq!(stats.pops, stats.pushes);
+    assert_eq!(stats.empty_pops, 3);
+    assert_eq!(stats.freed, stats.retired);
+    assert_eq!(stats.hazard_limit_hits, 0);
+    assert_consistent(stats, 0);
+}
+
+#[test]
+pub fn test_stats_zst() {
+    let lifo = AtomicLifo::new();
+    lifo.extend([(); 10]);
+    assert_eq!(lifo.pop_n(4).len(), 4);
+    while lifo.pop().is_some() {}
+
+    let stats = lifo.stats();
+    assert_eq!((stats.pushes, stats.pops, stats.empty_pops), (10, 10, 1));
+    assert_eq!(stats.retired, 0);
+}
+
+#[test]
+pub fn test_stats_leaky() {
+    let lifo = AtomicLifo::new_leaky();
+    lifo.extend(0..10u32);
+    assert_eq!(lifo.pop_n(4).len(), 4);
+    assert!(lifo.pop().is_some());
+
+    let stats = lifo.stats();
+    assert_eq!((stats.retired, stats.freed), (5, 0));
+    assert_eq!(lifo.deferred_node_count(), 5);
+
+    //Safe, no other thread uses the lifo.
+    unsafe {
+        lifo.quiesce_unchecked();
+    }
+    assert_eq!(lifo.stats().freed, 5);
+    assert_eq!(lifo.deferred_node_count(), 0);
+    assert_consistent(lifo.stats(), 5);
+}
+
+#[test]
+pub fn test_stats_constructed() {
+    let lifo = AtomicLifo::from(vec![1, 2, 3]);
+    assert_eq!(lifo.stats().pushes, 3);
+    assert_eq!(lifo.clone().stats().pushes, 3);
+}
+
+#[test]
+pub fn test_stats_mt() {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = if cfg!(miri) { 100 } else { 10_000 };
+
+    let lifo = AtomicLifo::new();
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for i in 0..ITERATIONS {
+                    lifo.push(i);
+                    lifo.pop();
+                }
+            });
+        }
+    });
+
+    lifo.quiesce();
+    let stats = lifo.stats();
+    assert_eq!(stats.pushes, (THREADS * ITERATIONS) as u64);
+    assert_eq!(stats.pops + stats.empty_pops, (THREADS * ITERATIONS) as u64);
+    assert_eq!(stats.freed, stats.retired);
+    assert_consistent(stats, lifo.into_vec().len());
+}
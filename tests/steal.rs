@@ -0,0 +1,66 @@
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::Arc;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_steal_batch_into() {
+    let victim = AtomicLifo::<String>::new();
+    let thief = AtomicLifo::<String>::new();
+    assert_eq!(victim.steal_batch_into(&thief, 2), 0);
+    victim.extend(["test1", "test2", "test3"].map(String::from));
+    thief.push(String::from("test4"));
+    assert_eq!(victim.steal_batch_into(&thief, 0), 0);
+    assert_eq!(victim.steal_batch_into(&thief, 2), 2);
+    assert_eq!(thief.into_vec(), ["test3", "test2", "test4"].map(String::from).to_vec());
+    assert_eq!(victim.steal_batch_into(&AtomicLifo::new(), 5), 1);
+    assert!(victim.is_empty());
+}
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITEMS: usize = if cfg!(miri) { 500 } else { 200_000 };
+const WORKERS: usize = 4;
+
+static LIFOS: [AtomicLifo<usize>; WORKERS] = [AtomicLifo::new(), AtomicLifo::new(), AtomicLifo::new(), AtomicLifo::new()];
+
+#[test]
+pub fn test_steal_batch_into_mt() {
+    let seen = Arc::new((0..ITEMS).map(|_| AtomicBool::new(false)).collect::<Vec<_>>());
+    let consumed = Arc::new(AtomicUsize::new(0));
+
+    let producer = thread::spawn(|| {
+        for i in 0..ITEMS {
+            LIFOS[i % 2].push(i);
+        }
+    });
+
+    let mut workers = Vec::new();
+    for (worker, own) in LIFOS.iter().enumerate() {
+        let seen = Arc::clone(&seen);
+        let consumed = Arc::clone(&consumed);
+        workers.push(thread::spawn(move || {
+            let mut victim = worker;
+            while consumed.load(SeqCst) < ITEMS {
+                if let Some(item) = own.pop() {
+                    assert!(!seen[item].swap(true, SeqCst));
+                    consumed.fetch_add(1, SeqCst);
+                    continue;
+                }
+
+                victim = (victim + 1) % WORKERS;
+                if victim != worker {
+                    LIFOS[victim].steal_batch_into(own, 16);
+                }
+            }
+        }));
+    }
+
+    producer.join().unwrap();
+    for jh in workers {
+        jh.join().unwrap();
+    }
+
+    assert!(seen.iter().all(|seen| seen.load(SeqCst)));
+    assert!(LIFOS.iter().all(AtomicLifo::is_empty));
+}
@@ -0,0 +1,140 @@
+#[cfg(feature = "epoch")]
+use atomic_lifo::EpochReclaim;
+use atomic_lifo::{AtomicLifo, HazardReclaim, LeakReclaim, Reclaim};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+
+const THREADS: u64 = 4;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u64 = if cfg!(miri) { 100 } else { 10_000 };
+
+struct Dropped(Arc<AtomicUsize>);
+
+impl Drop for Dropped {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, SeqCst);
+    }
+}
+
+fn order<R: Reclaim>(reclaim: R) {
+    let lifo = AtomicLifo::with_reclaim(reclaim);
+    assert_eq!(lifo.pop(), None);
+    for i in 0..10u64 {
+        lifo.push(i);
+    }
+    lifo.extend(10..20);
+    assert_eq!(lifo.pop(), Some(19));
+    assert!(lifo.drain().eq((0..19).rev()));
+    assert!(lifo.is_empty());
+}
+
+fn collections<R: Reclaim>(reclaim: R) {
+    let lifo = AtomicLifo::with_reclaim(reclaim);
+    lifo.extend(0..100u64);
+    lifo.retain(|i| i % 2 == 0);
+    assert_eq!(lifo.pop_n(2), vec![98, 96]);
+    let clone = lifo.clone();
+    assert!(lifo.take_all().eq((0..48).rev().map(|i| i * 2)));
+    assert_eq!(clone.peek_with(|top| *top), Some(94));
+    assert!(clone.into_iter().eq((0..48).rev().map(|i| i * 2)));
+}
+
+fn drops<R: Reclaim>(reclaim: R) {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let lifo = AtomicLifo::with_reclaim(reclaim);
+    for _ in 0..100 {
+        lifo.push(Dropped(Arc::clone(&dropped)));
+    }
+    for _ in 0..60 {
+        drop(lifo.pop());
+    }
+    assert_eq!(dropped.load(SeqCst), 60);
+    drop(lifo);
+    assert_eq!(dropped.load(SeqCst), 100);
+}
+
+fn mt<R: Reclaim>(reclaim: R) {
+    let lifo = AtomicLifo::with_reclaim(reclaim);
+    let popped = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let lifo = &lifo;
+            let popped = &popped;
+            scope.spawn(move || {
+                for i in 0..ITERATIONS {
+                    lifo.push(t * ITERATIONS + i);
+                    if let Some(value) = lifo.pop() {
+                        popped.fetch_add(usize::try_from(value).unwrap(), SeqCst);
+                    }
+                    if t == 0 {
+                        lifo.peek_with(|_| thread::yield_now());
+                    }
+                }
+            });
+        }
+    });
+
+    for value in lifo.drain() {
+        popped.fetch_add(usize::try_from(value).unwrap(), SeqCst);
+    }
+    let pushed = (0..THREADS * ITERATIONS).sum::<u64>();
+    assert_eq!(popped.load(SeqCst), usize::try_from(pushed).unwrap());
+}
+
+fn quiesce<R: Reclaim>(reclaim: R) {
+    let lifo = AtomicLifo::with_reclaim(reclaim);
+    lifo.reserve(16);
+    lifo.extend(0..100u64);
+    for _ in 0..50 {
+        lifo.pop();
+    }
+
+    lifo.quiesce();
+    assert_eq!(lifo.free_node_count(), 0);
+    lifo.push(50);
+    unsafe {
+        lifo.quiesce_unchecked();
+    }
+    assert_eq!(lifo.free_node_count(), 0);
+    assert!(lifo.into_iter().eq((0..51).rev()));
+}
+
+macro_rules! strategy {
+    ($name:ident, $reclaim:expr) => {
+        mod $name {
+            use super::*;
+
+            #[test]
+            pub fn test_order() {
+                order($reclaim);
+            }
+
+            #[test]
+            pub fn test_collections() {
+                collections($reclaim);
+            }
+
+            #[test]
+            pub fn test_drops() {
+                drops($reclaim);
+            }
+
+            #[test]
+            pub fn test_mt() {
+                mt($reclaim);
+            }
+
+            #[test]
+            pub fn test_quiesce() {
+                quiesce($reclaim);
+            }
+        }
+    };
+}
+
+strategy!(hazard, HazardReclaim);
+strategy!(leak, LeakReclaim);
+#[cfg(feature = "epoch")]
+strategy!(epoch, EpochReclaim);
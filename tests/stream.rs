@@ -0,0 +1,138 @@
+#![cfg(feature = "async")]
+use std::collections::HashSet;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::thread::Thread;
+use std::time::Duration;
+use atomic_lifo::AtomicLifo;
+use futures_core::Stream;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 100 } else { 1000 };
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+
+        thread::park();
+    }
+}
+
+#[test]
+pub fn test_stream() {
+    let lifo = AtomicLifo::<String>::new();
+    let waker = Waker::noop();
+    let mut cx = Context::from_waker(waker);
+    let mut stream = pin!(lifo.stream());
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Pending);
+    lifo.extend(["test1", "test2"].map(String::from));
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(String::from("test2"))));
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(String::from("test1"))));
+    assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Pending);
+}
+
+#[derive(Default)]
+struct FlagWaker(AtomicBool);
+
+impl Wake for FlagWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_stream_repoll() {
+    let lifo = AtomicLifo::<u32>::new();
+    let flag = Arc::new(FlagWaker::default());
+    let waker = Waker::from(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    let mut stream = Box::pin(lifo.stream());
+
+    for i in 0..1000 {
+        assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Pending);
+        if i % 10 == 0 {
+            lifo.push(i);
+            assert!(flag.0.swap(false, SeqCst));
+            assert_eq!(stream.as_mut().poll_next(&mut cx), Poll::Ready(Some(i)));
+        }
+    }
+    //Our Arc, our waker and the single registered clone.
+    assert_eq!(Arc::strong_count(&flag), 3);
+
+    //A cancelled stream neither keeps its waker nor absorbs the wake of a live one.
+    let live = Arc::new(FlagWaker::default());
+    let live_waker = Waker::from(live.clone());
+    let mut live_stream = Box::pin(lifo.stream());
+    assert_eq!(live_stream.as_mut().poll_next(&mut Context::from_waker(&live_waker)), Poll::Pending);
+    drop(stream);
+    assert_eq!(Arc::strong_count(&flag), 2);
+
+    lifo.push(1000);
+    assert!(live.0.load(SeqCst), "the wake was lost on the cancelled stream");
+    assert_eq!(live_stream.as_mut().poll_next(&mut Context::from_waker(&live_waker)), Poll::Ready(Some(1000)));
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_stream_mt() {
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        jh.push(thread::spawn(|| {
+            block_on(async {
+                let mut stream = pin!(MT_LIFO.stream());
+                let mut received = Vec::new();
+                while let Some(value) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+                    if value == u32::MAX {
+                        return received;
+                    }
+
+                    received.push(value);
+                }
+
+                unreachable!("stream terminated");
+            })
+        }));
+    }
+
+    for i in 0..ITERATIONS {
+        if i % (ITERATIONS / 10) == 0 {
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        MT_LIFO.push(i);
+    }
+
+    while !MT_LIFO.is_empty() {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    MT_LIFO.extend([u32::MAX; 2]);
+
+    let mut all = HashSet::new();
+    for jh in jh {
+        for value in jh.join().unwrap() {
+            assert!(all.insert(value));
+        }
+    }
+
+    assert_eq!(all.len(), ITERATIONS as usize);
+}
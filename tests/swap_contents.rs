@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u32 = if cfg!(miri) { 250 } else { 50_000 };
+
+#[test]
+pub fn test_swap_contents() {
+    let a = AtomicLifo::<String>::new();
+    let b = AtomicLifo::<String>::new();
+    a.extend(["a1", "a2", "a3"].map(String::from));
+    b.push(String::from("b1"));
+    a.swap_contents(&b);
+    assert_eq!(a.pop().unwrap(), String::from("b1"));
+    assert_eq!(a.pop(), None);
+    b.swap_contents(&b);
+    a.swap_contents(&b);
+    assert!(b.is_empty());
+    assert_eq!(a.into_vec(), ["a3", "a2", "a1"].map(String::from).to_vec());
+}
+
+static LIFO_A: AtomicLifo<u32> = AtomicLifo::new();
+static LIFO_B: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_swap_contents_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let popped = Arc::new(Mutex::new(Vec::new()));
+    let mut pushers = Vec::new();
+    for t in 0..2u32 {
+        pushers.push(thread::spawn(move || {
+            for i in 0..ITERATIONS {
+                let lifo = if i % 2 == 0 { &LIFO_A } else { &LIFO_B };
+                lifo.push(t * ITERATIONS + i);
+            }
+        }));
+    }
+
+    let mut others = Vec::new();
+    for lifo in [&LIFO_A, &LIFO_B] {
+        let stop_clone = Arc::clone(&stop);
+        let popped_clone = Arc::clone(&popped);
+        others.push(thread::spawn(move || {
+            let mut local = Vec::new();
+            while !stop_clone.load(SeqCst) {
+                if let Some(value) = lifo.pop() {
+                    local.push(value);
+                }
+            }
+
+            popped_clone.lock().unwrap().extend(local);
+        }));
+    }
+
+    let stop_clone = Arc::clone(&stop);
+    others.push(thread::spawn(move || {
+        while !stop_clone.load(SeqCst) {
+            LIFO_A.swap_contents(&LIFO_B);
+        }
+    }));
+
+    for jh in pushers {
+        jh.join().unwrap();
+    }
+
+    stop.store(true, SeqCst);
+    for jh in others {
+        jh.join().unwrap();
+    }
+
+    let mut all = popped.lock().unwrap().clone();
+    all.extend(LIFO_A.drain());
+    all.extend(LIFO_B.drain());
+    assert_eq!(all.len(), 2 * ITERATIONS as usize);
+    assert_eq!(all.into_iter().collect::<HashSet<_>>().len(), 2 * ITERATIONS as usize);
+}
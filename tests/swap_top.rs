@@ -0,0 +1,63 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+const THREADS: usize = 4;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 50_000 };
+
+#[test]
+pub fn test_swap_top() {
+    let lifo = AtomicLifo::new();
+    assert_eq!(lifo.swap_top(String::from("a")).unwrap_err(), "a");
+    assert!(lifo.is_empty());
+    lifo.extend(["a", "b"].map(String::from));
+    assert_eq!(lifo.swap_top(String::from("c")).unwrap(), "b");
+    assert_eq!(lifo.swap_top(String::from("d")).unwrap(), "c");
+    #[cfg(feature = "count")]
+    assert_eq!(lifo.len(), 2);
+    assert_eq!(lifo.into_vec(), ["d", "a"]);
+
+    let lifo = AtomicLifo::new();
+    assert_eq!(lifo.swap_top(()), Err(()));
+    lifo.push(());
+    assert_eq!(lifo.swap_top(()), Ok(()));
+    assert_eq!(lifo.pop(), Some(()));
+    assert_eq!(lifo.pop(), None);
+}
+
+#[test]
+pub fn test_swap_top_mt() {
+    let lifo = AtomicLifo::new();
+    let seen: Vec<AtomicBool> = (0..THREADS * ITERATIONS).map(|_| AtomicBool::new(false)).collect();
+    let mark = |value: usize| assert!(!seen[value].swap(true, SeqCst), "{value} was returned twice");
+
+    //Swaps race with pops and pushes, every value has to come out exactly once.
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let lifo = &lifo;
+            scope.spawn(move || {
+                for i in 0..ITERATIONS {
+                    let value = t * ITERATIONS + i;
+                    match i % 3 {
+                        0 => lifo.push(value),
+                        1 => match lifo.swap_top(value) {
+                            Ok(replaced) => mark(replaced),
+                            Err(value) => mark(value),
+                        },
+                        _ => {
+                            lifo.push(value);
+                            if let Some(popped) = lifo.pop() {
+                                mark(popped);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    lifo.into_vec().into_iter().for_each(mark);
+    assert!(seen.iter().all(|seen| seen.load(SeqCst)), "a value was lost");
+}
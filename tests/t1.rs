@@ -31,6 +31,21 @@ pub fn test_drop_with_elements() {
     drop(lifo);
 }
 
+#[test]
+pub fn test_is_empty() {
+    let lifo = AtomicLifo::<String>::new();
+    assert!(lifo.is_empty());
+    lifo.push(String::from("test1"));
+    assert!(!lifo.is_empty());
+    lifo.push(String::from("test2"));
+    assert!(!lifo.is_empty());
+    assert_eq!(lifo.pop().unwrap(), String::from("test2"));
+    assert!(!lifo.is_empty());
+    assert_eq!(lifo.pop().unwrap(), String::from("test1"));
+    assert!(lifo.is_empty());
+    assert_eq!(lifo.pop(), None);
+    assert!(lifo.is_empty());
+}
 
 static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
 
@@ -63,7 +78,12 @@ pub fn test_mt() {
         })
     };
 
-    thread::sleep(Duration::from_secs(15));
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(15)
+    });
     stop.store(true, SeqCst);
     th1.join().unwrap();
     th2.join().unwrap();
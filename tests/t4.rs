@@ -44,7 +44,12 @@ pub fn test_mt2_4() {
         jh.push(th2);
     }
 
-    thread::sleep(Duration::from_secs(15));
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(15)
+    });
     stop.store(true, SeqCst);
     for jh in jh {
         jh.join().unwrap();
@@ -0,0 +1,70 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 500 } else { 100_000 };
+
+#[test]
+pub fn test_take() {
+    let lifo = AtomicLifo::<String>::new();
+    assert!(lifo.take().is_empty());
+    lifo.extend(["test1", "test2", "test3"].map(String::from));
+    let taken = lifo.take();
+    assert!(lifo.is_empty());
+    lifo.push(String::from("test4"));
+    assert_eq!(taken.pop().unwrap(), String::from("test3"));
+    taken.push(String::from("test5"));
+    assert_eq!(taken.into_vec(), ["test5", "test2", "test1"].map(String::from).to_vec());
+    assert_eq!(lifo.into_vec(), vec![String::from("test4")]);
+}
+
+#[test]
+pub fn test_take_while_pinned() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.extend(["test1", "test2"].map(String::from));
+
+    //The pinned thread may still look at the old nodes, so the values are moved into new ones.
+    let guard = lifo.pin();
+    let taken = lifo.take();
+    drop(guard);
+    assert!(lifo.is_empty());
+    assert_eq!(taken.into_vec(), ["test2", "test1"].map(String::from).to_vec());
+}
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+struct DropCounter;
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        DROP_COUNT.fetch_add(1, SeqCst);
+    }
+}
+
+static MT_LIFO: AtomicLifo<DropCounter> = AtomicLifo::new();
+
+#[test]
+pub fn test_take_mt() {
+    let pusher = thread::spawn(|| {
+        for _ in 0..ITERATIONS {
+            MT_LIFO.push(DropCounter);
+        }
+    });
+
+    let mut taken = 0;
+    while !pusher.is_finished() {
+        let batch = MT_LIFO.take();
+        taken += batch.pop().map_or(0, |_| 1);
+        taken += batch.drain().count();
+    }
+
+    pusher.join().unwrap();
+    let rest = MT_LIFO.take();
+    assert!(MT_LIFO.is_empty());
+    let before = DROP_COUNT.load(SeqCst);
+    drop(rest);
+    assert_eq!(taken + DROP_COUNT.load(SeqCst) - before, ITERATIONS);
+    assert_eq!(DROP_COUNT.load(SeqCst), ITERATIONS);
+}
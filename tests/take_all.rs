@@ -0,0 +1,136 @@
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use std::cell::Cell;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_take_all() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.take_all().next(), None);
+    lifo.push(String::from("test1"));
+    lifo.push(String::from("test2"));
+    lifo.push(String::from("test3"));
+    let all: Vec<String> = lifo.take_all().collect();
+    assert_eq!(all, vec![String::from("test3"), String::from("test2"), String::from("test1")]);
+    assert!(lifo.is_empty());
+    lifo.push(String::from("test4"));
+    assert_eq!(lifo.pop().unwrap(), String::from("test4"));
+    assert_eq!(lifo.pop(), None);
+}
+
+static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct DropCounter(u32);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        DROP_COUNT.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_take_all_partial() {
+    let lifo = AtomicLifo::<DropCounter>::new();
+    for i in 0..10 {
+        lifo.push(DropCounter(i));
+    }
+
+    {
+        let mut chain = lifo.take_all();
+        assert_eq!(chain.next().unwrap().0, 9);
+        assert_eq!(chain.next().unwrap().0, 8);
+        assert_eq!(DROP_COUNT.load(SeqCst), 2);
+        lifo.push(DropCounter(10));
+    }
+
+    assert_eq!(DROP_COUNT.load(SeqCst), 10);
+    assert_eq!(lifo.pop().unwrap().0, 10);
+    assert_eq!(DROP_COUNT.load(SeqCst), 11);
+    assert!(lifo.pop().is_none());
+}
+
+#[test]
+pub fn test_take_all_send() {
+    //Send but not Sync, the chain is still printed and sent to another thread.
+    let lifo = AtomicLifo::from(vec![Cell::new(1), Cell::new(2), Cell::new(3)]);
+    let mut chain = lifo.take_all();
+    assert_eq!(chain.next().map(|cell| cell.get()), Some(3));
+    assert!(format!("{chain:?}").starts_with("OwnedChain"));
+
+    let sum = thread::scope(|scope| scope.spawn(move || chain.map(|cell| cell.get()).sum::<u32>()).join().unwrap());
+    assert_eq!(sum, 3);
+    assert!(lifo.is_empty());
+}
+
+static MT_LIFO: AtomicLifo<u32> = AtomicLifo::new();
+
+#[test]
+pub fn test_take_all_mt() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut jh = Vec::new();
+    for _ in 0..2 {
+        let stop_clone = Arc::clone(&stop);
+        jh.push(thread::spawn(move || {
+            let mut count = 0u64;
+            loop {
+                if stop_clone.load(SeqCst) {
+                    return count;
+                }
+                MT_LIFO.push(123456);
+                count += 1;
+                thread::yield_now();
+            }
+        }));
+    }
+
+    let stop_clone = Arc::clone(&stop);
+    let popper = thread::spawn(move || {
+        let mut count = 0u64;
+        loop {
+            if stop_clone.load(SeqCst) {
+                return count;
+            }
+
+            if let Some(data) = MT_LIFO.pop() {
+                assert_eq!(data, 123456);
+                count += 1;
+            }
+        }
+    });
+
+    let stop_clone = Arc::clone(&stop);
+    let taker = thread::spawn(move || {
+        let mut count = 0u64;
+        loop {
+            if stop_clone.load(SeqCst) {
+                return count;
+            }
+
+            for data in MT_LIFO.take_all() {
+                assert_eq!(data, 123456);
+                count += 1;
+            }
+            thread::yield_now();
+        }
+    });
+
+    //Miri interprets every operation and is orders of magnitude slower.
+    thread::sleep(if cfg!(miri) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_secs(5)
+    });
+    stop.store(true, SeqCst);
+    let mut pushed = 0;
+    for jh in jh {
+        pushed += jh.join().unwrap();
+    }
+
+    let removed = popper.join().unwrap() + taker.join().unwrap();
+    let remaining = MT_LIFO.take_all().count() as u64;
+    assert_eq!(pushed, removed + remaining);
+}
@@ -0,0 +1,48 @@
+//Spawns cargo, which Miri cannot run.
+#![cfg(not(miri))]
+use std::path::Path;
+use std::process::Command;
+
+const TARGET: &str = "thumbv6m-none-eabi";
+
+fn target_installed() -> bool {
+    let Ok(output) = Command::new("rustc").args(["--print", "sysroot"]).output() else {
+        return false;
+    };
+
+    let sysroot = String::from_utf8_lossy(&output.stdout);
+    Path::new(sysroot.trim())
+        .join("lib/rustlib")
+        .join(TARGET)
+        .exists()
+}
+
+//Cortex-M0 has no compare and swap, portable-atomic emulates it by disabling interrupts on this single core target.
+#[test]
+pub fn test_build_thumbv6m() {
+    if !target_installed() {
+        eprintln!("skipped, install the target with `rustup target add {TARGET}`");
+        return;
+    }
+
+    let status = Command::new(env!("CARGO"))
+        .args([
+            "build",
+            "--lib",
+            "--target",
+            TARGET,
+            "--features",
+            "portable-atomic,critical-section",
+        ])
+        .arg("--target-dir")
+        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join(TARGET))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env_remove("CARGO_ENCODED_RUSTFLAGS")
+        .env(
+            "RUSTFLAGS",
+            "--cfg portable_atomic_unsafe_assume_single_core",
+        )
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
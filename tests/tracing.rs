@@ -0,0 +1,148 @@
+#![cfg(feature = "tracing")]
+use atomic_lifo::AtomicLifo;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{mpsc, Mutex, Once};
+use std::thread;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+//Events of all threads, the lifo may emit events from whichever thread happens to free.
+static EVENTS: Mutex<Vec<Captured>> = Mutex::new(Vec::new());
+static INIT: Once = Once::new();
+
+#[derive(Debug, Default)]
+struct Captured {
+    message: String,
+    numbers: HashMap<&'static str, u64>,
+    strings: HashMap<&'static str, String>,
+}
+
+impl Visit for Captured {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.numbers.insert(field.name(), value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.strings.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        }
+    }
+}
+
+struct Capture;
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        assert_eq!(event.metadata().target(), "atomic_lifo");
+        let mut captured = Captured::default();
+        event.record(&mut captured);
+        EVENTS.lock().unwrap().push(captured);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn capture() {
+    INIT.call_once(|| tracing::subscriber::set_global_default(Capture).unwrap());
+}
+
+//Returns the values of the field of all captured events whose message starts with the prefix.
+fn captured(prefix: &str, field: &str) -> Vec<u64> {
+    EVENTS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|event| event.message.starts_with(prefix))
+        .filter_map(|event| event.numbers.get(field).copied())
+        .collect()
+}
+
+#[test]
+pub fn test_tracing_retries() {
+    capture();
+    let lifo = AtomicLifo::new();
+    lifo.push(0);
+
+    //Every push in the predicate changes the head, so the compare and swap of pop_if fails.
+    let mut calls = 0;
+    let popped = lifo.pop_if(|_| {
+        calls += 1;
+        if calls <= 40 {
+            lifo.push(calls);
+        }
+        true
+    });
+    assert_eq!(popped, Some(40));
+
+    let events = EVENTS.lock().unwrap();
+    let event = events
+        .iter()
+        .find(|event| event.strings.get("operation").is_some_and(|op| op == "pop_if"))
+        .expect("no retry event");
+    assert_eq!(event.numbers["retries"], 40);
+    assert_eq!(event.message, "compare and swap retried 40 times");
+}
+
+#[test]
+pub fn test_tracing_hazard() {
+    const ITERATIONS: u64 = 2000;
+
+    capture();
+    let lifo = AtomicLifo::with_hazard_limit(100);
+    lifo.push(u64::MAX);
+    let (entered_tx, entered_rx) = mpsc::channel();
+    let (resume_tx, resume_rx) = mpsc::channel::<()>();
+
+    let lifo = &lifo;
+    thread::scope(|scope| {
+        //Keeps every node popped below from being freed until it resumes.
+        let stalled = scope.spawn(move || {
+            lifo.peek_with(|value| {
+                entered_tx.send(()).unwrap();
+                resume_rx.recv().unwrap();
+                *value
+            })
+        });
+        entered_rx.recv().unwrap();
+
+        for i in 0..ITERATIONS {
+            lifo.push(i);
+            assert!(lifo.pop().is_some());
+        }
+
+        let limits = captured("hazard limit reached", "hazard_limit");
+        assert!(limits.contains(&100), "{limits:?}");
+
+        resume_tx.send(()).unwrap();
+        assert_eq!(stalled.join().unwrap(), Some(u64::MAX));
+    });
+
+    //Advances the generations until the nodes popped while the thread was stalled are freed together.
+    for i in 0..4 {
+        lifo.push(i);
+        assert!(lifo.pop().is_some());
+    }
+
+    let freed = captured("freed", "freed");
+    assert!(freed.iter().any(|freed| *freed >= 1024), "{freed:?}");
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::sync::Arc;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+type Job = Box<dyn FnOnce() -> usize + Send>;
+
+//Counts how often it was dropped, whether the closure owning it was called or not.
+struct DropCounter(Arc<AtomicUsize>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, SeqCst);
+    }
+}
+
+//Closures of different sizes, each owning a drop counter.
+fn jobs(dropped: &Arc<AtomicUsize>) -> Vec<Job> {
+    let small = DropCounter(dropped.clone());
+    let large = (DropCounter(dropped.clone()), [2usize; 64]);
+    let string = (DropCounter(dropped.clone()), String::from("four"));
+    vec![
+        Box::new(move || {
+            drop(small);
+            1
+        }),
+        Box::new(move || {
+            let (_counter, values) = large;
+            values.iter().sum::<usize>() / 64
+        }),
+        Box::new(move || {
+            let (_counter, string) = string;
+            string.len()
+        }),
+    ]
+}
+
+#[test]
+pub fn test_trait_objects() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let lifo = AtomicLifo::<Job>::new();
+    lifo.extend(jobs(&dropped));
+
+    assert_eq!(lifo.pop().unwrap()(), 4);
+    assert_eq!(lifo.pop().unwrap()(), 2);
+    assert_eq!(lifo.pop().unwrap()(), 1);
+    assert!(lifo.pop().is_none());
+    assert_eq!(dropped.load(SeqCst), 3);
+}
+
+#[test]
+pub fn test_trait_objects_dropped_with_lifo() {
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let lifo = AtomicLifo::<Job>::new();
+    lifo.extend(jobs(&dropped));
+    lifo.extend(jobs(&dropped));
+    assert_eq!(lifo.pop().unwrap()(), 4);
+    assert_eq!(dropped.load(SeqCst), 1);
+
+    //The 5 jobs that were never called are dropped through their vtables.
+    drop(lifo);
+    assert_eq!(dropped.load(SeqCst), 6);
+}
+
+#[test]
+pub fn test_trait_objects_mt() {
+    const ROUNDS: usize = if cfg!(miri) { 10 } else { 1000 };
+
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let lifo = AtomicLifo::<Job>::new();
+    let total = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    lifo.extend(jobs(&dropped));
+                    if let Some(job) = lifo.pop() {
+                        total.fetch_add(job(), SeqCst);
+                    }
+                }
+            });
+        }
+    });
+
+    let called = 2 * ROUNDS;
+    let left = lifo.into_iter().count();
+    assert_eq!(called + left, 6 * ROUNDS);
+    assert_eq!(dropped.load(SeqCst), 6 * ROUNDS);
+    assert!(total.load(SeqCst) >= called);
+}
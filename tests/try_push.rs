@@ -0,0 +1,76 @@
+use atomic_lifo::{AtomicLifo, PushError};
+
+#[test]
+pub fn test_try_push() {
+    let lifo = AtomicLifo::<String>::new();
+    assert_eq!(lifo.try_push(String::from("test1")), Ok(()));
+    assert_eq!(lifo.try_push(String::from("test2")), Ok(()));
+    assert_eq!(lifo.pop(), Some(String::from("test2")));
+    assert_eq!(lifo.pop(), Some(String::from("test1")));
+    assert_eq!(lifo.pop(), None);
+
+    let zst = AtomicLifo::<()>::new();
+    assert_eq!(zst.try_push(()), Ok(()));
+    assert_eq!(zst.pop(), Some(()));
+}
+
+#[test]
+pub fn test_push_error() {
+    let error = PushError(String::from("test"));
+    assert_eq!(format!("{error:?}"), "PushError { .. }");
+    assert_eq!(
+        error.to_string(),
+        "failed to allocate the node for the pushed value"
+    );
+    assert_eq!(error.into_inner(), "test");
+}
+
+#[cfg(feature = "allocator-api")]
+mod failing {
+    use allocator_api2::alloc::{AllocError, Allocator, Global};
+    use atomic_lifo::AtomicLifo;
+    use std::alloc::Layout;
+    use std::ptr::NonNull;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    #[derive(Default)]
+    struct FailingAllocator {
+        fail: AtomicBool,
+    }
+
+    unsafe impl Allocator for FailingAllocator {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            if self.fail.load(SeqCst) {
+                return Err(AllocError);
+            }
+
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            Global.deallocate(ptr, layout);
+        }
+    }
+
+    #[test]
+    pub fn test_try_push_alloc_failure() {
+        let allocator = FailingAllocator::default();
+        let lifo = AtomicLifo::new_in(&allocator);
+        lifo.push(String::from("test1"));
+        allocator.fail.store(true, SeqCst);
+
+        let error = lifo.try_push(String::from("test2")).unwrap_err();
+        assert_eq!(error.into_inner(), "test2");
+        assert_eq!(lifo.peek_with(String::clone).as_deref(), Some("test1"));
+
+        //Reserved nodes are used without allocating.
+        allocator.fail.store(false, SeqCst);
+        lifo.reserve(2);
+        allocator.fail.store(true, SeqCst);
+        assert_eq!(lifo.pop(), Some(String::from("test1")));
+        assert_eq!(lifo.try_push(String::from("test3")), Ok(()));
+        assert_eq!(lifo.pop(), Some(String::from("test3")));
+        allocator.fail.store(false, SeqCst);
+    }
+}
@@ -0,0 +1,14 @@
+use atomic_lifo::AtomicLifo;
+
+fn shorten<'a>(lifo: &'a AtomicLifo<&'static str>) -> &'a AtomicLifo<&'a str> {
+    lifo
+}
+
+fn main() {
+    let lifo = AtomicLifo::new();
+    {
+        let value = String::from("short lived");
+        shorten(&lifo).push(value.as_str());
+    }
+    println!("{:?}", lifo.pop());
+}
@@ -0,0 +1,6 @@
+use std::rc::Rc;
+use atomic_lifo::AtomicLifo;
+
+fn main() {
+    let _lifo: Option<AtomicLifo<Rc<u32>>> = None;
+}
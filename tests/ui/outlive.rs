@@ -0,0 +1,7 @@
+use atomic_lifo::AtomicLifo;
+
+fn main() {
+    let lifo = AtomicLifo::new();
+    let value = String::from("dropped before the lifo");
+    lifo.push(value.as_str());
+}
@@ -0,0 +1,10 @@
+use atomic_lifo::{AtomicFifo, BoundedAtomicLifo};
+
+fn main() {
+    let bounded = BoundedAtomicLifo::new(1);
+    let fifo = AtomicFifo::new();
+    let first = String::from("dropped before the lifo");
+    let second = String::from("dropped before the fifo");
+    bounded.try_push(first.as_str()).unwrap();
+    fifo.push(second.as_str());
+}
@@ -0,0 +1,8 @@
+use std::cell::Cell;
+use atomic_lifo::AtomicLifo;
+
+fn main() {
+    let lifo = AtomicLifo::new();
+    lifo.push(Cell::new(1));
+    lifo.peek_with(|value| value.get());
+}
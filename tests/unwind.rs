@@ -0,0 +1,69 @@
+use atomic_lifo::AtomicLifo;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+
+#[derive(Debug, PartialEq, Eq)]
+struct PanicClone(u32);
+
+impl Clone for PanicClone {
+    fn clone(&self) -> Self {
+        assert_ne!(self.0, 2, "clone of 2");
+        Self(self.0)
+    }
+}
+
+#[test]
+pub fn test_panicking_clone() {
+    let lifo = AtomicLifo::new();
+    lifo.extend([1, 2, 3].map(PanicClone));
+    assert!(catch_unwind(AssertUnwindSafe(|| lifo.clone())).is_err());
+
+    //Pops would wait forever for the borrow of 2 if it had not been released.
+    assert_eq!(lifo.pop(), Some(PanicClone(3)));
+    assert_eq!(lifo.pop(), Some(PanicClone(2)));
+    assert_eq!(lifo.pop(), Some(PanicClone(1)));
+    assert_eq!(lifo.pop(), None);
+
+    //Popped nodes are only reused once every thread left, including the one that panicked.
+    assert!(lifo.free_node_count() > 0);
+}
+
+#[test]
+pub fn test_panicking_closures() {
+    let lifo = AtomicLifo::new();
+    lifo.push(1u32);
+    assert!(catch_unwind(|| lifo.pop_if(|_| panic!("pred"))).is_err());
+    assert!(catch_unwind(|| lifo.peek_with(|_| panic!("peek"))).is_err());
+    assert_eq!(lifo.pop_if(|value| *value == 1), Some(1));
+
+    for i in 0..4 {
+        lifo.push(i);
+        assert_eq!(lifo.pop(), Some(i));
+    }
+    assert!(lifo.free_node_count() > 0);
+}
+
+static PANIC_ONCE: AtomicBool = AtomicBool::new(true);
+
+#[derive(Debug)]
+struct PanicDrop;
+
+impl Drop for PanicDrop {
+    fn drop(&mut self) {
+        assert!(!PANIC_ONCE.swap(false, SeqCst), "drop");
+    }
+}
+
+#[test]
+pub fn test_panicking_drop() {
+    let lifo = AtomicLifo::new();
+    lifo.push(PanicDrop);
+    lifo.push(PanicDrop);
+    assert!(catch_unwind(AssertUnwindSafe(|| lifo.clear())).is_err());
+    assert!(lifo.is_empty());
+
+    lifo.push(PanicDrop);
+    assert!(lifo.pop().is_some());
+    assert!(lifo.is_empty());
+}
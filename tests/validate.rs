@@ -0,0 +1,79 @@
+#![cfg(feature = "debug-validate")]
+#[cfg(feature = "epoch")]
+use atomic_lifo::EpochReclaim;
+use atomic_lifo::{AtomicLifo, HazardReclaim, LeakReclaim, Reclaim};
+use std::sync::Arc;
+use std::thread;
+
+const THREADS: u64 = 4;
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: u64 = if cfg!(miri) { 100 } else { 10_000 };
+
+fn validate<R: Reclaim>(reclaim: R) {
+    let mut lifo = AtomicLifo::with_reclaim(reclaim);
+    lifo.assert_invariants();
+    lifo.extend(0..100u64);
+    lifo.assert_invariants();
+
+    //Keeps the nodes of the popped elements waiting to be freed.
+    let pinned = lifo.pin();
+    assert_eq!(lifo.pop_n(10).len(), 10);
+    drop(pinned);
+    lifo.assert_invariants();
+
+    lifo.reserve(10);
+    lifo.retain(|i| i % 2 == 0);
+    lifo.assert_invariants();
+    lifo.clear();
+    lifo.assert_invariants();
+}
+
+#[test]
+pub fn test_validate() {
+    validate(HazardReclaim);
+    validate(LeakReclaim);
+    #[cfg(feature = "epoch")]
+    validate(EpochReclaim);
+}
+
+fn validate_threads<R: Reclaim>(reclaim: R) {
+    let mut lifo = Arc::new(AtomicLifo::with_reclaim(reclaim));
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let lifo = Arc::clone(&lifo);
+            thread::spawn(move || {
+                for i in 0..ITERATIONS {
+                    lifo.push(t * ITERATIONS + i);
+                    if i % 3 != 0 {
+                        lifo.pop().unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let lifo = Arc::get_mut(&mut lifo).unwrap();
+    lifo.assert_invariants();
+    assert_eq!(lifo.drain().count() as u64, THREADS * ITERATIONS.div_ceil(3));
+    lifo.assert_invariants();
+}
+
+#[test]
+pub fn test_validate_threads() {
+    validate_threads(HazardReclaim);
+    validate_threads(LeakReclaim);
+    #[cfg(feature = "epoch")]
+    validate_threads(EpochReclaim);
+}
+
+#[test]
+pub fn test_validate_zst() {
+    let mut lifo = AtomicLifo::new();
+    lifo.extend([(); 10]);
+    lifo.pop().unwrap();
+    lifo.assert_invariants();
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use atomic_lifo::AtomicLifo;
+
+#[test]
+pub fn test_visit() {
+    let lifo = AtomicLifo::<String>::new();
+    lifo.visit(|_| panic!("empty lifo visited an element"));
+
+    lifo.extend(["a", "b", "c"].map(String::from));
+    let mut visited = Vec::new();
+    lifo.visit(|element| visited.push(element.clone()));
+    assert_eq!(visited, ["c", "b", "a"]);
+    assert_eq!(lifo.pop().unwrap(), "c");
+
+    let mut total = 0;
+    lifo.visit(|element| total += element.len());
+    assert_eq!(total, 2);
+}
+
+#[test]
+pub fn test_visit_zst() {
+    let lifo = AtomicLifo::new();
+    lifo.extend([(); 5]);
+    let mut count = 0;
+    lifo.visit(|()| count += 1);
+    assert_eq!(count, 5);
+}
+
+//Run under Miri or with -Zsanitizer=address to check that no freed value is visited.
+#[test]
+pub fn test_visit_while_draining() {
+    const ROUNDS: u64 = if cfg!(miri) { 10 } else { 1000 };
+    const ELEMENTS: u64 = 64;
+
+    let lifo = AtomicLifo::new();
+    let stop = AtomicBool::new(false);
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for round in 0..ROUNDS {
+                lifo.extend((0..ELEMENTS).map(|i| Box::new(round * ELEMENTS + i)));
+                while lifo.pop().is_some() {}
+            }
+            stop.store(true, SeqCst);
+        });
+
+        while !stop.load(SeqCst) {
+            let mut seen = HashSet::new();
+            lifo.visit(|element| assert!(seen.insert(**element), "{element} visited twice"));
+            assert!(seen.len() <= ELEMENTS as usize);
+        }
+    });
+
+    assert!(lifo.is_empty());
+}
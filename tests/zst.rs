@@ -0,0 +1,132 @@
+use atomic_lifo::AtomicLifo;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+
+//Miri interprets every operation and is orders of magnitude slower.
+const ITERATIONS: usize = if cfg!(miri) { 100 } else { 10_000 };
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+struct Permit;
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, SeqCst);
+    }
+}
+
+#[test]
+pub fn test_zst_no_allocations() {
+    let lifo = AtomicLifo::<()>::new();
+    let before = allocations();
+    for _ in 0..1000 {
+        lifo.push(());
+    }
+    assert!(!lifo.is_empty());
+    assert_eq!(lifo.peek_with(|_| 1), Some(1));
+    for _ in 0..1000 {
+        assert_eq!(lifo.pop(), Some(()));
+    }
+    assert_eq!(lifo.pop(), None);
+    assert!(lifo.is_empty());
+    assert_eq!(allocations(), before);
+}
+
+#[test]
+pub fn test_zst_drop() {
+    let lifo = AtomicLifo::new();
+    for _ in 0..10 {
+        lifo.push(Permit);
+    }
+
+    drop(lifo.pop().unwrap());
+    assert_eq!(DROPPED.load(SeqCst), 1);
+    let taken = lifo.pop_n(3);
+    assert_eq!(taken.len(), 3);
+    drop(taken);
+    assert_eq!(DROPPED.load(SeqCst), 4);
+
+    let mut all = lifo.take_all();
+    drop(all.next().unwrap());
+    assert_eq!(DROPPED.load(SeqCst), 5);
+    drop(all);
+    assert_eq!(DROPPED.load(SeqCst), 10);
+
+    lifo.extend([Permit, Permit]);
+    assert_eq!(lifo.into_iter().count(), 2);
+    assert_eq!(DROPPED.load(SeqCst), 12);
+
+    let lifo = AtomicLifo::new();
+    lifo.push(Permit);
+    lifo.push(Permit);
+    drop(lifo);
+    assert_eq!(DROPPED.load(SeqCst), 14);
+}
+
+#[test]
+pub fn test_zst_debug() {
+    let lifo = AtomicLifo::<()>::new();
+    lifo.push(());
+    lifo.push(());
+    assert_eq!(
+        format!("{lifo:?}"),
+        "AtomicLifo { len: ~2, items: [(), ()] }"
+    );
+    assert_eq!(
+        format!("{:?}", lifo.clone()),
+        "AtomicLifo { len: ~2, items: [(), ()] }"
+    );
+    assert!(lifo.contains(&()));
+    assert_eq!(lifo.pop_if(|_| false), None);
+    assert_eq!(lifo.pop_if(|_| true), Some(()));
+    assert_eq!(lifo.pop_bottom(), Some(()));
+    assert!(lifo.is_empty());
+}
+
+#[test]
+pub fn test_zst_mt() {
+    let lifo = AtomicLifo::<()>::new();
+    let popped = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    lifo.push(());
+                    if lifo.pop().is_some() {
+                        popped.fetch_add(1, SeqCst);
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(popped.load(SeqCst) + lifo.take_all().count(), 4 * ITERATIONS);
+}